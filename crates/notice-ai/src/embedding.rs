@@ -0,0 +1,149 @@
+use crate::GeminiClient;
+
+/// Vector size stored in `document_chunks`; Gemini's
+/// `text-embedding-004` and the hash embedder both produce this.
+pub const EMBEDDING_DIMENSIONS: usize = 768;
+
+/// Characters of a passage that go into its embedding.
+const MAX_PASSAGE_CHARS: usize = 6000;
+
+/// What a text is, so the model can embed queries and documents into
+/// comparable but not identical spaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbedKind {
+    Document,
+    Query,
+}
+
+/// Turns text into vectors. Gemini in production; the hash embedder is
+/// deterministic and offline, for tests and local development.
+#[derive(Clone)]
+pub enum Embedder {
+    Gemini(GeminiClient),
+    Hash(HashEmbedder),
+}
+
+impl Embedder {
+    /// Stored next to each vector so switching embedders never compares
+    /// vectors from different models.
+    pub fn model(&self) -> &'static str {
+        match self {
+            Embedder::Gemini(_) => crate::EMBEDDING_MODEL,
+            Embedder::Hash(_) => "hash",
+        }
+    }
+
+    /// One vector per text, in order.
+    pub async fn embed(
+        &self,
+        texts: &[String],
+        kind: EmbedKind,
+    ) -> Result<Vec<Vec<f32>>, notice_core::Error> {
+        match self {
+            Embedder::Gemini(client) => client.embed(texts, kind).await,
+            Embedder::Hash(hash) => Ok(texts.iter().map(|t| hash.embed(t)).collect()),
+        }
+    }
+
+    pub async fn embed_one(
+        &self,
+        text: &str,
+        kind: EmbedKind,
+    ) -> Result<Vec<f32>, notice_core::Error> {
+        self.embed(&[text.to_string()], kind)
+            .await?
+            .pop()
+            .ok_or_else(|| notice_core::Error::Ai("Embedder returned no vector".into()))
+    }
+}
+
+/// The text a passage is embedded from: the document title and section
+/// heading give it context, then the passage itself.
+pub fn passage_input(title: Option<&str>, heading: Option<&str>, content: &str) -> String {
+    let content = notice_core::truncate_utf8(content, MAX_PASSAGE_CHARS);
+    let context: Vec<&str> = [title, heading]
+        .into_iter()
+        .flatten()
+        .filter(|s| !s.is_empty())
+        .collect();
+    if context.is_empty() {
+        content.to_string()
+    } else {
+        format!("{}\n\n{}", context.join(" — "), content)
+    }
+}
+
+/// Feature-hashing embedder: words and their character trigrams are hashed
+/// into signed buckets, then the vector is L2-normalized. Texts sharing
+/// words (or word fragments) end up close; no model or network needed.
+#[derive(Debug, Clone)]
+pub struct HashEmbedder {
+    dimensions: usize,
+}
+
+impl HashEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions: dimensions.max(1),
+        }
+    }
+
+    pub fn embed(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimensions];
+
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            let word = word.to_lowercase();
+            self.add(&mut vector, &word, 1.0);
+
+            let padded: Vec<char> = format!("#{}#", word).chars().collect();
+            for trigram in padded.windows(3) {
+                self.add(&mut vector, &trigram.iter().collect::<String>(), 0.5);
+            }
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+        vector
+    }
+
+    fn add(&self, vector: &mut [f32], feature: &str, weight: f32) {
+        let hash = fnv1a(feature.as_bytes());
+        let bucket = (hash % self.dimensions as u64) as usize;
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[bucket] += sign * weight;
+    }
+}
+
+/// Stable across platforms and releases, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[test]
+    fn hash_embeddings_are_deterministic_and_similar_for_related_text() {
+        let embedder = HashEmbedder::new(EMBEDDING_DIMENSIONS);
+        let query = embedder.embed("async runtime for rust");
+        assert_eq!(query, embedder.embed("async runtime for rust"));
+        assert_eq!(query.len(), EMBEDDING_DIMENSIONS);
+
+        let related = embedder.embed("Tokio is an asynchronous runtime for Rust");
+        let unrelated = embedder.embed("banana bread recipe with walnuts");
+        assert!(cosine(&query, &related) > cosine(&query, &unrelated));
+        assert!((cosine(&query, &query) - 1.0).abs() < 1e-5);
+    }
+}
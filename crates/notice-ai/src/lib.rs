@@ -1,7 +1,14 @@
+pub mod embedding;
+
+use embedding::EmbedKind;
 use serde::{Deserialize, Serialize};
 
 const GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
 const DEFAULT_MODEL: &str = "gemini-2.5-flash";
+pub const EMBEDDING_MODEL: &str = "text-embedding-004";
+
+/// Most texts `batchEmbedContents` accepts per call.
+const MAX_EMBED_BATCH: usize = 100;
 
 /// Client for the Gemini API.
 #[derive(Clone)]
@@ -56,6 +63,29 @@ struct CandidatePart {
     text: String,
 }
 
+#[derive(Serialize)]
+struct BatchEmbedRequest {
+    requests: Vec<EmbedRequest>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EmbedRequest {
+    model: String,
+    content: Content,
+    task_type: &'static str,
+}
+
+#[derive(Deserialize)]
+struct BatchEmbedResponse {
+    embeddings: Vec<EmbeddingValues>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingValues {
+    values: Vec<f32>,
+}
+
 // ─── Top-level error response (different shape) ───
 
 #[derive(Deserialize)]
@@ -72,6 +102,16 @@ struct GeminiErrorDetail {
     status: Option<String>,
 }
 
+// ─── Public result types ───
+
+/// LLM intent classification, see [`GeminiClient::classify_intent`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentGuess {
+    pub intent: String,
+    #[serde(default)]
+    pub argument: Option<String>,
+}
+
 // ─── Implementation ───
 
 impl GeminiClient {
@@ -175,6 +215,74 @@ impl GeminiClient {
         Ok(text)
     }
 
+    /// Embed texts with `text-embedding-004`, batching as needed.
+    pub async fn embed(
+        &self,
+        texts: &[String],
+        kind: EmbedKind,
+    ) -> Result<Vec<Vec<f32>>, notice_core::Error> {
+        let url = format!(
+            "{}/{}:batchEmbedContents?key={}",
+            GEMINI_BASE_URL, EMBEDDING_MODEL, self.api_key
+        );
+        let task_type = match kind {
+            EmbedKind::Document => "RETRIEVAL_DOCUMENT",
+            EmbedKind::Query => "RETRIEVAL_QUERY",
+        };
+
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(MAX_EMBED_BATCH) {
+            let body = BatchEmbedRequest {
+                requests: batch
+                    .iter()
+                    .map(|text| EmbedRequest {
+                        model: format!("models/{}", EMBEDDING_MODEL),
+                        content: Content {
+                            parts: vec![Part { text: text.clone() }],
+                        },
+                        task_type,
+                    })
+                    .collect(),
+            };
+
+            let response = self.http.post(&url).json(&body).send().await.map_err(|e| {
+                notice_core::Error::Ai(format!("Failed to reach Gemini API: {}", e))
+            })?;
+
+            let status = response.status();
+            let body_text = response.text().await.map_err(|e| {
+                notice_core::Error::Ai(format!("Failed to read Gemini response: {}", e))
+            })?;
+
+            if !status.is_success() {
+                let error_msg =
+                    if let Ok(err) = serde_json::from_str::<GeminiErrorResponse>(&body_text) {
+                        format!(
+                            "Gemini embedding error (HTTP {}): {}",
+                            status, err.error.message
+                        )
+                    } else {
+                        format!("Gemini embedding error (HTTP {}): {}", status, body_text)
+                    };
+                return Err(notice_core::Error::Ai(error_msg));
+            }
+
+            let parsed: BatchEmbedResponse = serde_json::from_str(&body_text).map_err(|e| {
+                notice_core::Error::Ai(format!("Failed to parse Gemini embeddings: {}", e))
+            })?;
+            if parsed.embeddings.len() != batch.len() {
+                return Err(notice_core::Error::Ai(format!(
+                    "Gemini returned {} embeddings for {} texts",
+                    parsed.embeddings.len(),
+                    batch.len()
+                )));
+            }
+            vectors.extend(parsed.embeddings.into_iter().map(|e| e.values));
+        }
+
+        Ok(vectors)
+    }
+
     /// Summarize web content.
     pub async fn summarize(&self, content: &str) -> Result<String, notice_core::Error> {
         let prompt = format!(
@@ -185,15 +293,64 @@ impl GeminiClient {
         self.generate(&prompt).await
     }
 
-    /// Classify a query's intent (fallback when rules don't match).
-    pub async fn classify_intent(&self, query: &str) -> Result<String, notice_core::Error> {
+    /// Classify a query's intent (fallback when rules aren't confident).
+    /// The argument is a normalized form the rule parsers can re-check,
+    /// e.g. "5 * 3" for "what is five times three".
+    pub async fn classify_intent(&self, query: &str) -> Result<IntentGuess, notice_core::Error> {
         let prompt = format!(
             "Classify the following user search query into exactly one category.\n\
              Categories: search, calculate, define, timer, convert\n\
-             Respond with ONLY the category name, nothing else.\n\n\
+             Also give the argument in a normalized form:\n\
+             - calculate: a plain math expression, e.g. \"5 * 3\"\n\
+             - convert: \"<amount> <unit> to <unit>\", e.g. \"3 cup to ml\"\n\
+             - define: the single word or short phrase to define\n\
+             - timer: a duration or clock time, e.g. \"10 min\" or \"to 17:00\"\n\
+             - search: null\n\
+             Respond with ONLY JSON: {{\"intent\": \"...\", \"argument\": \"...\"}}\n\n\
              Query: \"{}\"",
             query
         );
+        let raw = self.generate(&prompt).await?;
+
+        // Tolerate code fences or stray prose around the JSON object
+        let json = match (raw.find('{'), raw.rfind('}')) {
+            (Some(start), Some(end)) if start < end => &raw[start..=end],
+            _ => raw.trim(),
+        };
+
+        serde_json::from_str(json).map_err(|e| {
+            notice_core::Error::Ai(format!(
+                "Unparseable intent classification '{}': {}",
+                raw, e
+            ))
+        })
+    }
+
+    /// Define a term using only the given document snippets (dictionary fallback).
+    /// Returns raw JSON text: {"part_of_speech", "definition", "synonyms"}.
+    pub async fn define(
+        &self,
+        term: &str,
+        contexts: &[String],
+    ) -> Result<String, notice_core::Error> {
+        let combined_context = contexts
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("Source [{}]:\n{}\n", i + 1, c))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let prompt = format!(
+            "Write a dictionary-style definition of the term '{term}' based ONLY on the \
+             sources below. If the sources do not explain the term, respond with an empty \
+             definition.\n\n\
+             SOURCES:\n{ctx}\n\n\
+             RETURN JSON:\n\
+             {{\"part_of_speech\": \"noun\", \"definition\": \"One sentence\", \"synonyms\": []}}",
+            term = term,
+            ctx = combined_context,
+        );
+
         self.generate(&prompt).await
     }
 
@@ -0,0 +1,54 @@
+use thiserror::Error;
+
+/// Everything that can go wrong while tokenizing, parsing or evaluating
+/// an expression. Positions are byte offsets into the original input.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum CalcError {
+    #[error("Empty expression")]
+    Empty,
+
+    #[error("Unexpected character '{ch}' at position {position}")]
+    UnexpectedChar { ch: char, position: usize },
+
+    #[error("Unexpected '{found}' at position {position}")]
+    UnexpectedToken { found: String, position: usize },
+
+    #[error("Unexpected end of expression")]
+    UnexpectedEnd,
+
+    #[error("Unknown function or constant '{name}' at position {position}")]
+    UnknownIdentifier { name: String, position: usize },
+
+    #[error("{function}() takes {expected} argument(s), got {found}")]
+    WrongArity {
+        function: String,
+        expected: String,
+        found: usize,
+    },
+
+    #[error("Division by zero")]
+    DivisionByZero,
+
+    #[error("{0} is undefined for this input")]
+    Domain(String),
+
+    /// `position` is set when a literal itself is too large (`1e400`).
+    #[error("Number is too large to represent")]
+    Overflow { position: Option<usize> },
+
+    #[error("Expression is too long or too deeply nested")]
+    TooComplex,
+}
+
+impl CalcError {
+    /// Byte offset of the offending input, when the error is tied to one.
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            CalcError::UnexpectedChar { position, .. }
+            | CalcError::UnexpectedToken { position, .. }
+            | CalcError::UnknownIdentifier { position, .. } => Some(*position),
+            CalcError::Overflow { position } => *position,
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::error::CalcError;
+use crate::parser::{BinaryOp, Expr};
+use crate::value::Value;
+
+/// Named constants usable in expressions.
+pub fn constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" | "π" => Some(std::f64::consts::PI),
+        "tau" | "τ" => Some(std::f64::consts::TAU),
+        "e" => Some(std::f64::consts::E),
+        "phi" | "φ" => Some(1.618_033_988_749_895),
+        _ => None,
+    }
+}
+
+/// Functions usable in expressions. Trigonometry works in radians.
+const FUNCTIONS: &[&str] = &[
+    "sqrt", "cbrt", "abs", "floor", "ceil", "round", "exp", "ln", "log", "log2", "log10", "sin",
+    "cos", "tan", "asin", "acos", "atan", "sinh", "cosh", "tanh", "min", "max",
+];
+
+pub fn is_function(name: &str) -> bool {
+    FUNCTIONS.contains(&name)
+}
+
+/// Evaluate an expression tree.
+pub fn evaluate(expr: &Expr) -> Result<Value, CalcError> {
+    match expr {
+        Expr::Number(n) => Ok(*n),
+        Expr::Constant(name) => {
+            constant(name)
+                .map(Value::Float)
+                .ok_or_else(|| CalcError::UnknownIdentifier {
+                    name: name.clone(),
+                    position: 0,
+                })
+        }
+        Expr::Neg(inner) => evaluate(inner)?.try_neg(),
+        Expr::Binary { op, lhs, rhs } => {
+            let a = evaluate(lhs)?;
+            let b = evaluate(rhs)?;
+            match op {
+                BinaryOp::Add => a.try_add(b),
+                BinaryOp::Sub => a.try_sub(b),
+                BinaryOp::Mul => a.try_mul(b),
+                BinaryOp::Div => a.try_div(b),
+                BinaryOp::Rem => a.try_rem(b),
+                BinaryOp::Pow => a.try_pow(b),
+            }
+        }
+        Expr::Call { name, args } => {
+            let values = args.iter().map(evaluate).collect::<Result<Vec<_>, _>>()?;
+            call(name, &values)
+        }
+    }
+}
+
+fn call(name: &str, args: &[Value]) -> Result<Value, CalcError> {
+    match name {
+        "min" | "max" => {
+            let first = *args.first().ok_or_else(|| arity(name, "at least 1", 0))?;
+            args[1..].iter().try_fold(first, |best, &v| {
+                let pick_v = if name == "min" {
+                    v.as_f64() < best.as_f64()
+                } else {
+                    v.as_f64() > best.as_f64()
+                };
+                Ok(if pick_v { v } else { best })
+            })
+        }
+        "log" => match args {
+            [x] => log(*x, 10.0, name),
+            [x, base] => log(*x, base.as_f64(), name),
+            _ => Err(arity(name, "1 or 2", args.len())),
+        },
+        _ => {
+            let [x] = args else {
+                return Err(arity(name, "1", args.len()));
+            };
+            unary(name, *x)
+        }
+    }
+}
+
+fn unary(name: &str, x: Value) -> Result<Value, CalcError> {
+    let f = x.as_f64();
+    match name {
+        "abs" => match x {
+            Value::Int(n) => Ok(n
+                .checked_abs()
+                .map(Value::Int)
+                .unwrap_or(Value::Float(f.abs()))),
+            Value::Float(_) => Ok(Value::Float(f.abs())),
+        },
+        "floor" => Ok(integral(f.floor(), x)),
+        "ceil" => Ok(integral(f.ceil(), x)),
+        "round" => Ok(integral(f.round(), x)),
+        "sqrt" => {
+            if f < 0.0 {
+                return Err(CalcError::Domain(name.to_string()));
+            }
+            let root = f.sqrt();
+            // Keep perfect squares exact: sqrt(144) = 12, not 12.0
+            if let Value::Int(n) = x {
+                let r = root.round() as i128;
+                if r.checked_mul(r) == Some(n) {
+                    return Ok(Value::Int(r));
+                }
+            }
+            Value::float(root, name)
+        }
+        "cbrt" => Value::float(f.cbrt(), name),
+        "exp" => Value::float(f.exp(), name),
+        "ln" => log(x, std::f64::consts::E, name),
+        "log2" => log(x, 2.0, name),
+        "log10" => log(x, 10.0, name),
+        "sin" => Value::float(f.sin(), name),
+        "cos" => Value::float(f.cos(), name),
+        "tan" => Value::float(f.tan(), name),
+        "asin" => Value::float(f.asin(), name),
+        "acos" => Value::float(f.acos(), name),
+        "atan" => Value::float(f.atan(), name),
+        "sinh" => Value::float(f.sinh(), name),
+        "cosh" => Value::float(f.cosh(), name),
+        "tanh" => Value::float(f.tanh(), name),
+        _ => Err(CalcError::UnknownIdentifier {
+            name: name.to_string(),
+            position: 0,
+        }),
+    }
+}
+
+fn log(x: Value, base: f64, name: &str) -> Result<Value, CalcError> {
+    let f = x.as_f64();
+    if f <= 0.0 || base <= 0.0 || base == 1.0 {
+        return Err(CalcError::Domain(name.to_string()));
+    }
+    Value::float(f.log(base), name)
+}
+
+/// Result of floor/ceil/round: an exact integer whenever it fits.
+fn integral(rounded: f64, original: Value) -> Value {
+    match original {
+        Value::Int(_) => original,
+        Value::Float(_) if rounded.abs() < i128::MAX as f64 => Value::Int(rounded as i128),
+        Value::Float(_) => Value::Float(rounded),
+    }
+}
+
+fn arity(function: &str, expected: &str, found: usize) -> CalcError {
+    CalcError::WrongArity {
+        function: function.to_string(),
+        expected: expected.to_string(),
+        found,
+    }
+}
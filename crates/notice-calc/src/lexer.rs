@@ -0,0 +1,174 @@
+use crate::error::CalcError;
+use crate::value::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Number(Value),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    LParen,
+    RParen,
+    Comma,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// Byte offset of the first character of the token.
+    pub position: usize,
+}
+
+impl TokenKind {
+    /// Human-readable form used in error messages.
+    pub fn describe(&self) -> String {
+        match self {
+            TokenKind::Number(n) => n.to_string(),
+            TokenKind::Ident(name) => name.clone(),
+            TokenKind::Plus => "+".into(),
+            TokenKind::Minus => "-".into(),
+            TokenKind::Star => "*".into(),
+            TokenKind::Slash => "/".into(),
+            TokenKind::Percent => "%".into(),
+            TokenKind::Caret => "^".into(),
+            TokenKind::LParen => "(".into(),
+            TokenKind::RParen => ")".into(),
+            TokenKind::Comma => ",".into(),
+        }
+    }
+}
+
+/// Split an expression into tokens.
+/// Accepts the usual ASCII operators plus `×`, `÷`, `−` and `**` (as `^`).
+pub fn tokenize(input: &str) -> Result<Vec<Token>, CalcError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(position, ch)) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        if ch.is_ascii_digit() || ch == '.' {
+            let number = read_number(input, position)?;
+            // Advance past everything read_number consumed
+            while chars.peek().is_some_and(|&(i, _)| i < position + number.1) {
+                chars.next();
+            }
+            tokens.push(Token {
+                kind: TokenKind::Number(number.0),
+                position,
+            });
+            continue;
+        }
+
+        if ch.is_alphabetic() || ch == '_' {
+            let mut name = String::new();
+            while let Some(&(_, c)) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    name.push(c.to_ascii_lowercase());
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token {
+                kind: TokenKind::Ident(name),
+                position,
+            });
+            continue;
+        }
+
+        chars.next();
+        let kind = match ch {
+            '+' => TokenKind::Plus,
+            '-' | '−' => TokenKind::Minus,
+            '*' | '×' => {
+                if chars.peek().is_some_and(|&(_, c)| c == '*') {
+                    chars.next();
+                    TokenKind::Caret
+                } else {
+                    TokenKind::Star
+                }
+            }
+            '/' | '÷' => TokenKind::Slash,
+            '%' => TokenKind::Percent,
+            '^' => TokenKind::Caret,
+            '(' => TokenKind::LParen,
+            ')' => TokenKind::RParen,
+            ',' => TokenKind::Comma,
+            other => {
+                return Err(CalcError::UnexpectedChar {
+                    ch: other,
+                    position,
+                });
+            }
+        };
+        tokens.push(Token { kind, position });
+    }
+
+    Ok(tokens)
+}
+
+/// Read a decimal literal (with optional exponent) starting at `start`.
+/// Integer literals stay exact; anything with a dot or exponent is a float.
+/// Returns the value and the number of bytes consumed.
+fn read_number(input: &str, start: usize) -> Result<(Value, usize), CalcError> {
+    let bytes = input.as_bytes();
+    let mut end = start;
+    let mut seen_dot = false;
+    let mut seen_exp = false;
+
+    while end < bytes.len() {
+        match bytes[end] {
+            b'0'..=b'9' => end += 1,
+            b'.' if !seen_dot => {
+                seen_dot = true;
+                end += 1;
+            }
+            _ => break,
+        }
+    }
+
+    // Scientific notation: only when the `e` is followed by digits, so that
+    // `2e` still reads as 2 × e.
+    if end < bytes.len() && (bytes[end] == b'e' || bytes[end] == b'E') {
+        let mut exp_end = end + 1;
+        if exp_end < bytes.len() && (bytes[exp_end] == b'+' || bytes[exp_end] == b'-') {
+            exp_end += 1;
+        }
+        if exp_end < bytes.len() && bytes[exp_end].is_ascii_digit() {
+            while exp_end < bytes.len() && bytes[exp_end].is_ascii_digit() {
+                exp_end += 1;
+            }
+            end = exp_end;
+            seen_exp = true;
+        }
+    }
+
+    let literal = &input[start..end];
+    if !seen_dot
+        && !seen_exp
+        && let Ok(n) = literal.parse::<i128>()
+    {
+        return Ok((Value::Int(n), end - start));
+    }
+    let n = literal
+        .parse::<f64>()
+        .map_err(|_| CalcError::UnexpectedToken {
+            found: literal.to_string(),
+            position: start,
+        })?;
+    // "1e400" parses as infinity
+    if !n.is_finite() {
+        return Err(CalcError::Overflow {
+            position: Some(start),
+        });
+    }
+    Ok((Value::Float(n), end - start))
+}
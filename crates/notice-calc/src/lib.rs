@@ -0,0 +1,177 @@
+//! Expression engine behind the Calculate intent.
+//!
+//! `evaluate("2 * (3 + 4)^2")` runs the input through a tokenizer and a
+//! precedence-climbing parser, then evaluates the tree with exact integer
+//! arithmetic where possible and f64 everywhere else.
+
+pub mod error;
+pub mod eval;
+pub mod lexer;
+pub mod parser;
+pub mod units;
+pub mod value;
+
+pub use error::CalcError;
+pub use value::Value;
+
+use serde::Serialize;
+
+/// A successfully evaluated expression, ready to render.
+#[derive(Debug, Clone, Serialize)]
+pub struct Calculation {
+    /// The expression as the user typed it (trimmed).
+    pub expression: String,
+    /// Numeric result. May be rounded for very large integers — use
+    /// `formatted` for display.
+    pub result: f64,
+    /// Display form of the result (exact digits for integers,
+    /// scientific notation for huge or tiny floats).
+    pub formatted: String,
+    /// True when the result is an exact integer.
+    pub exact: bool,
+}
+
+/// Evaluate a math expression such as `sqrt(144) + 2^10 % 7`.
+pub fn evaluate(input: &str) -> Result<Calculation, CalcError> {
+    let expression = input.trim();
+    let tokens = lexer::tokenize(expression)?;
+    let tree = parser::parse(&tokens)?;
+    let value = eval::evaluate(&tree)?;
+
+    Ok(Calculation {
+        expression: expression.to_string(),
+        result: value.as_f64(),
+        formatted: value.to_string(),
+        exact: value.is_exact(),
+    })
+}
+
+/// Cheap check used by the classifier: does this query read as arithmetic?
+///
+/// True when the input tokenizes cleanly, contains at least one number and
+/// only uses identifiers we know (functions and constants). "sqrt(144)" and
+/// "2pi" qualify; "pi" alone or "2024 election" do not.
+pub fn looks_like_expression(input: &str) -> bool {
+    let Ok(tokens) = lexer::tokenize(input) else {
+        return false;
+    };
+
+    let has_number = tokens
+        .iter()
+        .any(|t| matches!(t.kind, lexer::TokenKind::Number(_)));
+
+    let known_idents = tokens.iter().all(|t| match &t.kind {
+        lexer::TokenKind::Ident(name) => eval::is_function(name) || eval::constant(name).is_some(),
+        _ => true,
+    });
+
+    has_number && known_idents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calc(expr: &str) -> String {
+        evaluate(expr).unwrap().formatted
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(calc("150 * 6 + 7"), "907");
+        assert_eq!(calc("2 + 3 * 4 - 6 / 2"), "11");
+        assert_eq!(calc("10 - 4 - 3"), "3");
+        assert_eq!(calc("(2 + 3) * 4"), "20");
+    }
+
+    #[test]
+    fn powers_and_unary_minus() {
+        assert_eq!(calc("2^10"), "1024");
+        assert_eq!(calc("2^3^2"), "512");
+        assert_eq!(calc("-2^2"), "-4");
+        assert_eq!(calc("(-2)^2"), "4");
+        assert_eq!(calc("2 * -3"), "-6");
+        assert_eq!(calc("2^-1"), "0.5");
+        assert_eq!(calc("3 ** 2"), "9");
+    }
+
+    #[test]
+    fn modulo() {
+        assert_eq!(calc("17 % 5"), "2");
+        assert_eq!(calc("-7 % 3"), "2");
+    }
+
+    #[test]
+    fn functions_and_constants() {
+        assert_eq!(calc("sqrt(144)"), "12");
+        assert_eq!(calc("sqrt 16 * 2"), "8");
+        assert_eq!(calc("abs(-5)"), "5");
+        assert_eq!(calc("log(1000)"), "3");
+        assert_eq!(calc("log(8, 2)"), "3");
+        assert_eq!(calc("max(3, 9, 4)"), "9");
+        assert_eq!(calc("round(2.6)"), "3");
+        assert_eq!(calc("sin(0)"), "0");
+        assert_eq!(calc("2pi"), "6.2831853072");
+        assert_eq!(calc("cos(pi)"), "-1");
+    }
+
+    #[test]
+    fn decimals() {
+        assert_eq!(calc("0.1 + 0.2"), "0.3");
+        assert_eq!(calc("1 / 3"), "0.3333333333");
+        assert_eq!(calc("7 / 2"), "3.5");
+        assert_eq!(calc("1.5e3"), "1500");
+    }
+
+    #[test]
+    fn big_numbers_stay_exact() {
+        assert_eq!(calc("2^100"), "1267650600228229401496703205376");
+        assert!(evaluate("2^100").unwrap().exact);
+        assert_eq!(calc("2^200"), "1.606938044259e60");
+        assert_eq!(calc("10^-12"), "1e-12");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(evaluate("1 / 0").unwrap_err(), CalcError::DivisionByZero);
+        assert_eq!(evaluate("").unwrap_err(), CalcError::Empty);
+        assert_eq!(evaluate("(1 + 2").unwrap_err(), CalcError::UnexpectedEnd);
+        assert!(matches!(
+            evaluate("sqrt(-1)").unwrap_err(),
+            CalcError::Domain(_)
+        ));
+        assert_eq!(
+            evaluate("10^400").unwrap_err(),
+            CalcError::Overflow { position: None }
+        );
+        assert_eq!(
+            evaluate("2 * 1e400").unwrap_err(),
+            CalcError::Overflow { position: Some(4) }
+        );
+
+        // Deep nesting and long chains are rejected, not recursed into
+        let nested = format!("{}1", "(".repeat(10_000));
+        assert_eq!(evaluate(&nested).unwrap_err(), CalcError::TooComplex);
+        let signs = format!("{}1", "-".repeat(10_000));
+        assert_eq!(evaluate(&signs).unwrap_err(), CalcError::TooComplex);
+        let chain = format!("1{}", "+1".repeat(10_000));
+        assert_eq!(evaluate(&chain).unwrap_err(), CalcError::TooComplex);
+        assert_eq!(calc(&format!("{}1{}", "(".repeat(50), ")".repeat(50))), "1");
+
+        let err = evaluate("2 + foo").unwrap_err();
+        assert_eq!(err.position(), Some(4));
+
+        let err = evaluate("3 $ 4").unwrap_err();
+        assert_eq!(err.position(), Some(2));
+    }
+
+    #[test]
+    fn expression_detection() {
+        assert!(looks_like_expression("150 * 6 + 7"));
+        assert!(looks_like_expression("sqrt(144)"));
+        assert!(looks_like_expression("2^10 % 7"));
+        assert!(!looks_like_expression("pi"));
+        assert!(!looks_like_expression("2024 election"));
+        assert!(!looks_like_expression("rust ownership"));
+    }
+}
@@ -0,0 +1,219 @@
+use crate::error::CalcError;
+use crate::eval;
+use crate::lexer::{Token, TokenKind};
+use crate::value::Value;
+
+// Binding power of each operator level (higher binds tighter).
+const ADDITIVE: u8 = 1;
+const MULTIPLICATIVE: u8 = 2;
+const UNARY: u8 = 3;
+const POWER: u8 = 4;
+
+/// Longest token stream parsed. Also bounds the depth of the tree, which
+/// evaluation walks recursively.
+const MAX_TOKENS: usize = 256;
+/// Deepest nesting of parentheses, unary signs, `^` and function calls.
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+/// Parsed expression tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(Value),
+    Constant(String),
+    Neg(Box<Expr>),
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+}
+
+/// Parse a token stream into an expression tree using precedence climbing.
+///
+/// Precedence, loosest to tightest: `+ -`, `* / %` (and implicit
+/// multiplication such as `2pi` or `3(4+5)`), unary minus, `^`.
+/// `^` is right-associative, so `-2^2` is `-4` and `2^3^2` is `512`.
+pub fn parse(tokens: &[Token]) -> Result<Expr, CalcError> {
+    if tokens.is_empty() {
+        return Err(CalcError::Empty);
+    }
+    if tokens.len() > MAX_TOKENS {
+        return Err(CalcError::TooComplex);
+    }
+
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        depth: 0,
+    };
+    let expr = parser.expression(0)?;
+
+    match parser.peek() {
+        Some(token) => Err(unexpected(token)),
+        None => Ok(expr),
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    /// Current `expression` recursion depth
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<(), CalcError> {
+        match self.advance() {
+            Some(token) if token.kind == kind => Ok(()),
+            Some(token) => Err(unexpected(token)),
+            None => Err(CalcError::UnexpectedEnd),
+        }
+    }
+
+    fn expression(&mut self, min_prec: u8) -> Result<Expr, CalcError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(CalcError::TooComplex);
+        }
+        self.depth += 1;
+        let expr = self.binary(min_prec);
+        self.depth -= 1;
+        expr
+    }
+
+    fn binary(&mut self, min_prec: u8) -> Result<Expr, CalcError> {
+        let mut lhs = self.unary()?;
+
+        while let Some(token) = self.peek() {
+            let (op, prec, implicit) = match token.kind {
+                TokenKind::Plus => (BinaryOp::Add, ADDITIVE, false),
+                TokenKind::Minus => (BinaryOp::Sub, ADDITIVE, false),
+                TokenKind::Star => (BinaryOp::Mul, MULTIPLICATIVE, false),
+                TokenKind::Slash => (BinaryOp::Div, MULTIPLICATIVE, false),
+                TokenKind::Percent => (BinaryOp::Rem, MULTIPLICATIVE, false),
+                TokenKind::Caret => (BinaryOp::Pow, POWER, false),
+                // "2pi", "3(4 + 5)", "(1 + 1)(2 + 2)"
+                TokenKind::Ident(_) | TokenKind::LParen => (BinaryOp::Mul, MULTIPLICATIVE, true),
+                _ => break,
+            };
+
+            if prec < min_prec {
+                break;
+            }
+            if !implicit {
+                self.advance();
+            }
+
+            // Right-associative for ^, left-associative for everything else
+            let next_min = if op == BinaryOp::Pow { prec } else { prec + 1 };
+            let rhs = self.expression(next_min)?;
+
+            lhs = Expr::Binary {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, CalcError> {
+        match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Minus) => {
+                self.advance();
+                Ok(Expr::Neg(Box::new(self.expression(UNARY)?)))
+            }
+            Some(TokenKind::Plus) => {
+                self.advance();
+                self.expression(UNARY)
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, CalcError> {
+        let token = self.advance().cloned().ok_or(CalcError::UnexpectedEnd)?;
+
+        match token.kind {
+            TokenKind::Number(n) => Ok(Expr::Number(n)),
+            TokenKind::LParen => {
+                let inner = self.expression(0)?;
+                self.expect(TokenKind::RParen)?;
+                Ok(inner)
+            }
+            TokenKind::Ident(name) => {
+                if eval::constant(&name).is_some() {
+                    return Ok(Expr::Constant(name));
+                }
+                if !eval::is_function(&name) {
+                    return Err(CalcError::UnknownIdentifier {
+                        name,
+                        position: token.position,
+                    });
+                }
+
+                // "sqrt(144)" or "sqrt 144"
+                let args = if matches!(self.peek().map(|t| &t.kind), Some(TokenKind::LParen)) {
+                    self.advance();
+                    self.arguments()?
+                } else {
+                    vec![self.expression(UNARY)?]
+                };
+
+                Ok(Expr::Call { name, args })
+            }
+            _ => Err(unexpected(&token)),
+        }
+    }
+
+    /// Comma-separated arguments after an opening parenthesis.
+    fn arguments(&mut self) -> Result<Vec<Expr>, CalcError> {
+        let mut args = Vec::new();
+
+        if matches!(self.peek().map(|t| &t.kind), Some(TokenKind::RParen)) {
+            self.advance();
+            return Ok(args);
+        }
+
+        loop {
+            args.push(self.expression(0)?);
+            match self.advance() {
+                Some(token) if token.kind == TokenKind::Comma => continue,
+                Some(token) if token.kind == TokenKind::RParen => return Ok(args),
+                Some(token) => return Err(unexpected(token)),
+                None => return Err(CalcError::UnexpectedEnd),
+            }
+        }
+    }
+}
+
+fn unexpected(token: &Token) -> CalcError {
+    CalcError::UnexpectedToken {
+        found: token.kind.describe(),
+        position: token.position,
+    }
+}
@@ -0,0 +1,425 @@
+//! Unit and currency conversion.
+//!
+//! Physical units live in a static table (factor + offset to a base unit per
+//! dimension). Currencies are only *recognized* here — exchange rates come
+//! from the `currency_rates` table at answer time.
+
+use serde::Serialize;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::CalcError;
+use crate::value::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dimension {
+    Length,
+    Mass,
+    Temperature,
+    Data,
+    Time,
+    Volume,
+    Speed,
+    Area,
+    Currency,
+}
+
+impl Dimension {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Dimension::Length => "length",
+            Dimension::Mass => "mass",
+            Dimension::Temperature => "temperature",
+            Dimension::Data => "data",
+            Dimension::Time => "time",
+            Dimension::Volume => "volume",
+            Dimension::Speed => "speed",
+            Dimension::Area => "area",
+            Dimension::Currency => "currency",
+        }
+    }
+}
+
+/// A physical unit. `base = value * factor + offset`, where the base unit
+/// is m, kg, K, byte, s, L, m/s or m² depending on the dimension.
+#[derive(Debug, PartialEq)]
+pub struct Unit {
+    /// Canonical short symbol used for display ("km", "°F", "GiB").
+    pub symbol: &'static str,
+    /// Canonical singular name ("kilometer").
+    pub name: &'static str,
+    pub dimension: Dimension,
+    factor: f64,
+    offset: f64,
+    /// Case-sensitive spellings ("MB" is a megabyte, "Mb" a megabit).
+    symbols: &'static [&'static str],
+    /// Case-insensitive spellings, matched after lowercasing.
+    names: &'static [&'static str],
+}
+
+impl Unit {
+    /// Convert `value` expressed in this unit into `to`.
+    /// Callers must check both units share a dimension.
+    pub fn convert(&self, value: f64, to: &Unit) -> f64 {
+        let base = value * self.factor + self.offset;
+        (base - to.offset) / to.factor
+    }
+}
+
+const fn unit(
+    symbol: &'static str,
+    name: &'static str,
+    dimension: Dimension,
+    factor: f64,
+    symbols: &'static [&'static str],
+    names: &'static [&'static str],
+) -> Unit {
+    Unit {
+        symbol,
+        name,
+        dimension,
+        factor,
+        offset: 0.0,
+        symbols,
+        names,
+    }
+}
+
+use Dimension::*;
+
+#[rustfmt::skip]
+static UNITS: &[Unit] = &[
+    // ── Length (m) ──
+    unit("nm", "nanometer", Length, 1e-9, &["nm"], &["nanometer", "nanometers", "nanometre", "nanometres"]),
+    unit("µm", "micrometer", Length, 1e-6, &["µm", "um"], &["micrometer", "micrometers", "micron", "microns"]),
+    unit("mm", "millimeter", Length, 1e-3, &[], &["mm", "millimeter", "millimeters", "millimetre", "millimetres"]),
+    unit("cm", "centimeter", Length, 1e-2, &[], &["cm", "centimeter", "centimeters", "centimetre", "centimetres"]),
+    unit("m", "meter", Length, 1.0, &["m"], &["meter", "meters", "metre", "metres"]),
+    unit("km", "kilometer", Length, 1e3, &[], &["km", "kms", "kilometer", "kilometers", "kilometre", "kilometres"]),
+    unit("in", "inch", Length, 0.0254, &["\""], &["in", "inch", "inches"]),
+    unit("ft", "foot", Length, 0.3048, &["'"], &["ft", "foot", "feet"]),
+    unit("yd", "yard", Length, 0.9144, &[], &["yd", "yds", "yard", "yards"]),
+    unit("mi", "mile", Length, 1609.344, &[], &["mi", "mile", "miles"]),
+    unit("nmi", "nautical mile", Length, 1852.0, &[], &["nmi", "nautical mile", "nautical miles"]),
+    // ── Mass (kg) ──
+    unit("mg", "milligram", Mass, 1e-6, &[], &["mg", "milligram", "milligrams"]),
+    unit("g", "gram", Mass, 1e-3, &[], &["g", "gr", "gram", "grams"]),
+    unit("kg", "kilogram", Mass, 1.0, &[], &["kg", "kgs", "kilo", "kilos", "kilogram", "kilograms"]),
+    unit("t", "tonne", Mass, 1e3, &["t"], &["tonne", "tonnes", "metric ton", "metric tons"]),
+    unit("oz", "ounce", Mass, 0.028_349_523_125, &[], &["oz", "ounce", "ounces"]),
+    unit("lb", "pound", Mass, 0.453_592_37, &[], &["lb", "lbs", "pound", "pounds"]),
+    unit("st", "stone", Mass, 6.350_293_18, &[], &["st", "stone", "stones"]),
+    // ── Temperature (K) ──
+    Unit {
+        symbol: "°C",
+        name: "celsius",
+        dimension: Temperature,
+        factor: 1.0,
+        offset: 273.15,
+        symbols: &["C", "°C"],
+        names: &["c", "°c", "celsius", "degc", "degrees celsius", "centigrade"],
+    },
+    Unit {
+        symbol: "°F",
+        name: "fahrenheit",
+        dimension: Temperature,
+        factor: 5.0 / 9.0,
+        offset: 459.67 * 5.0 / 9.0,
+        symbols: &["F", "°F"],
+        names: &["f", "°f", "fahrenheit", "degf", "degrees fahrenheit"],
+    },
+    unit("K", "kelvin", Temperature, 1.0, &["K"], &["kelvin", "kelvins"]),
+    // ── Data (byte) ──
+    unit("bit", "bit", Data, 0.125, &["b"], &["bit", "bits"]),
+    unit("B", "byte", Data, 1.0, &["B"], &["byte", "bytes"]),
+    unit("kB", "kilobyte", Data, 1e3, &["kB", "KB"], &["kb", "kilobyte", "kilobytes"]),
+    unit("MB", "megabyte", Data, 1e6, &["MB"], &["mb", "megabyte", "megabytes"]),
+    unit("GB", "gigabyte", Data, 1e9, &["GB"], &["gb", "gigabyte", "gigabytes", "gig", "gigs"]),
+    unit("TB", "terabyte", Data, 1e12, &["TB"], &["tb", "terabyte", "terabytes"]),
+    unit("PB", "petabyte", Data, 1e15, &["PB"], &["pb", "petabyte", "petabytes"]),
+    unit("KiB", "kibibyte", Data, 1024.0, &[], &["kib", "kibibyte", "kibibytes"]),
+    unit("MiB", "mebibyte", Data, 1_048_576.0, &[], &["mib", "mebibyte", "mebibytes"]),
+    unit("GiB", "gibibyte", Data, 1_073_741_824.0, &[], &["gib", "gibibyte", "gibibytes"]),
+    unit("TiB", "tebibyte", Data, 1_099_511_627_776.0, &[], &["tib", "tebibyte", "tebibytes"]),
+    unit("kbit", "kilobit", Data, 125.0, &["Kb"], &["kbit", "kilobit", "kilobits"]),
+    unit("Mbit", "megabit", Data, 125_000.0, &["Mb"], &["mbit", "megabit", "megabits"]),
+    unit("Gbit", "gigabit", Data, 125_000_000.0, &["Gb"], &["gbit", "gigabit", "gigabits"]),
+    // ── Time (s) ──
+    unit("ms", "millisecond", Time, 1e-3, &[], &["ms", "millisecond", "milliseconds"]),
+    unit("s", "second", Time, 1.0, &[], &["s", "sec", "secs", "second", "seconds"]),
+    unit("min", "minute", Time, 60.0, &[], &["min", "mins", "minute", "minutes"]),
+    unit("h", "hour", Time, 3600.0, &[], &["h", "hr", "hrs", "hour", "hours"]),
+    unit("d", "day", Time, 86_400.0, &[], &["d", "day", "days"]),
+    unit("wk", "week", Time, 604_800.0, &[], &["wk", "week", "weeks"]),
+    unit("mo", "month", Time, 2_629_800.0, &[], &["month", "months"]),
+    unit("yr", "year", Time, 31_557_600.0, &[], &["yr", "yrs", "year", "years"]),
+    // ── Volume (L) ──
+    unit("mL", "milliliter", Volume, 1e-3, &[], &["ml", "milliliter", "milliliters", "millilitre", "millilitres"]),
+    unit("L", "liter", Volume, 1.0, &["L"], &["l", "liter", "liters", "litre", "litres"]),
+    unit("m³", "cubic meter", Volume, 1e3, &["m3", "m³"], &["cubic meter", "cubic meters", "cubic metre", "cubic metres"]),
+    unit("tsp", "teaspoon", Volume, 0.004_928_921_593_75, &[], &["tsp", "teaspoon", "teaspoons"]),
+    unit("tbsp", "tablespoon", Volume, 0.014_786_764_781_25, &[], &["tbsp", "tablespoon", "tablespoons"]),
+    unit("fl oz", "fluid ounce", Volume, 0.029_573_529_562_5, &[], &["fl oz", "floz", "fluid ounce", "fluid ounces"]),
+    unit("cup", "cup", Volume, 0.236_588_236_5, &[], &["cup", "cups"]),
+    unit("pt", "pint", Volume, 0.473_176_473, &[], &["pt", "pint", "pints"]),
+    unit("qt", "quart", Volume, 0.946_352_946, &[], &["qt", "quart", "quarts"]),
+    unit("gal", "gallon", Volume, 3.785_411_784, &[], &["gal", "gallon", "gallons"]),
+    // ── Speed (m/s) ──
+    unit("m/s", "meter per second", Speed, 1.0, &[], &["m/s", "mps", "meters per second", "metres per second"]),
+    unit("km/h", "kilometer per hour", Speed, 1.0 / 3.6, &[], &["km/h", "kmh", "kph", "kmph", "kilometers per hour", "kilometres per hour"]),
+    unit("mph", "mile per hour", Speed, 0.447_04, &[], &["mph", "mi/h", "miles per hour"]),
+    unit("ft/s", "foot per second", Speed, 0.3048, &[], &["ft/s", "fps", "feet per second"]),
+    unit("kn", "knot", Speed, 1852.0 / 3600.0, &[], &["kn", "kt", "kts", "knot", "knots"]),
+    // ── Area (m²) ──
+    unit("cm²", "square centimeter", Area, 1e-4, &["cm2", "cm²"], &["square centimeter", "square centimeters", "sq cm"]),
+    unit("m²", "square meter", Area, 1.0, &["m2", "m²"], &["square meter", "square meters", "square metre", "square metres", "sq m", "sqm"]),
+    unit("km²", "square kilometer", Area, 1e6, &["km2", "km²"], &["square kilometer", "square kilometers", "sq km"]),
+    unit("ha", "hectare", Area, 1e4, &[], &["ha", "hectare", "hectares"]),
+    unit("ft²", "square foot", Area, 0.092_903_04, &["ft2", "ft²"], &["square foot", "square feet", "sq ft", "sqft"]),
+    unit("ac", "acre", Area, 4_046.856_422_4, &[], &["ac", "acre", "acres"]),
+    unit("mi²", "square mile", Area, 2_589_988.110_336, &["mi2", "mi²"], &["square mile", "square miles", "sq mi"]),
+];
+
+/// ISO 4217 codes we recognize, with common names and symbols.
+/// "pound" is deliberately left to the mass unit.
+#[rustfmt::skip]
+static CURRENCIES: &[(&str, &[&str])] = &[
+    ("USD", &["$", "dollar", "dollars", "us dollar", "us dollars", "buck", "bucks"]),
+    ("EUR", &["€", "euro", "euros"]),
+    ("GBP", &["£", "sterling", "british pound", "british pounds", "quid"]),
+    ("JPY", &["¥", "yen", "japanese yen"]),
+    ("CNY", &["rmb", "yuan", "renminbi"]),
+    ("INR", &["₹", "rupee", "rupees", "indian rupee", "indian rupees"]),
+    ("CAD", &["canadian dollar", "canadian dollars"]),
+    ("AUD", &["australian dollar", "australian dollars"]),
+    ("NZD", &["new zealand dollar", "new zealand dollars"]),
+    ("CHF", &["swiss franc", "swiss francs"]),
+    ("SEK", &["swedish krona", "swedish kronor"]),
+    ("NOK", &["norwegian krone", "norwegian kroner"]),
+    ("DKK", &["danish krone", "danish kroner"]),
+    ("PLN", &["zloty", "zlotys"]),
+    ("CZK", &["koruna"]),
+    ("HUF", &["forint"]),
+    ("RUB", &["₽", "ruble", "rubles", "rouble", "roubles"]),
+    ("TRY", &["₺", "lira", "turkish lira"]),
+    ("BRL", &["real", "reais"]),
+    ("MXN", &["mexican peso", "mexican pesos"]),
+    ("ZAR", &["rand"]),
+    ("KRW", &["₩", "won"]),
+    ("SGD", &["singapore dollar", "singapore dollars"]),
+    ("HKD", &["hong kong dollar", "hong kong dollars"]),
+    ("AED", &["dirham", "dirhams"]),
+    ("SAR", &["riyal", "riyals"]),
+    ("ILS", &["₪", "shekel", "shekels"]),
+    ("THB", &["baht"]),
+    ("IDR", &["rupiah"]),
+    ("PHP", &["philippine peso", "philippine pesos"]),
+    ("ETB", &["birr"]),
+    ("NGN", &["₦", "naira"]),
+    ("KES", &["kenyan shilling", "kenyan shillings"]),
+    ("EGP", &["egyptian pound", "egyptian pounds"]),
+];
+
+/// One side of a conversion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quantity {
+    Unit(&'static Unit),
+    /// ISO 4217 code, e.g. "EUR".
+    Currency(&'static str),
+}
+
+impl Quantity {
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            Quantity::Unit(u) => u.dimension,
+            Quantity::Currency(_) => Dimension::Currency,
+        }
+    }
+
+    /// Short display symbol ("km", "EUR").
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Quantity::Unit(u) => u.symbol,
+            Quantity::Currency(code) => code,
+        }
+    }
+
+    /// Display name ("kilometer", "EUR").
+    pub fn name(&self) -> &'static str {
+        match self {
+            Quantity::Unit(u) => u.name,
+            Quantity::Currency(code) => code,
+        }
+    }
+}
+
+/// A parsed "10 km in miles" query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionQuery {
+    pub amount: f64,
+    pub from: Quantity,
+    pub to: Quantity,
+}
+
+impl ConversionQuery {
+    /// Convert between physical units. Returns None for currencies,
+    /// which need exchange rates from the database.
+    pub fn convert_units(&self) -> Option<f64> {
+        match (self.from, self.to) {
+            (Quantity::Unit(from), Quantity::Unit(to)) => Some(from.convert(self.amount, to)),
+            _ => None,
+        }
+    }
+}
+
+static CONVERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?ix)
+        ^(?:what\s+is\s+|what's\s+|how\s+much\s+is\s+|convert\s+)?
+        (?P<sym>[$€£¥₹₽₩₪₦₺])?\s*
+        (?P<amount>-?\d[\d,]*(?:\.\d+)?|-?\.\d+)\s*
+        (?P<from>[^\d\s].*?)?
+        \s+(?:in|to|into|as|=|->)\s+
+        (?P<to>.+?)\s*\??$",
+    )
+    .unwrap()
+});
+
+/// Recognize conversion queries: "10 km in miles", "72 F to C",
+/// "5 GiB to MB", "100 usd to eur", "$20 in euros".
+///
+/// Returns None unless both sides resolve to known units of the same
+/// dimension, so "10 reasons to learn rust" stays a search.
+pub fn parse_conversion(query: &str) -> Option<ConversionQuery> {
+    let caps = CONVERSION_RE.captures(query.trim())?;
+
+    let amount: f64 = caps["amount"].replace(',', "").parse().ok()?;
+
+    let from_text = caps
+        .name("sym")
+        .or_else(|| caps.name("from"))
+        .map(|m| m.as_str())?;
+    let from = lookup(from_text)?;
+    let to = lookup(&caps["to"])?;
+
+    if from.dimension() != to.dimension() {
+        return None;
+    }
+
+    Some(ConversionQuery { amount, from, to })
+}
+
+/// Resolve a unit or currency spelling.
+pub fn lookup(text: &str) -> Option<Quantity> {
+    let text = text.trim().trim_end_matches('.');
+    if text.is_empty() {
+        return None;
+    }
+
+    // Case-sensitive symbols first so "Mb" (megabit) beats "mb" (megabyte)
+    if let Some(u) = UNITS.iter().find(|u| u.symbols.contains(&text)) {
+        return Some(Quantity::Unit(u));
+    }
+
+    let lower = text.to_lowercase();
+    if let Some(u) = UNITS.iter().find(|u| u.names.contains(&lower.as_str())) {
+        return Some(Quantity::Unit(u));
+    }
+
+    CURRENCIES
+        .iter()
+        .find(|(code, aliases)| {
+            code.eq_ignore_ascii_case(&lower) || aliases.contains(&lower.as_str())
+        })
+        .map(|(code, _)| Quantity::Currency(code))
+}
+
+/// Reject a converted amount that overflowed or is NaN, e.g. from an
+/// amount too large for f64.
+pub fn check_result(value: f64) -> Result<f64, CalcError> {
+    Value::float(value, "conversion").map(Value::as_f64)
+}
+
+/// Format a converted amount to at most 6 significant digits. `value`
+/// must be finite (see `check_result`).
+pub fn format_amount(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+
+    let magnitude = value.abs().log10().floor() as i32;
+    if !(-6..15).contains(&magnitude) {
+        return format!("{:.5e}", value);
+    }
+
+    let decimals = (5 - magnitude).max(0) as usize;
+    let formatted = format!("{:.*}", decimals, value);
+    if formatted.contains('.') {
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    } else {
+        formatted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(query: &str) -> String {
+        let q = parse_conversion(query).unwrap();
+        format_amount(q.convert_units().unwrap())
+    }
+
+    #[test]
+    fn length_and_temperature() {
+        assert_eq!(convert("10 km in miles"), "6.21371");
+        assert_eq!(convert("72 F to C"), "22.2222");
+        assert_eq!(convert("100°C to °F"), "212");
+        assert_eq!(convert("0 kelvin in celsius"), "-273.15");
+        assert_eq!(convert("convert 5 feet to cm"), "152.4");
+    }
+
+    #[test]
+    fn data_units_respect_case() {
+        assert_eq!(convert("5 GiB to MB"), "5368.71");
+        assert_eq!(convert("1 MB to Mb"), "8");
+        assert_eq!(convert("1 gb in mb"), "1000");
+    }
+
+    #[test]
+    fn currencies_are_recognized() {
+        let q = parse_conversion("100 usd to eur").unwrap();
+        assert_eq!(q.amount, 100.0);
+        assert_eq!(q.from, Quantity::Currency("USD"));
+        assert_eq!(q.to, Quantity::Currency("EUR"));
+        assert!(q.convert_units().is_none());
+
+        let q = parse_conversion("$1,250 in euros").unwrap();
+        assert_eq!(q.amount, 1250.0);
+        assert_eq!(q.from, Quantity::Currency("USD"));
+    }
+
+    #[test]
+    fn rejects_non_finite_results() {
+        let huge = format!("1{} km in mm", "0".repeat(400));
+        let q = parse_conversion(&huge).unwrap();
+        assert_eq!(
+            check_result(q.convert_units().unwrap()),
+            Err(CalcError::Overflow { position: None })
+        );
+        assert!(matches!(check_result(f64::NAN), Err(CalcError::Domain(_))));
+        assert_eq!(check_result(6.5), Ok(6.5));
+    }
+
+    #[test]
+    fn rejects_non_conversions() {
+        assert!(parse_conversion("10 reasons to learn rust").is_none());
+        assert!(parse_conversion("10 km to kg").is_none());
+        assert!(parse_conversion("how to convert units").is_none());
+    }
+}
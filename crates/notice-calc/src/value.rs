@@ -0,0 +1,157 @@
+use std::fmt;
+
+use crate::error::CalcError;
+
+/// A number produced during evaluation.
+///
+/// Integers are kept exact (i128) for as long as the arithmetic allows,
+/// so `2^100` or `20!`-sized products don't lose digits. Anything that
+/// overflows, divides unevenly or goes through a transcendental function
+/// falls back to f64.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    Int(i128),
+    Float(f64),
+}
+
+impl Value {
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Value::Int(n) => n as f64,
+            Value::Float(f) => f,
+        }
+    }
+
+    /// Whether the value is an exact integer (no rounding happened).
+    pub fn is_exact(self) -> bool {
+        matches!(self, Value::Int(_))
+    }
+
+    /// Wrap a float result, rejecting infinities and NaN.
+    pub fn float(f: f64, operation: &str) -> Result<Value, CalcError> {
+        if f.is_nan() {
+            Err(CalcError::Domain(operation.to_string()))
+        } else if f.is_infinite() {
+            Err(CalcError::Overflow { position: None })
+        } else {
+            Ok(Value::Float(f))
+        }
+    }
+
+    pub fn try_neg(self) -> Result<Value, CalcError> {
+        match self {
+            Value::Int(n) => match n.checked_neg() {
+                Some(r) => Ok(Value::Int(r)),
+                None => Value::float(-(n as f64), "negation"),
+            },
+            Value::Float(f) => Ok(Value::Float(-f)),
+        }
+    }
+
+    pub fn try_add(self, rhs: Value) -> Result<Value, CalcError> {
+        if let (Value::Int(a), Value::Int(b)) = (self, rhs)
+            && let Some(r) = a.checked_add(b)
+        {
+            return Ok(Value::Int(r));
+        }
+        Value::float(self.as_f64() + rhs.as_f64(), "addition")
+    }
+
+    pub fn try_sub(self, rhs: Value) -> Result<Value, CalcError> {
+        if let (Value::Int(a), Value::Int(b)) = (self, rhs)
+            && let Some(r) = a.checked_sub(b)
+        {
+            return Ok(Value::Int(r));
+        }
+        Value::float(self.as_f64() - rhs.as_f64(), "subtraction")
+    }
+
+    pub fn try_mul(self, rhs: Value) -> Result<Value, CalcError> {
+        if let (Value::Int(a), Value::Int(b)) = (self, rhs)
+            && let Some(r) = a.checked_mul(b)
+        {
+            return Ok(Value::Int(r));
+        }
+        Value::float(self.as_f64() * rhs.as_f64(), "multiplication")
+    }
+
+    pub fn try_div(self, rhs: Value) -> Result<Value, CalcError> {
+        if rhs.as_f64() == 0.0 {
+            return Err(CalcError::DivisionByZero);
+        }
+        if let (Value::Int(a), Value::Int(b)) = (self, rhs)
+            && a.checked_rem(b) == Some(0)
+            && let Some(r) = a.checked_div(b)
+        {
+            return Ok(Value::Int(r));
+        }
+        Value::float(self.as_f64() / rhs.as_f64(), "division")
+    }
+
+    /// Modulo with the sign of the divisor's magnitude (Euclidean),
+    /// so `-7 % 3` is `2` like most calculators.
+    pub fn try_rem(self, rhs: Value) -> Result<Value, CalcError> {
+        if rhs.as_f64() == 0.0 {
+            return Err(CalcError::DivisionByZero);
+        }
+        if let (Value::Int(a), Value::Int(b)) = (self, rhs)
+            && let Some(r) = a.checked_rem_euclid(b)
+        {
+            return Ok(Value::Int(r));
+        }
+        Value::float(self.as_f64().rem_euclid(rhs.as_f64()), "modulo")
+    }
+
+    pub fn try_pow(self, rhs: Value) -> Result<Value, CalcError> {
+        if let (Value::Int(base), Value::Int(exp)) = (self, rhs) {
+            if base == 0 && exp < 0 {
+                return Err(CalcError::DivisionByZero);
+            }
+            if let Ok(exp) = u32::try_from(exp)
+                && let Some(r) = base.checked_pow(exp)
+            {
+                return Ok(Value::Int(r));
+            }
+        }
+        Value::float(self.as_f64().powf(rhs.as_f64()), "exponentiation")
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Float(x) => f.write_str(&format_float(*x)),
+        }
+    }
+}
+
+/// Format a float for display: plain decimals for everyday magnitudes,
+/// scientific notation for very large or very small numbers.
+fn format_float(x: f64) -> String {
+    if x == 0.0 {
+        return "0".to_string();
+    }
+
+    let magnitude = x.abs();
+    if !(1e-9..1e15).contains(&magnitude) {
+        let formatted = format!("{:.12e}", x);
+        let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+        let mantissa = trim_decimals(mantissa);
+        return format!("{}e{}", mantissa, exponent);
+    }
+
+    if x == x.trunc() {
+        return format!("{}", x as i64);
+    }
+
+    trim_decimals(&format!("{:.10}", x)).to_string()
+}
+
+fn trim_decimals(s: &str) -> &str {
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        s
+    }
+}
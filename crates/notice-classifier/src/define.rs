@@ -0,0 +1,88 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Definition phrasings with how sure we are that each one is a dictionary
+/// lookup. "X meaning" is often a search ("life meaning"), hence the low score.
+static DEFINE_PATTERNS: LazyLock<Vec<(Regex, f32)>> = LazyLock::new(|| {
+    [
+        (r"(?i)^define:?\s+(.+)$", 0.95),
+        (
+            r"(?i)^(?:what\s+is\s+the\s+|whats\s+the\s+|what's\s+the\s+)?(?:definition|meaning)\s+of\s+(.+)$",
+            0.85,
+        ),
+        (r"(?i)^what\s+does\s+(.+?)\s+mean$", 0.85),
+        (r"(?i)^what\s+is\s+meant\s+by\s+(.+)$", 0.8),
+        (r"(?i)^(\S+(?:\s+\S+){0,2})\s+(?:meaning|definition)$", 0.55),
+    ]
+    .iter()
+    .map(|(p, confidence)| (Regex::new(p).unwrap(), *confidence))
+    .collect()
+});
+
+/// Longest phrase we treat as a dictionary term.
+const MAX_TERM_WORDS: usize = 4;
+
+/// Pull the term out of a definition query, with the confidence of the
+/// phrasing that matched: "define entropy", "what does osmosis mean?",
+/// "meaning of 'ubiquitous'", "serendipity meaning".
+/// Returns None if the query isn't one of these forms.
+pub fn extract_term(query: &str) -> Option<(String, f32)> {
+    let query = query.trim().trim_end_matches(['?', '.', '!']).trim();
+
+    let (caps, confidence) = DEFINE_PATTERNS
+        .iter()
+        .find_map(|(re, confidence)| Some((re.captures(query)?, *confidence)))?;
+    let raw = caps.get(1)?.as_str();
+
+    let term = raw.trim();
+    let term = strip_prefix_ci(term, "the word ").unwrap_or(term);
+    let term = term
+        .trim_matches(|c: char| matches!(c, '"' | '\'' | '“' | '”' | '‘' | '’'))
+        .trim();
+
+    let words = term.split_whitespace().count();
+    if words == 0 || words > MAX_TERM_WORDS {
+        return None;
+    }
+
+    Some((term.to_string(), confidence))
+}
+
+fn strip_prefix_ci<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    s.get(..prefix.len())
+        .filter(|head| head.eq_ignore_ascii_case(prefix))
+        .map(|_| &s[prefix.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(query: &str) -> Option<String> {
+        extract_term(query).map(|(term, _)| term)
+    }
+
+    #[test]
+    fn extracts_terms() {
+        assert_eq!(term("define entropy").as_deref(), Some("entropy"));
+        assert_eq!(term("Define: Osmosis").as_deref(), Some("Osmosis"));
+        assert_eq!(
+            term("what does ubiquitous mean?").as_deref(),
+            Some("ubiquitous")
+        );
+        assert_eq!(
+            term("meaning of \"serendipity\"").as_deref(),
+            Some("serendipity")
+        );
+        assert_eq!(
+            term("what is the definition of the word quorum").as_deref(),
+            Some("quorum")
+        );
+        assert_eq!(term("petrichor meaning").as_deref(), Some("petrichor"));
+        assert_eq!(term("rust borrow checker"), None);
+        assert_eq!(
+            term("what does it mean to live a good life and be happy"),
+            None
+        );
+    }
+}
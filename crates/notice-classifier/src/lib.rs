@@ -1,4 +1,10 @@
-use regex::Regex;
+pub mod define;
+pub mod navigate;
+pub mod timer;
+
+use navigate::Navigation;
+use notice_calc::units::ConversionQuery;
+use timer::TimerSpec;
 
 /// What the user wants to do.
 #[derive(Debug, Clone)]
@@ -7,36 +13,183 @@ pub enum QueryIntent {
     Search(String),
     /// Evaluate a math expression
     Calculate(String),
-    /// Look up a word definition
+    /// Convert between units or currencies
+    Convert(ConversionQuery),
+    /// Look up a word definition (carries the extracted term)
     Define(String),
-    /// Set a timer
-    Timer(String),
+    /// Start a countdown
+    Timer(TimerSpec),
+    /// Jump to a site: a bang ("!gh tokio") or a bare domain ("docs.rs")
+    Navigate(Navigation),
+}
+
+impl QueryIntent {
+    /// Short name used in search history and the intent cache.
+    pub fn name(&self) -> &'static str {
+        match self {
+            QueryIntent::Search(_) => "search",
+            QueryIntent::Calculate(_) => "calculate",
+            QueryIntent::Convert(_) => "convert",
+            QueryIntent::Define(_) => "define",
+            QueryIntent::Timer(_) => "timer",
+            QueryIntent::Navigate(_) => "navigate",
+        }
+    }
+}
+
+/// An intent plus how sure the rules are about it (0.0–1.0).
+#[derive(Debug, Clone)]
+pub struct Classification {
+    pub intent: QueryIntent,
+    pub confidence: f32,
 }
 
+/// Words that suggest an instant answer even when no rule matched,
+/// e.g. "what is five times three" or "how many cups in a liter".
+const INTENT_HINTS: &[&str] = &[
+    "convert",
+    "calculate",
+    "times",
+    "plus",
+    "minus",
+    "divided",
+    "percent",
+    "squared",
+    "how many",
+    "how much",
+    "define",
+    "definition",
+    "meaning",
+    "mean",
+    "timer",
+    "countdown",
+    "remind",
+];
+
 /// Classify a user query into an intent.
 /// Uses rule-based matching for deterministic queries.
 /// Everything else goes to search.
-pub fn classify(query: &str) -> QueryIntent {
+pub fn classify(query: &str) -> Classification {
     let trimmed = query.trim();
 
+    // Navigation: "!gh tokio", "docs.rs", "https://example.com"
+    if let Some((navigation, confidence)) = navigate::parse_navigation(trimmed) {
+        return Classification {
+            intent: QueryIntent::Navigate(navigation),
+            confidence,
+        };
+    }
+
     // Math expressions: "150 * 6 + 7", "sqrt(144)", "2^10"
-    let math_re = Regex::new(r"^[\d\s\+\-\*/\.\(\)\^%]+$").unwrap();
-    if math_re.is_match(trimmed) {
-        return QueryIntent::Calculate(trimmed.to_string());
+    if notice_calc::looks_like_expression(trimmed) {
+        // A bare number ("1984", "2024") is more likely a search
+        let has_operator = trimmed
+            .chars()
+            .any(|c| "+-*/%^×÷−(".contains(c) || c.is_alphabetic());
+        return Classification {
+            intent: QueryIntent::Calculate(trimmed.to_string()),
+            confidence: if has_operator { 0.95 } else { 0.4 },
+        };
+    }
+
+    // Conversions: "10 km in miles", "72 F to C", "100 usd to eur"
+    if let Some(conversion) = notice_calc::units::parse_conversion(trimmed) {
+        return Classification {
+            intent: QueryIntent::Convert(conversion),
+            confidence: 0.9,
+        };
     }
 
     // Definitions: "define entropy", "what does osmosis mean"
-    let define_re =
-        Regex::new(r"(?i)^(define |what does .+ mean|meaning of |definition of )").unwrap();
-    if define_re.is_match(trimmed) {
-        return QueryIntent::Define(trimmed.to_string());
+    if let Some((term, confidence)) = define::extract_term(trimmed) {
+        return Classification {
+            intent: QueryIntent::Define(term),
+            confidence,
+        };
+    }
+
+    // Timers: "set a timer for 10 minutes", "timer 5m", "countdown to 17:00"
+    if let Some(spec) = timer::parse_timer(trimmed) {
+        return Classification {
+            intent: QueryIntent::Timer(spec),
+            confidence: 0.9,
+        };
     }
 
-    // Timers: "set a timer for 10 minutes", "timer 5m"
-    let timer_re = Regex::new(r"(?i)(set .* timer|timer .*(min|sec|hour)|countdown)").unwrap();
-    if timer_re.is_match(trimmed) {
-        return QueryIntent::Timer(trimmed.to_string());
+    let normalized = normalize_query(trimmed);
+    let words: Vec<&str> = normalized
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let hinted = INTENT_HINTS.iter().any(|hint| {
+        let hint: Vec<&str> = hint.split(' ').collect();
+        words.windows(hint.len()).any(|w| w == hint.as_slice())
+    });
+
+    Classification {
+        intent: QueryIntent::Search(trimmed.to_string()),
+        confidence: if hinted { 0.5 } else { 0.9 },
     }
+}
 
-    QueryIntent::Search(trimmed.to_string())
+/// Cache key for a query: lowercased, whitespace collapsed, trailing
+/// punctuation dropped.
+pub fn normalize_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['?', '.', '!'])
+        .to_lowercase()
+}
+
+/// Turn an LLM classification back into a typed intent.
+///
+/// The model returns a category plus a normalized argument ("5 * 3" for
+/// "what is five times three"); the argument is re-parsed with the same
+/// rules as `classify`, so a hallucinated or malformed answer yields None
+/// and the caller keeps the rule-based intent.
+pub fn intent_from_llm(query: &str, category: &str, argument: Option<&str>) -> Option<QueryIntent> {
+    let argument = argument.map(str::trim).filter(|a| !a.is_empty());
+
+    match category.trim().to_lowercase().as_str() {
+        "search" => Some(QueryIntent::Search(query.trim().to_string())),
+        "calculate" => {
+            let expr = argument?;
+            notice_calc::evaluate(expr).ok()?;
+            Some(QueryIntent::Calculate(expr.to_string()))
+        }
+        "convert" => notice_calc::units::parse_conversion(argument?).map(QueryIntent::Convert),
+        "define" => {
+            let term = argument?;
+            (term.split_whitespace().count() <= 4).then(|| QueryIntent::Define(term.to_string()))
+        }
+        "timer" => {
+            let arg = argument?;
+            timer::parse_timer(&format!("timer {}", arg)).map(QueryIntent::Timer)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confidence() {
+        assert!(classify("150 * 6 + 7").confidence > 0.9);
+        assert!(classify("1984").confidence < 0.6);
+        assert!(classify("what is five times three").confidence < 0.6);
+        assert!(classify("rust borrow checker").confidence > 0.6);
+    }
+
+    #[test]
+    fn llm_answers_are_reparsed() {
+        let intent = intent_from_llm("what is five times three", "calculate", Some("5 * 3"));
+        assert!(matches!(intent, Some(QueryIntent::Calculate(e)) if e == "5 * 3"));
+        assert!(intent_from_llm("x", "calculate", Some("five times")).is_none());
+        assert!(intent_from_llm("x", "weather", None).is_none());
+        assert_eq!(normalize_query("  What  IS this? "), "what is this");
+    }
 }
@@ -0,0 +1,209 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Placeholder in a bang URL template that is replaced by the search terms.
+pub const QUERY_PLACEHOLDER: &str = "{query}";
+
+static TRIGGER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z0-9][a-z0-9_-]{0,19}$").unwrap());
+
+static URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^https?://[a-z0-9.-]+\.[a-z]{2,}(?::\d+)?(?:[/?#]\S*)?$").unwrap()
+});
+
+static DOMAIN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^((?:[a-z0-9](?:[a-z0-9-]*[a-z0-9])?\.)+([a-z]{2,}))(?::\d+)?(?:/\S*)?$")
+        .unwrap()
+});
+
+/// A built-in bang. Users and admins can override or add to these through
+/// the `bangs` table.
+#[derive(Debug, Clone, Copy)]
+pub struct BangDef {
+    pub trigger: &'static str,
+    pub name: &'static str,
+    pub url: &'static str,
+}
+
+#[rustfmt::skip]
+pub const DEFAULT_BANGS: &[BangDef] = &[
+    BangDef { trigger: "gh",     name: "GitHub",               url: "https://github.com/search?q={query}" },
+    BangDef { trigger: "rs",     name: "docs.rs",              url: "https://docs.rs/releases/search?query={query}" },
+    BangDef { trigger: "crates", name: "crates.io",            url: "https://crates.io/search?q={query}" },
+    BangDef { trigger: "std",    name: "Rust std",             url: "https://doc.rust-lang.org/std/?search={query}" },
+    BangDef { trigger: "mdn",    name: "MDN Web Docs",         url: "https://developer.mozilla.org/en-US/search?q={query}" },
+    BangDef { trigger: "npm",    name: "npm",                  url: "https://www.npmjs.com/search?q={query}" },
+    BangDef { trigger: "py",     name: "Python docs",          url: "https://docs.python.org/3/search.html?q={query}" },
+    BangDef { trigger: "pypi",   name: "PyPI",                 url: "https://pypi.org/search/?q={query}" },
+    BangDef { trigger: "so",     name: "Stack Overflow",       url: "https://stackoverflow.com/search?q={query}" },
+    BangDef { trigger: "w",      name: "Wikipedia",            url: "https://en.wikipedia.org/wiki/Special:Search?search={query}" },
+    BangDef { trigger: "yt",     name: "YouTube",              url: "https://www.youtube.com/results?search_query={query}" },
+    BangDef { trigger: "g",      name: "Google",               url: "https://www.google.com/search?q={query}" },
+    BangDef { trigger: "ddg",    name: "DuckDuckGo",           url: "https://duckduckgo.com/?q={query}" },
+    BangDef { trigger: "hn",     name: "Hacker News (Algolia)", url: "https://hn.algolia.com/?q={query}" },
+];
+
+/// Top-level domains we accept for bare-domain queries like "docs.rs".
+#[rustfmt::skip]
+const TLDS: &[&str] = &[
+    "com", "org", "net", "io", "dev", "app", "ai", "co", "edu", "gov", "me", "info", "xyz", "tech",
+    "rs", "sh", "so", "md", "py", "pl", "gg", "tv", "fm", "ly", "to", "cc",
+    "uk", "de", "fr", "nl", "jp", "ca", "au", "eu", "us", "ch", "se", "no", "fi", "it", "es", "in",
+];
+
+/// TLDs that are also common file extensions ("main.rs", "notes.md").
+/// A bare domain with one of these is only navigational for hosts we know.
+const AMBIGUOUS_TLDS: &[&str] = &["rs", "sh", "so", "md", "py", "pl"];
+
+/// Where a navigational query wants to go.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Navigation {
+    /// "!gh tokio" or "tokio !gh": the trigger is resolved against the
+    /// bang table by the caller, since user overrides live in the database.
+    Bang { trigger: String, terms: String },
+    /// "docs.rs", "https://example.com/page": go straight there.
+    Url(String),
+}
+
+/// Detect a bang or a bare URL/domain, with a confidence.
+pub fn parse_navigation(query: &str) -> Option<(Navigation, f32)> {
+    let query = query.trim();
+    let tokens: Vec<&str> = query.split_whitespace().collect();
+
+    // Bangs go first or last: "!gh tokio", "tokio !gh"
+    for index in [0, tokens.len().saturating_sub(1)] {
+        let Some(trigger) = tokens.get(index).and_then(|t| t.strip_prefix('!')) else {
+            continue;
+        };
+        let trigger = trigger.to_lowercase();
+        if !is_valid_trigger(&trigger) {
+            continue;
+        }
+
+        let terms = tokens
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, t)| *t)
+            .collect::<Vec<_>>()
+            .join(" ");
+        return Some((Navigation::Bang { trigger, terms }, 0.95));
+    }
+
+    if tokens.len() != 1 {
+        return None;
+    }
+
+    if URL_RE.is_match(query) {
+        return Some((Navigation::Url(query.to_string()), 0.95));
+    }
+
+    let caps = DOMAIN_RE.captures(query)?;
+    let host = caps[1].to_lowercase();
+    let tld = caps[2].to_lowercase();
+    if !TLDS.contains(&tld.as_str()) {
+        return None;
+    }
+
+    if AMBIGUOUS_TLDS.contains(&tld.as_str()) && !is_known_host(&host) {
+        return None;
+    }
+    Some((Navigation::Url(format!("https://{}", query)), 0.85))
+}
+
+/// Bang triggers are short lowercase words: "gh", "mdn", "rs".
+pub fn is_valid_trigger(trigger: &str) -> bool {
+    TRIGGER_RE.is_match(trigger)
+}
+
+pub fn default_bang(trigger: &str) -> Option<&'static BangDef> {
+    DEFAULT_BANGS.iter().find(|b| b.trigger == trigger)
+}
+
+/// Fill a bang template. Without terms, a search template goes to the
+/// site's front page; a template without `{query}` is used as it is.
+pub fn expand_template(template: &str, terms: &str) -> String {
+    if !template.contains(QUERY_PLACEHOLDER) {
+        return template.to_string();
+    }
+    let terms = terms.trim();
+    if terms.is_empty() {
+        return site_root(template).unwrap_or(template).to_string();
+    }
+    template.replace(QUERY_PLACEHOLDER, &urlencoding::encode(terms))
+}
+
+/// "https://github.com/search?q={query}" → "https://github.com/"
+fn site_root(url: &str) -> Option<&str> {
+    let host_start = url.find("://")? + 3;
+    match url[host_start..].find('/') {
+        Some(slash) => Some(&url[..host_start + slash + 1]),
+        None => Some(url),
+    }
+}
+
+/// Hosts of the built-in bangs, e.g. "docs.rs".
+fn is_known_host(host: &str) -> bool {
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    DEFAULT_BANGS.iter().any(|b| {
+        site_root(b.url)
+            .and_then(|root| root.split("://").nth(1))
+            .map(|h| h.trim_end_matches('/'))
+            .is_some_and(|h| h.strip_prefix("www.").unwrap_or(h) == host)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bang(trigger: &str, terms: &str) -> Option<Navigation> {
+        Some(Navigation::Bang {
+            trigger: trigger.to_string(),
+            terms: terms.to_string(),
+        })
+    }
+
+    fn nav(query: &str) -> Option<Navigation> {
+        parse_navigation(query).map(|(n, _)| n)
+    }
+
+    #[test]
+    fn bangs() {
+        assert_eq!(nav("!gh tokio"), bang("gh", "tokio"));
+        assert_eq!(nav("serde derive !RS"), bang("rs", "serde derive"));
+        assert_eq!(nav("!mdn"), bang("mdn", ""));
+        assert_eq!(nav("hello world!"), None);
+        assert_eq!(nav("what is ! in rust"), None);
+    }
+
+    #[test]
+    fn domains() {
+        assert_eq!(
+            nav("docs.rs"),
+            Some(Navigation::Url("https://docs.rs".into()))
+        );
+        assert_eq!(
+            nav("https://example.com/a?b=c"),
+            Some(Navigation::Url("https://example.com/a?b=c".into()))
+        );
+        assert_eq!(nav("main.rs"), None);
+        assert_eq!(nav("config.toml"), None);
+        assert_eq!(nav("docs.rs tokio"), None);
+    }
+
+    #[test]
+    fn templates() {
+        let gh = default_bang("gh").unwrap();
+        assert_eq!(
+            expand_template(gh.url, "async runtime"),
+            "https://github.com/search?q=async%20runtime"
+        );
+        assert_eq!(expand_template(gh.url, ""), "https://github.com/");
+
+        // Bookmark-style bangs keep their path, with or without terms
+        let dashboard = "https://example.com/team/dashboard";
+        assert_eq!(expand_template(dashboard, ""), dashboard);
+        assert_eq!(expand_template(dashboard, "ignored"), dashboard);
+    }
+}
@@ -0,0 +1,292 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveTime, TimeZone, Utc};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Longest timer we accept (one week).
+pub const MAX_TIMER_SECONDS: u64 = 7 * 86_400;
+
+static TIMER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(?:timer|countdown|count\s+down)\b").unwrap());
+
+static DURATION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(\d+(?:\.\d+)?|half\s+an?|an?)\s*(hours|hour|hrs|hr|h|minutes|minute|mins|min|m|seconds|second|secs|sec|s)",
+    )
+    .unwrap()
+});
+
+static CLOCK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:to|until|till|til|at)\s+(?:(noon|midnight)|(\d{1,2})(?::(\d{2}))?\s*(am|pm|a\.m\.|p\.m\.)?)",
+    )
+    .unwrap()
+});
+
+static LABEL_FOR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:for|called|named|labell?ed)\s+(?:the\s+|my\s+)?([a-z][a-z '\-]*?)\s*$")
+        .unwrap()
+});
+
+static LABEL_PREFIX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:(?:set|start)\s+)?(?:(?:a|an|the|my)\s+)?([a-z]+)\s+timer\b").unwrap()
+});
+
+/// Words that can precede "timer" without being a label.
+const NOT_LABELS: &[&str] = &["set", "start", "new", "a", "an", "the", "my", "countdown"];
+
+/// What to count down to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimerTarget {
+    /// A fixed duration in seconds.
+    Duration(u64),
+    /// A wall-clock time in the user's timezone (next occurrence).
+    Until(NaiveTime),
+}
+
+/// A parsed timer request: "timer 5m", "set a pasta timer for 1h 30 min",
+/// "countdown to 17:00".
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerSpec {
+    pub target: TimerTarget,
+    pub label: Option<String>,
+}
+
+/// A timer pinned to an absolute deadline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedTimer {
+    pub seconds: u64,
+    pub target: DateTime<Utc>,
+    pub label: Option<String>,
+}
+
+impl TimerSpec {
+    /// Resolve against the current time. `tz_offset_minutes` is the user's
+    /// offset east of UTC and only matters for wall-clock targets;
+    /// out-of-range offsets are treated as UTC.
+    pub fn resolve(&self, now: DateTime<Utc>, tz_offset_minutes: i32) -> ResolvedTimer {
+        let seconds = match self.target {
+            TimerTarget::Duration(seconds) => seconds,
+            TimerTarget::Until(time) => {
+                let offset = tz_offset_minutes
+                    .checked_mul(60)
+                    .and_then(FixedOffset::east_opt)
+                    .unwrap_or(FixedOffset::east_opt(0).unwrap());
+                let local_now = now.with_timezone(&offset);
+
+                let mut target = offset
+                    .from_local_datetime(&local_now.date_naive().and_time(time))
+                    .single()
+                    .unwrap_or(local_now);
+                if target <= local_now {
+                    target += Duration::days(1);
+                }
+
+                (target - local_now).num_seconds().max(0) as u64
+            }
+        };
+
+        ResolvedTimer {
+            seconds,
+            target: now + Duration::seconds(seconds as i64),
+            label: self.label.clone(),
+        }
+    }
+}
+
+/// Parse a timer query. Returns None unless the query mentions a timer or
+/// countdown *and* contains a usable duration or clock time, so searches
+/// like "kitchen timer reviews" fall through to search.
+pub fn parse_timer(query: &str) -> Option<TimerSpec> {
+    if !TIMER_RE.is_match(query) {
+        return None;
+    }
+
+    let target = match parse_clock(query) {
+        Some(time) => TimerTarget::Until(time),
+        None => {
+            let seconds = parse_duration(query)?;
+            if seconds == 0 || seconds > MAX_TIMER_SECONDS {
+                return None;
+            }
+            TimerTarget::Duration(seconds)
+        }
+    };
+
+    Some(TimerSpec {
+        target,
+        label: parse_label(query),
+    })
+}
+
+/// Sum every "<amount> <unit>" in the text: "1h 30 min" → 5400.
+fn parse_duration(text: &str) -> Option<u64> {
+    let mut total = 0.0;
+    let mut found = false;
+
+    for caps in DURATION_RE.captures_iter(text) {
+        let whole = caps.get(0)?;
+        // Reject unit prefixes of longer words ("5 mice", "a stopwatch")
+        if text[whole.end()..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphabetic())
+        {
+            continue;
+        }
+
+        let amount_text = caps[1].to_lowercase();
+        let is_word = !amount_text.starts_with(|c: char| c.is_ascii_digit());
+        // ...and word amounts glued to the end of another word ("pasta s")
+        if is_word
+            && text[..whole.start()]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphabetic())
+        {
+            continue;
+        }
+
+        let amount = match amount_text.as_str() {
+            "a" | "an" => 1.0,
+            s if s.starts_with("half") => 0.5,
+            s => s.parse::<f64>().ok()?,
+        };
+
+        let unit = match caps[2].to_lowercase().chars().next()? {
+            'h' => 3600.0,
+            'm' => 60.0,
+            _ => 1.0,
+        };
+
+        total += amount * unit;
+        found = true;
+    }
+
+    found.then_some(total.round() as u64)
+}
+
+/// "to 17:00", "until 5pm", "at 6:30 am", "to midnight".
+/// A bare number ("countdown to 5") is too ambiguous and is ignored.
+fn parse_clock(text: &str) -> Option<NaiveTime> {
+    let caps = CLOCK_RE.captures(text)?;
+
+    if let Some(word) = caps.get(1) {
+        let hour = if word.as_str().eq_ignore_ascii_case("noon") {
+            12
+        } else {
+            0
+        };
+        return NaiveTime::from_hms_opt(hour, 0, 0);
+    }
+
+    let hour: u32 = caps.get(2)?.as_str().parse().ok()?;
+    let minute: Option<u32> = caps.get(3).and_then(|m| m.as_str().parse().ok());
+    let meridiem = caps
+        .get(4)
+        .map(|m| m.as_str().to_lowercase().replace('.', ""));
+
+    let hour = match meridiem.as_deref() {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some("am") => hour % 12,
+        Some(_) => hour % 12 + 12,
+        None if minute.is_none() => return None,
+        None => hour,
+    };
+
+    NaiveTime::from_hms_opt(hour, minute.unwrap_or(0), 0)
+}
+
+fn parse_label(text: &str) -> Option<String> {
+    if let Some(caps) = LABEL_FOR_RE.captures(text) {
+        let label = caps[1].trim();
+        if !label.is_empty() && DURATION_RE.find(label).is_none_or(|m| m.as_str() != label) {
+            return Some(label.to_string());
+        }
+    }
+
+    let caps = LABEL_PREFIX_RE.captures(text)?;
+    let word = caps[1].to_lowercase();
+    (!NOT_LABELS.contains(&word.as_str())).then_some(word)
+}
+
+/// Compact display form: 5400 → "1h 30m", 45 → "45s".
+pub fn format_duration(seconds: u64) -> String {
+    let (h, m, s) = (seconds / 3600, (seconds % 3600) / 60, seconds % 60);
+    let parts: Vec<String> = [(h, "h"), (m, "m"), (s, "s")]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, unit)| format!("{}{}", n, unit))
+        .collect();
+
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seconds(query: &str) -> Option<u64> {
+        match parse_timer(query)?.target {
+            TimerTarget::Duration(s) => Some(s),
+            TimerTarget::Until(_) => None,
+        }
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(seconds("timer 5m"), Some(300));
+        assert_eq!(seconds("set a timer for 1h 30 min"), Some(5400));
+        assert_eq!(seconds("timer 1h30m"), Some(5400));
+        assert_eq!(seconds("timer for half an hour"), Some(1800));
+        assert_eq!(seconds("90 second timer"), Some(90));
+        assert_eq!(seconds("kitchen timer reviews"), None);
+        assert_eq!(seconds("5 minutes"), None);
+    }
+
+    #[test]
+    fn labels() {
+        let spec = parse_timer("set a pasta timer for 10 minutes").unwrap();
+        assert_eq!(spec.label.as_deref(), Some("pasta"));
+
+        let spec = parse_timer("timer 10 min for the eggs").unwrap();
+        assert_eq!(spec.label.as_deref(), Some("eggs"));
+
+        let spec = parse_timer("set a timer for 10 minutes").unwrap();
+        assert_eq!(spec.label, None);
+    }
+
+    #[test]
+    fn clock_targets_resolve_to_next_occurrence() {
+        let spec = parse_timer("countdown to 17:00").unwrap();
+        assert_eq!(
+            spec.target,
+            TimerTarget::Until(NaiveTime::from_hms_opt(17, 0, 0).unwrap())
+        );
+
+        // 15:30 UTC is 17:30 at UTC+2, so 17:00 local is tomorrow
+        let now = Utc.with_ymd_and_hms(2025, 1, 4, 15, 30, 0).unwrap();
+        let timer = spec.resolve(now, 120);
+        assert_eq!(timer.seconds, 23 * 3600 + 30 * 60);
+
+        // ...but still ahead in UTC
+        assert_eq!(spec.resolve(now, 0).seconds, 90 * 60);
+
+        let spec = parse_timer("timer until 5:15 pm").unwrap();
+        assert_eq!(
+            spec.target,
+            TimerTarget::Until(NaiveTime::from_hms_opt(17, 15, 0).unwrap())
+        );
+        assert!(parse_timer("countdown to 5").is_none());
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(format_duration(5400), "1h 30m");
+        assert_eq!(format_duration(45), "45s");
+        assert_eq!(format_duration(3605), "1h 5s");
+    }
+}
@@ -8,9 +8,13 @@ pub struct AppConfig {
     // PostgreSQL
     pub database_url: String,
 
-    // Meilisearch
+    // Search backend
+    /// "meilisearch" or "tantivy" (embedded, no server needed)
+    pub search_backend: String,
     pub meili_url: String,
     pub meili_api_key: String,
+    /// Where the tantivy backend keeps its indexes
+    pub tantivy_index_dir: String,
 
     // Gemini
     pub gemini_api_key: String,
@@ -18,8 +22,58 @@ pub struct AppConfig {
     // Auth
     pub jwt_secret: String,
 
+    // Instant answers
+    /// JSON exchange-rate file loaded into `currency_rates` at startup
+    pub currency_rates_file: Option<String>,
+    /// Directory holding wiktextract dumps for the definitions import
+    pub definitions_dir: Option<String>,
+
     // Crawler
     pub crawler: CrawlerConfig,
+
+    // Intent classification
+    pub classifier: ClassifierConfig,
+
+    // Ranking
+    pub search: SearchConfig,
+    pub embedding: EmbeddingConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchConfig {
+    /// Age at which a document loses half its recency weight (0 disables decay)
+    pub recency_half_life_days: f64,
+    /// Largest share of the relevance score age can take away (0.0–1.0)
+    pub recency_weight: f64,
+    /// How often the spelling dictionary is rebuilt from the corpus (0 disables correction)
+    pub spelling_rebuild_secs: u64,
+    /// How often the autocomplete index is rebuilt (0 disables suggestions)
+    pub suggest_rebuild_secs: u64,
+    /// Most index outbox entries pushed to the search backend at once
+    pub outbox_batch_size: usize,
+    /// How long the outbox dispatcher sleeps when there is nothing to push
+    pub outbox_poll_ms: u64,
+    /// Failed pushes of an entry before it is kept as failed
+    pub outbox_max_attempts: u32,
+    /// Rebuild and swap in the search indexes at startup when their
+    /// settings are outdated
+    pub auto_reindex: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct EmbeddingConfig {
+    /// "gemini", "hash" (deterministic and offline, for tests) or "none"
+    pub provider: String,
+    /// Share of a hybrid search score that comes from vector similarity (0.0–1.0)
+    pub semantic_ratio: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClassifierConfig {
+    /// Rule confidence below which the LLM is consulted (0.0 disables it)
+    pub llm_threshold: f32,
+    /// How long a search may wait on the LLM before keeping the rule result
+    pub llm_timeout_ms: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -36,8 +90,17 @@ pub struct CrawlerConfig {
     pub user_agent: String,
     /// Whether to discover and enqueue links from crawled pages
     pub discover_links: bool,
-    /// Maximum depth for link discovery (0 = don't follow links from discovered pages)
+    /// Deepest queue entries whose links are followed; seeds are depth 0
+    /// (0 = don't follow links from discovered pages)
     pub max_link_depth: u32,
+    /// How long a site's robots.txt rules are reused before refetching
+    pub robots_cache_secs: u64,
+    /// Whether indexed pages are fetched again to pick up changes
+    pub recrawl: bool,
+    /// Shortest wait between fetches of a page that keeps changing
+    pub recrawl_min_secs: u64,
+    /// Longest wait between fetches of a page that never changes
+    pub recrawl_max_secs: u64,
     /// Whether the crawler is enabled
     pub enabled: bool,
 }
@@ -46,6 +109,10 @@ impl AppConfig {
     /// Load configuration from environment variables.
     /// Panics if required variables are missing — fail fast at startup.
     pub fn from_env() -> Self {
+        let search_backend = std::env::var("SEARCH_BACKEND")
+            .unwrap_or_else(|_| "meilisearch".into())
+            .to_lowercase();
+
         Self {
             host: std::env::var("HOST").unwrap_or_else(|_| "0.0.0.0".into()),
             port: std::env::var("PORT")
@@ -55,9 +122,18 @@ impl AppConfig {
             database_url: std::env::var("DATABASE_URL").expect("DATABASE_URL must be set"),
             meili_url: std::env::var("MEILI_URL")
                 .unwrap_or_else(|_| "http://localhost:7700".into()),
-            meili_api_key: std::env::var("MEILI_MASTER_KEY").expect("MEILI_MASTER_KEY must be set"),
+            meili_api_key: if search_backend == "meilisearch" {
+                std::env::var("MEILI_MASTER_KEY").expect("MEILI_MASTER_KEY must be set")
+            } else {
+                std::env::var("MEILI_MASTER_KEY").unwrap_or_default()
+            },
+            tantivy_index_dir: std::env::var("TANTIVY_INDEX_DIR")
+                .unwrap_or_else(|_| "data/index".into()),
+            search_backend,
             gemini_api_key: std::env::var("GEMINI_API_KEY").expect("GEMINI_API_KEY must be set"),
             jwt_secret: std::env::var("JWT_SECRET").expect("JWT_SECRET must be set"),
+            currency_rates_file: std::env::var("CURRENCY_RATES_FILE").ok(),
+            definitions_dir: std::env::var("DEFINITIONS_DIR").ok(),
             crawler: CrawlerConfig {
                 workers: std::env::var("CRAWLER_WORKERS")
                     .unwrap_or_else(|_| "2".into())
@@ -86,11 +162,80 @@ impl AppConfig {
                     .unwrap_or_else(|_| "1".into())
                     .parse()
                     .unwrap_or(1),
+                robots_cache_secs: std::env::var("CRAWLER_ROBOTS_CACHE_SECS")
+                    .unwrap_or_else(|_| "86400".into())
+                    .parse()
+                    .unwrap_or(86400),
+                recrawl: std::env::var("CRAWLER_RECRAWL")
+                    .unwrap_or_else(|_| "true".into())
+                    .parse()
+                    .unwrap_or(true),
+                recrawl_min_secs: std::env::var("CRAWLER_RECRAWL_MIN_SECS")
+                    .unwrap_or_else(|_| "3600".into())
+                    .parse()
+                    .unwrap_or(3600),
+                recrawl_max_secs: std::env::var("CRAWLER_RECRAWL_MAX_SECS")
+                    .unwrap_or_else(|_| "2592000".into()) // 30 days
+                    .parse()
+                    .unwrap_or(2_592_000),
                 enabled: std::env::var("CRAWLER_ENABLED")
                     .unwrap_or_else(|_| "true".into())
                     .parse()
                     .unwrap_or(true),
             },
+            classifier: ClassifierConfig {
+                llm_threshold: std::env::var("CLASSIFIER_LLM_THRESHOLD")
+                    .unwrap_or_else(|_| "0.6".into())
+                    .parse()
+                    .unwrap_or(0.6),
+                llm_timeout_ms: std::env::var("CLASSIFIER_LLM_TIMEOUT_MS")
+                    .unwrap_or_else(|_| "1500".into())
+                    .parse()
+                    .unwrap_or(1500),
+            },
+            search: SearchConfig {
+                recency_half_life_days: std::env::var("SEARCH_RECENCY_HALF_LIFE_DAYS")
+                    .unwrap_or_else(|_| "0".into())
+                    .parse()
+                    .unwrap_or(0.0),
+                recency_weight: std::env::var("SEARCH_RECENCY_WEIGHT")
+                    .unwrap_or_else(|_| "0.3".into())
+                    .parse()
+                    .unwrap_or(0.3),
+                spelling_rebuild_secs: std::env::var("SEARCH_SPELLING_REBUILD_SECS")
+                    .unwrap_or_else(|_| "3600".into())
+                    .parse()
+                    .unwrap_or(3600),
+                suggest_rebuild_secs: std::env::var("SEARCH_SUGGEST_REBUILD_SECS")
+                    .unwrap_or_else(|_| "600".into())
+                    .parse()
+                    .unwrap_or(600),
+                outbox_batch_size: std::env::var("SEARCH_OUTBOX_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".into())
+                    .parse()
+                    .unwrap_or(100),
+                outbox_poll_ms: std::env::var("SEARCH_OUTBOX_POLL_MS")
+                    .unwrap_or_else(|_| "1000".into())
+                    .parse()
+                    .unwrap_or(1000),
+                outbox_max_attempts: std::env::var("SEARCH_OUTBOX_MAX_ATTEMPTS")
+                    .unwrap_or_else(|_| "10".into())
+                    .parse()
+                    .unwrap_or(10),
+                auto_reindex: std::env::var("SEARCH_AUTO_REINDEX")
+                    .unwrap_or_else(|_| "true".into())
+                    .parse()
+                    .unwrap_or(true),
+            },
+            embedding: EmbeddingConfig {
+                provider: std::env::var("EMBEDDING_PROVIDER")
+                    .unwrap_or_else(|_| "gemini".into())
+                    .to_lowercase(),
+                semantic_ratio: std::env::var("SEARCH_SEMANTIC_RATIO")
+                    .unwrap_or_else(|_| "0.5".into())
+                    .parse()
+                    .unwrap_or(0.5),
+            },
         }
     }
 }
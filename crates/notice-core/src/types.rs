@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -26,10 +26,95 @@ pub struct SearchResult {
 pub struct SearchResponse {
     pub query: String,
     pub results: Vec<SearchResult>,
-    pub total: usize,
+    /// Matching documents; None for semantic search, which has no count
+    pub total: Option<usize>,
     pub instant_answer: Option<InstantAnswer>,
     pub ai_answer: Option<String>,
     pub discovery_triggered: bool,
+    /// Set for navigational queries ("!gh tokio", "docs.rs"): the client
+    /// should go straight to this URL.
+    pub redirect: Option<String>,
+    /// Operator breakdown of the query, for searches that went to the index
+    pub parsed_query: Option<ParsedQuery>,
+    /// Result counts per domain and status, for "narrow to this site" chips
+    pub facets: Option<SearchFacets>,
+    /// Set when the query found nothing and these are the results for its
+    /// spelling correction instead ("Showing results for ...")
+    pub corrected_query: Option<String>,
+    /// A spelling correction the user may want to try instead
+    pub did_you_mean: Option<String>,
+    /// The search backend is down; results come from PostgreSQL full-text
+    /// search (no facets, plainer ranking)
+    pub degraded: bool,
+}
+
+/// Facet distributions for a search, most frequent values first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchFacets {
+    pub domains: Vec<FacetCount>,
+    pub statuses: Vec<FacetCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// How a search query was interpreted: free text plus operators like
+/// `site:docs.rs`, `-term`, `"exact phrase"`, `status:summarized`,
+/// `after:2025-01-01`. Echoed back so users can see what was searched.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParsedQuery {
+    pub terms: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phrases: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sites: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_sites: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<NaiveDate>,
+}
+
+/// Sites match with and without "www.": every search backend expands
+/// `site:` operators through this.
+pub fn with_www(sites: &[String]) -> Vec<String> {
+    sites
+        .iter()
+        .flat_map(|s| [s.clone(), format!("www.{}", s)])
+        .collect()
+}
+
+/// Response of `GET /api/suggest`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestResponse {
+    pub query: String,
+    pub suggestions: Vec<QuerySuggestion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuerySuggestion {
+    pub text: String,
+    pub source: SuggestionSource,
+}
+
+/// Where a suggestion came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionSource {
+    /// The user's own earlier searches
+    History,
+    /// Searches many people have made
+    Popular,
+    /// An indexed document's title
+    Title,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,10 +125,99 @@ pub struct SummaryResponse {
     pub cached: bool,
 }
 
+/// A widget answer shown instead of (or above) web results.
+///
+/// Serialized with a `type` discriminator, e.g.
+/// `{"type": "calculation", "expression": "2+2", ...}`. Clients should
+/// ignore types they don't know so new widgets can ship independently.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct InstantAnswer {
-    pub answer_type: String,
-    pub value: String,
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InstantAnswer {
+    Calculation {
+        expression: String,
+        result: f64,
+        /// Display form (exact digits for integers).
+        formatted: String,
+        /// True when the result is an exact integer.
+        exact: bool,
+    },
+    Conversion {
+        /// "length", "temperature", "currency", ...
+        dimension: String,
+        amount: f64,
+        from: UnitRef,
+        to: UnitRef,
+        result: f64,
+        formatted: String,
+        /// Present for currency conversions.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rate: Option<ExchangeRate>,
+    },
+    Definition {
+        term: String,
+        /// "wiktionary", "generated", ...
+        source: String,
+        entries: Vec<DefinitionEntry>,
+    },
+    Timer {
+        kind: TimerKind,
+        seconds: u64,
+        /// Absolute deadline, so the countdown survives a slow response.
+        target: DateTime<Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        /// Human-readable form ("1h 30m", "17:00 (in 2h 5m)").
+        display: String,
+    },
+    /// A widget recognized the query but couldn't answer it.
+    Error {
+        /// Which widget failed: "calculation", "conversion", ...
+        widget: String,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        input: Option<String>,
+        /// Character offset of the problem in `input`, if known.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        position: Option<usize>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitRef {
+    pub symbol: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeRate {
+    pub rate: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefinitionEntry {
+    pub part_of_speech: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pronunciation: Option<String>,
+    pub senses: Vec<DefinitionSense>,
+    #[serde(default)]
+    pub synonyms: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefinitionSense {
+    pub gloss: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerKind {
+    /// Fixed duration ("timer 5m").
+    Duration,
+    /// Wall-clock deadline ("countdown to 17:00").
+    Until,
 }
 
 // ─── Auth API ───
@@ -91,5 +265,174 @@ pub struct ScrapedPage {
     pub text_content: String,
     /// Raw HTML for link extraction. Not stored in the database.
     pub raw_html: String,
+    /// Publication date from the page's metadata, if it declares one
+    pub published_at: Option<DateTime<Utc>>,
+    /// `ETag` and `Last-Modified` response headers, for conditional refetches
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
     pub scraped_at: DateTime<Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Serialize `answer`, check it against `expected`, and check it reads
+    /// back to the same JSON.
+    fn assert_json(answer: InstantAnswer, expected: serde_json::Value) {
+        let value = serde_json::to_value(&answer).unwrap();
+        assert_eq!(value, expected);
+        let parsed: InstantAnswer = serde_json::from_value(value).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), expected);
+    }
+
+    #[test]
+    fn calculation_json() {
+        assert_json(
+            InstantAnswer::Calculation {
+                expression: "2 + 2".into(),
+                result: 4.0,
+                formatted: "4".into(),
+                exact: true,
+            },
+            json!({
+                "type": "calculation",
+                "expression": "2 + 2",
+                "result": 4.0,
+                "formatted": "4",
+                "exact": true,
+            }),
+        );
+    }
+
+    #[test]
+    fn conversion_json() {
+        let unit = |symbol: &str, name: &str| UnitRef {
+            symbol: symbol.into(),
+            name: name.into(),
+        };
+        assert_json(
+            InstantAnswer::Conversion {
+                dimension: "length".into(),
+                amount: 5.0,
+                from: unit("km", "kilometer"),
+                to: unit("mi", "mile"),
+                result: 3.106856,
+                formatted: "3.106856".into(),
+                rate: None,
+            },
+            json!({
+                "type": "conversion",
+                "dimension": "length",
+                "amount": 5.0,
+                "from": { "symbol": "km", "name": "kilometer" },
+                "to": { "symbol": "mi", "name": "mile" },
+                "result": 3.106856,
+                "formatted": "3.106856",
+            }),
+        );
+        assert_json(
+            InstantAnswer::Conversion {
+                dimension: "currency".into(),
+                amount: 10.0,
+                from: unit("USD", "US dollar"),
+                to: unit("EUR", "euro"),
+                result: 9.2,
+                formatted: "9.20".into(),
+                rate: Some(ExchangeRate {
+                    rate: 0.92,
+                    updated_at: "2025-01-04T00:00:00Z".parse().unwrap(),
+                }),
+            },
+            json!({
+                "type": "conversion",
+                "dimension": "currency",
+                "amount": 10.0,
+                "from": { "symbol": "USD", "name": "US dollar" },
+                "to": { "symbol": "EUR", "name": "euro" },
+                "result": 9.2,
+                "formatted": "9.20",
+                "rate": { "rate": 0.92, "updated_at": "2025-01-04T00:00:00Z" },
+            }),
+        );
+    }
+
+    #[test]
+    fn definition_json() {
+        assert_json(
+            InstantAnswer::Definition {
+                term: "crate".into(),
+                source: "wiktionary".into(),
+                entries: vec![DefinitionEntry {
+                    part_of_speech: "noun".into(),
+                    pronunciation: None,
+                    senses: vec![DefinitionSense {
+                        gloss: "A box for transport.".into(),
+                        examples: vec![],
+                    }],
+                    synonyms: vec!["box".into()],
+                }],
+            },
+            json!({
+                "type": "definition",
+                "term": "crate",
+                "source": "wiktionary",
+                "entries": [{
+                    "part_of_speech": "noun",
+                    "senses": [{ "gloss": "A box for transport." }],
+                    "synonyms": ["box"],
+                }],
+            }),
+        );
+    }
+
+    #[test]
+    fn timer_json() {
+        assert_json(
+            InstantAnswer::Timer {
+                kind: TimerKind::Duration,
+                seconds: 300,
+                target: "2025-01-04T12:05:00Z".parse().unwrap(),
+                label: Some("tea".into()),
+                display: "5m".into(),
+            },
+            json!({
+                "type": "timer",
+                "kind": "duration",
+                "seconds": 300,
+                "target": "2025-01-04T12:05:00Z",
+                "label": "tea",
+                "display": "5m",
+            }),
+        );
+    }
+
+    #[test]
+    fn error_json() {
+        assert_json(
+            InstantAnswer::Error {
+                widget: "calculation".into(),
+                message: "Unexpected end of expression".into(),
+                input: Some("2 +".into()),
+                position: Some(3),
+            },
+            json!({
+                "type": "error",
+                "widget": "calculation",
+                "message": "Unexpected end of expression",
+                "input": "2 +",
+                "position": 3,
+            }),
+        );
+        assert_json(
+            InstantAnswer::Error {
+                widget: "conversion".into(),
+                message: "No rate".into(),
+                input: None,
+                position: None,
+            },
+            json!({ "type": "error", "widget": "conversion", "message": "No rate" }),
+        );
+    }
+}
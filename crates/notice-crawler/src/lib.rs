@@ -1,8 +1,12 @@
 pub mod discovery;
 pub mod links;
+pub mod passages;
+pub mod published;
 pub mod rate_limiter;
+mod recrawl;
 pub mod robots;
 pub mod scraper_engine;
+pub mod sitemap;
 pub mod worker;
 
 pub use worker::{CrawlerHandle, start_crawler};
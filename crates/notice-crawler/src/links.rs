@@ -85,7 +85,7 @@ pub fn extract_links(html: &str, base_url: &str) -> Vec<String> {
 }
 
 /// URLs we should never crawl (assets, auth pages, etc.)
-fn should_skip_url(url: &str) -> bool {
+pub(crate) fn should_skip_url(url: &str) -> bool {
     let skip_patterns = [
         "/login",
         "/signup",
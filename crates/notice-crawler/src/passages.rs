@@ -0,0 +1,71 @@
+use notice_ai::embedding::{self, EmbedKind, Embedder};
+use notice_db::chunks::{NewChunk, PendingChunk};
+use notice_db::documents::DocumentRow;
+use notice_search::chunk;
+use sqlx::PgPool;
+
+/// Passages past this are not indexed, so one huge page can't flood the
+/// index or the embedding quota.
+const MAX_PASSAGES: usize = 200;
+
+/// Split a document into passages, store them in PostgreSQL (which queues
+/// them for the passages index), and embed them when semantic search is
+/// on. Replaces whatever passages the document had. Returns how many were
+/// stored.
+pub async fn index_document(
+    db: &PgPool,
+    embedder: Option<&Embedder>,
+    doc: &DocumentRow,
+) -> Result<usize, notice_core::Error> {
+    let mut passages = chunk::split(&doc.raw_content);
+    passages.truncate(MAX_PASSAGES);
+
+    let new_chunks: Vec<NewChunk> = passages
+        .into_iter()
+        .map(|p| NewChunk {
+            heading: p.heading,
+            content: p.content,
+            start_offset: p.start as i32,
+            end_offset: p.end as i32,
+        })
+        .collect();
+    let rows = notice_db::chunks::replace(db, doc.id, &new_chunks).await?;
+    let stored = rows.len();
+
+    if let Some(embedder) = embedder {
+        let pending: Vec<PendingChunk> = rows
+            .into_iter()
+            .map(|row| PendingChunk {
+                id: row.id,
+                title: doc.title.clone(),
+                heading: row.heading,
+                content: row.content,
+            })
+            .collect();
+        embed_chunks(db, embedder, &pending).await?;
+    }
+
+    Ok(stored)
+}
+
+/// Embed passages in one batch and store their vectors.
+pub async fn embed_chunks(
+    db: &PgPool,
+    embedder: &Embedder,
+    chunks: &[PendingChunk],
+) -> Result<usize, notice_core::Error> {
+    if chunks.is_empty() {
+        return Ok(0);
+    }
+
+    let texts: Vec<String> = chunks
+        .iter()
+        .map(|c| embedding::passage_input(c.title.as_deref(), c.heading.as_deref(), &c.content))
+        .collect();
+    let vectors = embedder.embed(&texts, EmbedKind::Document).await?;
+
+    for (chunk, vector) in chunks.iter().zip(&vectors) {
+        notice_db::chunks::set_embedding(db, chunk.id, embedder.model(), vector).await?;
+    }
+    Ok(vectors.len())
+}
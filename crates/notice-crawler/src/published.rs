@@ -0,0 +1,125 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use scraper::{Html, Selector};
+
+/// Meta tags that carry a publication date, most reliable first.
+const META_SELECTORS: &[&str] = &[
+    r#"meta[property="article:published_time"]"#,
+    r#"meta[itemprop="datePublished"]"#,
+    r#"meta[name="date"]"#,
+    r#"meta[name="publish-date"]"#,
+    r#"meta[name="pubdate"]"#,
+    r#"meta[name="DC.date.issued"]"#,
+    r#"meta[name="dcterms.created"]"#,
+];
+
+/// Extract a page's publication date from meta tags, JSON-LD
+/// (`datePublished`) or a `<time pubdate>` / `<time itemprop>` element.
+/// Dates in the future or before the web existed are ignored.
+pub fn extract_published_date(document: &Html) -> Option<DateTime<Utc>> {
+    meta_date(document)
+        .or_else(|| json_ld_date(document))
+        .or_else(|| time_element_date(document))
+        .filter(|date| is_plausible(*date))
+}
+
+fn meta_date(document: &Html) -> Option<DateTime<Utc>> {
+    META_SELECTORS.iter().find_map(|selector| {
+        let selector = Selector::parse(selector).ok()?;
+        document
+            .select(&selector)
+            .filter_map(|el| el.value().attr("content"))
+            .find_map(parse_date)
+    })
+}
+
+fn json_ld_date(document: &Html) -> Option<DateTime<Utc>> {
+    let selector = Selector::parse(r#"script[type="application/ld+json"]"#).ok()?;
+    document.select(&selector).find_map(|el| {
+        let json: serde_json::Value = serde_json::from_str(&el.text().collect::<String>()).ok()?;
+        find_date_published(&json)
+    })
+}
+
+/// `datePublished` anywhere in a JSON-LD tree (it is often nested in
+/// `@graph` arrays).
+fn find_date_published(value: &serde_json::Value) -> Option<DateTime<Utc>> {
+    match value {
+        serde_json::Value::Object(map) => map
+            .get("datePublished")
+            .and_then(|v| v.as_str())
+            .and_then(parse_date)
+            .or_else(|| map.values().find_map(find_date_published)),
+        serde_json::Value::Array(items) => items.iter().find_map(find_date_published),
+        _ => None,
+    }
+}
+
+fn time_element_date(document: &Html) -> Option<DateTime<Utc>> {
+    let selector =
+        Selector::parse(r#"time[pubdate][datetime], time[itemprop="datePublished"][datetime]"#)
+            .ok()?;
+    document
+        .select(&selector)
+        .filter_map(|el| el.value().attr("datetime"))
+        .find_map(parse_date)
+}
+
+/// RFC 3339 ("2025-01-04T10:00:00+02:00"), naive date-times (taken as
+/// UTC) and plain dates.
+fn parse_date(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+
+    if let Ok(date) = DateTime::parse_from_rfc3339(raw) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S"] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(raw, format) {
+            return Some(Utc.from_utc_datetime(&date));
+        }
+    }
+
+    // Plain date, possibly followed by something we don't understand
+    let date = NaiveDate::parse_from_str(raw.get(..10)?, "%Y-%m-%d").ok()?;
+    Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?))
+}
+
+fn is_plausible(date: DateTime<Utc>) -> bool {
+    let earliest = Utc.with_ymd_and_hms(1991, 1, 1, 0, 0, 0).unwrap();
+    date >= earliest && date <= Utc::now() + Duration::days(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn published(html: &str) -> Option<String> {
+        extract_published_date(&Html::parse_document(html)).map(|d| d.to_rfc3339())
+    }
+
+    #[test]
+    fn extracts_dates() {
+        assert_eq!(
+            published(
+                r#"<head><meta property="article:published_time" content="2024-03-05T10:00:00+01:00"></head>"#
+            )
+            .as_deref(),
+            Some("2024-03-05T09:00:00+00:00")
+        );
+        assert_eq!(
+            published(
+                r#"<script type="application/ld+json">{"@graph": [{"@type": "Article", "datePublished": "2023-11-20"}]}</script>"#
+            )
+            .as_deref(),
+            Some("2023-11-20T00:00:00+00:00")
+        );
+        assert_eq!(
+            published(r#"<time pubdate datetime="2022-07-01T08:30">July 1</time>"#).as_deref(),
+            Some("2022-07-01T08:30:00+00:00")
+        );
+        assert_eq!(
+            published(r#"<meta name="date" content="3024-01-01">"#),
+            None
+        );
+        assert_eq!(published("<p>No dates here</p>"), None);
+    }
+}
@@ -2,6 +2,10 @@ use std::collections::HashMap;
 use tokio::sync::Mutex;
 use tokio::time::{Duration, Instant};
 
+/// Longest robots.txt `Crawl-delay` honoured; a worker waits it out, so
+/// larger values would stall the crawler.
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(30);
+
 /// Per-domain rate limiter. Ensures we wait at least `delay` between
 /// requests to the same domain.
 pub struct DomainRateLimiter {
@@ -17,16 +21,20 @@ impl DomainRateLimiter {
         }
     }
 
-    /// Wait until it's safe to make a request to this domain.
+    /// Wait until it's safe to make a request to this domain, spacing
+    /// requests by the site's `crawl_delay` when it is longer than ours.
     /// Returns immediately if enough time has passed.
-    pub async fn wait_for_domain(&self, domain: &str) {
+    pub async fn wait_for_domain(&self, domain: &str, crawl_delay: Option<Duration>) {
+        let delay = crawl_delay
+            .map(|d| d.min(MAX_CRAWL_DELAY))
+            .map_or(self.delay, |d| d.max(self.delay));
         let mut map = self.last_request.lock().await;
         let now = Instant::now();
 
         if let Some(last) = map.get(domain) {
             let elapsed = now.duration_since(*last);
-            if elapsed < self.delay {
-                let wait = self.delay - elapsed;
+            if elapsed < delay {
+                let wait = delay - elapsed;
                 drop(map); // Release lock during sleep
                 tokio::time::sleep(wait).await;
                 let mut map = self.last_request.lock().await;
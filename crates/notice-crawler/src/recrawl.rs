@@ -0,0 +1,185 @@
+use notice_db::recrawl::DueDocument;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use tokio::time::Duration;
+
+use crate::scraper_engine::{self, Validators};
+use crate::worker::{self, CrawlerContext};
+
+/// How long to wait before looking for due documents again once none are left.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// Documents claimed at a time.
+const BATCH_SIZE: i64 = 20;
+/// How long a claimed document is held back from other claims, in case
+/// this process dies before recording the recrawl.
+const LEASE_SECS: i64 = 3600;
+
+/// Refetch indexed documents as they come due, until the crawler stops.
+pub(crate) async fn run(ctx: Arc<CrawlerContext>) {
+    tracing::info!("Recrawl scheduler started");
+
+    loop {
+        let due = match notice_db::recrawl::claim_due(&ctx.db, BATCH_SIZE, LEASE_SECS).await {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to claim documents to recrawl");
+                vec![]
+            }
+        };
+
+        for doc in &due {
+            if ctx.cancel.is_cancelled() {
+                break;
+            }
+            recrawl_one(&ctx, doc).await;
+        }
+
+        // A full batch means more may be due already
+        if (due.len() as i64) < BATCH_SIZE {
+            tokio::select! {
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                _ = ctx.cancel.cancelled() => break,
+            }
+        } else if ctx.cancel.is_cancelled() {
+            break;
+        }
+    }
+
+    tracing::info!("Recrawl scheduler stopped");
+}
+
+/// Recrawl a document and schedule its next visit.
+async fn recrawl_one(ctx: &CrawlerContext, doc: &DueDocument) {
+    match recrawl(ctx, doc).await {
+        Ok(changed) => {
+            ctx.stats.pages_recrawled.fetch_add(1, Ordering::Relaxed);
+            if changed {
+                ctx.stats.pages_changed.fetch_add(1, Ordering::Relaxed);
+            }
+
+            let interval = next_interval(
+                doc.interval_secs,
+                changed,
+                ctx.config.recrawl_min_secs,
+                ctx.config.recrawl_max_secs,
+            );
+            tracing::debug!(url = %doc.url, changed, interval, "Recrawled");
+            if let Err(e) = notice_db::recrawl::complete(&ctx.db, doc.document_id, interval).await {
+                tracing::error!(doc_id = %doc.document_id, error = %e, "Failed to record recrawl");
+            }
+        }
+        Err(e) => {
+            tracing::warn!(url = %doc.url, error = %e, "Recrawl failed");
+            if let Err(e) = notice_db::recrawl::fail(&ctx.db, doc.document_id, &e.to_string()).await
+            {
+                tracing::error!(doc_id = %doc.document_id, error = %e, "Failed to record recrawl");
+            }
+        }
+    }
+}
+
+/// Fetch a document again, sending the validators from the last fetch.
+/// Only a change to the extracted text updates, re-summarizes and
+/// re-indexes it. Returns whether it changed.
+async fn recrawl(ctx: &CrawlerContext, doc: &DueDocument) -> Result<bool, notice_core::Error> {
+    let parsed_url = url::Url::parse(&doc.url)
+        .map_err(|e| notice_core::Error::Crawler(format!("Invalid URL {}: {}", doc.url, e)))?;
+
+    let robots = ctx.robots.rules_for(&parsed_url).await;
+    if !robots.allows(&parsed_url) {
+        return Err(notice_core::Error::Crawler(format!(
+            "Blocked by robots.txt: {}",
+            doc.url
+        )));
+    }
+
+    let domain = parsed_url.host_str().unwrap_or_default().to_string();
+    ctx.rate_limiter
+        .wait_for_domain(&domain, robots.crawl_delay)
+        .await;
+
+    let validators = Validators {
+        etag: doc.etag.as_deref(),
+        last_modified: doc.last_modified.as_deref(),
+    };
+    let Some(page) = scraper_engine::scrape_if_modified(
+        &ctx.http,
+        &doc.url,
+        ctx.config.max_content_size,
+        validators,
+    )
+    .await?
+    else {
+        return Ok(false);
+    };
+
+    if notice_db::recrawl::content_hash(&page.text_content) == doc.content_hash {
+        // Validators can change without the text changing, e.g. on markup edits
+        notice_db::recrawl::set_validators(
+            &ctx.db,
+            doc.document_id,
+            page.etag.as_deref(),
+            page.last_modified.as_deref(),
+        )
+        .await?;
+        return Ok(false);
+    }
+
+    // Stores the new validators too, so they never get ahead of the content
+    let quality_score =
+        worker::calculate_quality_score(&doc.url, page.title.as_deref(), &page.text_content);
+    let updated =
+        notice_db::documents::update_content(&ctx.db, doc.document_id, &page, quality_score)
+            .await?;
+
+    tracing::info!(doc_id = %updated.id, url = %doc.url, "Document changed, re-indexing");
+
+    if let Err(e) = crate::passages::index_document(&ctx.db, ctx.embedder.as_ref(), &updated).await
+    {
+        tracing::warn!(doc_id = %updated.id, error = %e, "Failed to index passages");
+    }
+    worker::summarize(ctx, updated.id, &page.text_content).await;
+
+    Ok(true)
+}
+
+/// Halve the interval after a change and grow it by half after a fetch
+/// that found nothing new, so pages settle near how often they change.
+fn next_interval(current_secs: i32, changed: bool, min_secs: u64, max_secs: u64) -> i32 {
+    let current = current_secs.max(1) as u64;
+    let next = if changed {
+        current / 2
+    } else {
+        current + current / 2
+    };
+    next.min(max_secs).max(min_secs).min(i32::MAX as u64) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: u64 = 3600;
+    const DAY: u64 = 24 * HOUR;
+
+    #[test]
+    fn interval_adapts_to_change_frequency_within_bounds() {
+        let day = DAY as i32;
+        assert_eq!(next_interval(day, true, HOUR, 30 * DAY), day / 2);
+        assert_eq!(next_interval(day, false, HOUR, 30 * DAY), day + day / 2);
+
+        // Clamped to the configured range
+        assert_eq!(
+            next_interval(HOUR as i32, true, HOUR, 30 * DAY),
+            HOUR as i32
+        );
+        assert_eq!(next_interval(25 * day, false, HOUR, 30 * DAY), 30 * day);
+
+        // A page that keeps not changing backs off to the maximum
+        let mut interval = day;
+        for _ in 0..20 {
+            interval = next_interval(interval, false, HOUR, 30 * DAY);
+        }
+        assert_eq!(interval, 30 * day);
+    }
+}
@@ -1,139 +1,405 @@
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
-/// In-memory robots.txt cache. Stores parsed rules per domain.
+/// RFC 9309 asks parsers to read at least the first 500 KiB.
+const MAX_ROBOTS_BYTES: usize = 500 * 1024;
+/// How soon an unreachable robots.txt (5xx, network error) is tried
+/// again. Everything on the site is disallowed meanwhile.
+const UNREACHABLE_TTL: Duration = Duration::from_secs(600);
+/// Expired entries are swept once the cache grows past this.
+const MAX_CACHED_SITES: usize = 10_000;
+
+/// robots.txt rules per site (scheme, host and port), refetched after a TTL.
 pub struct RobotsChecker {
     client: Client,
-    /// domain → list of disallowed path prefixes
-    cache: RwLock<HashMap<String, Vec<String>>>,
-    user_agent: String,
+    cache: RwLock<HashMap<String, CachedRules>>,
+    /// Product token we match `User-agent` lines against, lowercased
+    product_token: String,
+    ttl: Duration,
+}
+
+struct CachedRules {
+    rules: Arc<RobotsRules>,
+    expires_at: Instant,
+}
+
+/// What a site's robots.txt says for our user agent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsRules {
+    /// Rules of every group for our agent, or for `*` if none names us
+    rules: Vec<Rule>,
+    /// Everything is off limits (robots.txt unreachable)
+    disallow_all: bool,
+    /// Minimum time between requests asked for by the matched group
+    pub crawl_delay: Option<Duration>,
+    /// `Sitemap:` URLs, which apply to every agent
+    pub sitemaps: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+#[derive(Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
 }
 
 impl RobotsChecker {
-    pub fn new(client: Client, user_agent: &str) -> Self {
+    pub fn new(client: Client, user_agent: &str, ttl: Duration) -> Self {
         Self {
             client,
             cache: RwLock::new(HashMap::new()),
-            user_agent: user_agent.to_string(),
+            product_token: product_token(user_agent),
+            ttl,
         }
     }
 
     /// Check if crawling the given URL is allowed by robots.txt.
     pub async fn is_allowed(&self, url: &str) -> bool {
-        let parsed = match url::Url::parse(url) {
-            Ok(u) => u,
-            Err(_) => return false,
-        };
-
-        let domain = match parsed.host_str() {
-            Some(h) => h.to_string(),
-            None => return false,
-        };
-
-        let path = parsed.path().to_string();
+        match url::Url::parse(url) {
+            Ok(parsed) => self.rules_for(&parsed).await.allows(&parsed),
+            Err(_) => false,
+        }
+    }
 
-        // Check cache first
+    /// Rules for the URL's site, fetching robots.txt if they are not
+    /// cached or have expired.
+    pub async fn rules_for(&self, url: &url::Url) -> Arc<RobotsRules> {
+        let site = url.origin().ascii_serialization();
         {
             let cache = self.cache.read().await;
-            if let Some(disallowed) = cache.get(&domain) {
-                return !disallowed.iter().any(|prefix| path.starts_with(prefix));
+            if let Some(cached) = cache.get(&site)
+                && cached.expires_at > Instant::now()
+            {
+                return cached.rules.clone();
             }
         }
 
-        // Fetch and parse robots.txt
-        let robots_url = format!("{}://{}/robots.txt", parsed.scheme(), domain);
-        let disallowed = self.fetch_and_parse(&robots_url).await;
-
-        let allowed = !disallowed.iter().any(|prefix| path.starts_with(prefix));
+        let robots_url = format!("{}/robots.txt", site);
+        let (rules, ttl) = match self.fetch_and_parse(&robots_url).await {
+            Some(rules) => (rules, self.ttl),
+            None => (RobotsRules::disallow_all(), self.ttl.min(UNREACHABLE_TTL)),
+        };
+        let rules = Arc::new(rules);
 
-        // Cache the result
-        {
-            let mut cache = self.cache.write().await;
-            cache.insert(domain, disallowed);
+        let mut cache = self.cache.write().await;
+        let now = Instant::now();
+        if cache.len() >= MAX_CACHED_SITES {
+            cache.retain(|_, cached| cached.expires_at > now);
         }
-
-        allowed
+        cache.insert(
+            site,
+            CachedRules {
+                rules: rules.clone(),
+                expires_at: now + ttl,
+            },
+        );
+        rules
     }
 
-    /// Fetch robots.txt and extract Disallow rules for our user agent.
-    async fn fetch_and_parse(&self, robots_url: &str) -> Vec<String> {
-        let response = match self.client.get(robots_url).send().await {
-            Ok(r) if r.status().is_success() => r,
-            _ => {
-                tracing::debug!(url = robots_url, "No robots.txt found — allowing all");
-                return vec![];
+    /// Fetch robots.txt and parse the rules for our user agent. A 4xx
+    /// means there are no rules; None if the file is unreachable (5xx,
+    /// 429 or a network error), in which case nothing may be crawled.
+    async fn fetch_and_parse(&self, robots_url: &str) -> Option<RobotsRules> {
+        let mut response = match self.client.get(robots_url).send().await {
+            Ok(r) => r,
+            Err(e) => {
+                tracing::debug!(url = robots_url, error = %e, "robots.txt unreachable — disallowing all");
+                return None;
             }
         };
 
-        let body = match response.text().await {
-            Ok(b) => b,
-            Err(_) => return vec![],
-        };
+        let status = response.status();
+        if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+            tracing::debug!(url = robots_url, %status, "robots.txt unreachable — disallowing all");
+            return None;
+        }
+        if !status.is_success() {
+            tracing::debug!(url = robots_url, %status, "No robots.txt found — allowing all");
+            return Some(RobotsRules::default());
+        }
 
-        self.parse_robots_txt(&body)
+        // Anything past the limit is ignored, so stop reading there
+        let mut body = Vec::new();
+        while body.len() < MAX_ROBOTS_BYTES {
+            match response.chunk().await.ok()? {
+                Some(chunk) => body.extend_from_slice(&chunk),
+                None => break,
+            }
+        }
+        body.truncate(MAX_ROBOTS_BYTES);
+        let body = String::from_utf8_lossy(&body);
+        Some(RobotsRules::parse(&body, &self.product_token))
     }
+}
 
-    /// Parse robots.txt content. Returns disallowed path prefixes.
-    /// Checks for our specific user agent first, falls back to *.
-    fn parse_robots_txt(&self, content: &str) -> Vec<String> {
-        let mut disallowed_star: Vec<String> = vec![];
-        let mut disallowed_specific: Vec<String> = vec![];
-        let mut current_agent = CurrentAgent::None;
+impl RobotsRules {
+    pub fn disallow_all() -> Self {
+        Self {
+            disallow_all: true,
+            ..Default::default()
+        }
+    }
 
-        let our_agent_lower = self
-            .user_agent
-            .split('/')
-            .next()
-            .unwrap_or("")
-            .to_lowercase();
+    /// Parse robots.txt for the agent with product token `agent` (lowercase).
+    /// Every group naming the agent applies, or every `*` group if none
+    /// does; `Sitemap:` lines are collected wherever they appear.
+    pub fn parse(content: &str, agent: &str) -> Self {
+        let mut groups: Vec<Group> = vec![];
+        let mut sitemaps = vec![];
+        // Consecutive User-agent lines share one group
+        let mut reading_agents = false;
 
         for line in content.lines() {
-            let line = line.trim();
-
-            // Skip comments and empty lines
-            if line.is_empty() || line.starts_with('#') {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
                 continue;
-            }
-
-            let (key, value) = match line.split_once(':') {
-                Some((k, v)) => (k.trim().to_lowercase(), v.trim().to_string()),
-                None => continue,
             };
+            let value = value.trim();
 
-            match key.as_str() {
+            match key.trim().to_lowercase().as_str() {
                 "user-agent" => {
-                    let agent = value.to_lowercase();
-                    if agent == "*" {
-                        current_agent = CurrentAgent::Star;
-                    } else if !our_agent_lower.is_empty() && agent.contains(&our_agent_lower) {
-                        current_agent = CurrentAgent::Specific;
-                    } else {
-                        current_agent = CurrentAgent::Other;
+                    if !reading_agents {
+                        groups.push(Group::default());
+                        reading_agents = true;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(product_token(value));
+                    }
+                }
+                "allow" | "disallow" => {
+                    reading_agents = false;
+                    // An empty Disallow allows everything, same as no rule
+                    if let Some(group) = groups.last_mut()
+                        && !value.is_empty()
+                    {
+                        group.rules.push(Rule {
+                            allow: key.trim().eq_ignore_ascii_case("allow"),
+                            pattern: value.to_string(),
+                        });
+                    }
+                }
+                "crawl-delay" => {
+                    reading_agents = false;
+                    if let Some(group) = groups.last_mut()
+                        && let Ok(secs) = value.parse::<f64>()
+                        && secs.is_finite()
+                        && secs >= 0.0
+                    {
+                        // Too long to represent is as long as it gets; the
+                        // rate limiter caps it anyway
+                        group.crawl_delay =
+                            Some(Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX));
                     }
                 }
-                "disallow" if !value.is_empty() => match current_agent {
-                    CurrentAgent::Star => disallowed_star.push(value),
-                    CurrentAgent::Specific => disallowed_specific.push(value),
-                    _ => {}
-                },
+                "sitemap" if !value.is_empty() => sitemaps.push(value.to_string()),
                 _ => {}
             }
         }
 
-        // Prefer specific rules over * rules
-        if !disallowed_specific.is_empty() {
-            disallowed_specific
+        let named: Vec<&Group> = groups
+            .iter()
+            .filter(|g| g.agents.iter().any(|a| a == agent))
+            .collect();
+        let matched = if named.is_empty() {
+            groups
+                .iter()
+                .filter(|g| g.agents.iter().any(|a| a == "*"))
+                .collect()
         } else {
-            disallowed_star
+            named
+        };
+
+        Self {
+            rules: matched.iter().flat_map(|g| g.rules.clone()).collect(),
+            disallow_all: false,
+            crawl_delay: matched.iter().find_map(|g| g.crawl_delay),
+            sitemaps,
         }
     }
+
+    /// Whether the URL may be crawled.
+    pub fn allows(&self, url: &url::Url) -> bool {
+        self.is_allowed(&path_of(url))
+    }
+
+    /// Whether `path` (path and query) may be crawled. The longest
+    /// matching pattern decides; on a tie, Allow wins.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        if path == "/robots.txt" {
+            return true;
+        }
+        if self.disallow_all {
+            return false;
+        }
+
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+/// Lowercased product token of a user agent: "NoticeBot/0.1 (...)" → "noticebot".
+fn product_token(user_agent: &str) -> String {
+    let user_agent = user_agent.trim();
+    if user_agent == "*" {
+        return "*".into();
+    }
+    user_agent
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .next()
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// Path and query, as robots.txt patterns are matched against.
+fn path_of(url: &url::Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
 }
 
-#[derive(Clone, Copy)]
-enum CurrentAgent {
-    None,
-    Star,
-    Specific,
-    Other,
+/// Match a robots.txt pattern: `*` is any run of characters and a
+/// trailing `$` anchors the end; otherwise it is a prefix match.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p.as_bytes(), true),
+        None => (pattern.as_bytes(), false),
+    };
+    let path = path.as_bytes();
+
+    let (mut p, mut s) = (0, 0);
+    // Position after the last `*` and where in the path it resumes
+    let mut star: Option<(usize, usize)> = None;
+    loop {
+        if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p + 1, s));
+            p += 1;
+            continue;
+        }
+        if p == pattern.len() {
+            if !anchored || s == path.len() {
+                return true;
+            }
+        } else if s < path.len() && pattern[p] == path[s] {
+            p += 1;
+            s += 1;
+            continue;
+        }
+
+        // Let the last `*` swallow one more character
+        match star {
+            Some((after_star, resume)) if resume < path.len() => {
+                star = Some((after_star, resume + 1));
+                p = after_star;
+                s = resume + 1;
+            }
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+# Example
+User-agent: *
+Disallow: /private/
+Allow: /private/public.html
+Crawl-delay: 2
+
+User-agent: NoticeBot
+User-agent: OtherBot
+Disallow: /*.pdf$
+Disallow: /search
+Allow: /search/about
+Crawl-delay: 1.5
+
+Sitemap: https://example.com/sitemap.xml
+";
+
+    #[test]
+    fn uses_the_group_naming_our_agent() {
+        let rules = RobotsRules::parse(ROBOTS, "noticebot");
+        assert!(rules.is_allowed("/private/secret"));
+        assert!(!rules.is_allowed("/files/report.pdf"));
+        assert!(rules.is_allowed("/files/report.pdf?download=1"));
+        assert!(!rules.is_allowed("/search?q=rust"));
+        assert!(rules.is_allowed("/search/about"));
+        assert_eq!(rules.crawl_delay, Some(Duration::from_millis(1500)));
+        assert_eq!(rules.sitemaps, ["https://example.com/sitemap.xml"]);
+    }
+
+    #[test]
+    fn falls_back_to_the_star_group() {
+        let rules = RobotsRules::parse(ROBOTS, "somebot");
+        assert!(!rules.is_allowed("/private/secret"));
+        assert!(rules.is_allowed("/private/public.html"));
+        assert!(rules.is_allowed("/search"));
+        assert_eq!(rules.crawl_delay, Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn survives_out_of_range_crawl_delays() {
+        let rules = RobotsRules::parse("User-agent: *\nCrawl-delay: 1e300\n", "noticebot");
+        assert_eq!(rules.crawl_delay, Some(Duration::MAX));
+        let rules = RobotsRules::parse("User-agent: *\nCrawl-delay: -1\n", "noticebot");
+        assert_eq!(rules.crawl_delay, None);
+        let rules = RobotsRules::parse("User-agent: *\nCrawl-delay: inf\n", "noticebot");
+        assert_eq!(rules.crawl_delay, None);
+    }
+
+    #[test]
+    fn longest_match_wins_and_allow_wins_ties() {
+        let rules = RobotsRules::parse(
+            "User-agent: *\nDisallow: /a\nAllow: /a/b\nDisallow: /a/b/c\nAllow: /x\nDisallow: /x\n",
+            "noticebot",
+        );
+        assert!(!rules.is_allowed("/a/z"));
+        assert!(rules.is_allowed("/a/b/z"));
+        assert!(!rules.is_allowed("/a/b/c"));
+        assert!(rules.is_allowed("/x"));
+    }
+
+    #[test]
+    fn unreachable_disallows_everything_but_robots_txt() {
+        let rules = RobotsRules::disallow_all();
+        assert!(!rules.is_allowed("/"));
+        assert!(rules.is_allowed("/robots.txt"));
+        assert!(RobotsRules::default().is_allowed("/anything"));
+    }
+
+    #[test]
+    fn matches_wildcards_and_anchors() {
+        assert!(pattern_matches("/fish", "/fish.html"));
+        assert!(pattern_matches("/fish*", "/fish/salmon"));
+        assert!(pattern_matches("/*.php", "/index.php?x=1"));
+        assert!(pattern_matches("/*.php$", "/folder/index.php"));
+        assert!(!pattern_matches("/*.php$", "/index.php?x=1"));
+        assert!(pattern_matches("/fish*.php", "/fishheads/catfish.php"));
+        assert!(!pattern_matches("/fish", "/Fish"));
+        assert!(pattern_matches("*", "/anything"));
+        assert!(pattern_matches("/a*b*c$", "/axxbyyc"));
+        assert!(!pattern_matches("/a*b*c$", "/axxbyycd"));
+    }
+
+    #[test]
+    fn reads_product_tokens() {
+        assert_eq!(product_token("NoticeBot/0.1 (+https://x)"), "noticebot");
+        assert_eq!(product_token(" * "), "*");
+        assert_eq!(product_token("Googlebot-Image"), "googlebot-image");
+    }
 }
@@ -14,6 +14,13 @@ pub fn build_http_client(config: &CrawlerConfig) -> Result<Client, notice_core::
         .map_err(|e| notice_core::Error::Crawler(format!("Failed to build HTTP client: {}", e)))
 }
 
+/// Validators from an earlier fetch of a page.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Validators<'a> {
+    pub etag: Option<&'a str>,
+    pub last_modified: Option<&'a str>,
+}
+
 /// Scrape a URL and extract its text content.
 /// Returns the extracted text and the raw HTML (for link extraction).
 pub async fn scrape_url(
@@ -21,13 +28,40 @@ pub async fn scrape_url(
     target_url: &str,
     max_size: usize,
 ) -> Result<ScrapedPage, notice_core::Error> {
+    scrape_if_modified(client, target_url, max_size, Validators::default())
+        .await?
+        .ok_or_else(|| {
+            notice_core::Error::Crawler(format!("Unexpected 304 Not Modified for {}", target_url))
+        })
+}
+
+/// Like `scrape_url`, but sends `If-None-Match`/`If-Modified-Since` from
+/// `validators`. Returns None if the server says the page hasn't changed.
+pub async fn scrape_if_modified(
+    client: &Client,
+    target_url: &str,
+    max_size: usize,
+    validators: Validators<'_>,
+) -> Result<Option<ScrapedPage>, notice_core::Error> {
     tracing::debug!(url = target_url, "Fetching URL");
 
-    let response = client.get(target_url).send().await.map_err(|e| {
+    let mut request = client.get(target_url);
+    if let Some(etag) = validators.etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = validators.last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+
+    let response = request.send().await.map_err(|e| {
         notice_core::Error::Crawler(format!("Request failed for {}: {}", target_url, e))
     })?;
 
     let status = response.status();
+    if status == reqwest::StatusCode::NOT_MODIFIED {
+        tracing::debug!(url = target_url, "Not modified");
+        return Ok(None);
+    }
     if !status.is_success() {
         return Err(notice_core::Error::Crawler(format!(
             "HTTP {} for {}",
@@ -49,6 +83,16 @@ pub async fn scrape_url(
         )));
     }
 
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header(reqwest::header::ETAG);
+    let last_modified = header(reqwest::header::LAST_MODIFIED);
+
     // Check content length
     if let Some(len) = response.content_length() {
         if len as usize > max_size {
@@ -82,6 +126,8 @@ pub async fn scrape_url(
         .map(|el| el.text().collect::<String>().trim().to_string())
         .filter(|t| !t.is_empty());
 
+    let published_at = crate::published::extract_published_date(&document);
+
     // ── Boileplate Stripping ──
     // We define "noise" elements that often contain navigation, ads, or secondary content.
     let noise_selectors = [
@@ -122,8 +168,16 @@ pub async fn scrape_url(
         if !is_noise {
             let text = element.text().collect::<String>();
             let trimmed = text.trim().to_string();
-            if !trimmed.is_empty() {
-                text_parts.push(trimmed);
+            if trimmed.is_empty() {
+                continue;
+            }
+            // Headings become "## Title" lines so passages can be split on them
+            match heading_level(element.value().name()) {
+                Some(level) => {
+                    let title = trimmed.split_whitespace().collect::<Vec<_>>().join(" ");
+                    text_parts.push(format!("{} {}", "#".repeat(level), title));
+                }
+                None => text_parts.push(trimmed),
             }
         }
     }
@@ -141,15 +195,32 @@ pub async fn scrape_url(
     tracing::debug!(
         url = target_url,
         title = ?title,
+        published_at = ?published_at,
         content_len = text_content.len(),
         "Page scraped"
     );
 
-    Ok(ScrapedPage {
+    Ok(Some(ScrapedPage {
         url: target_url.to_string(),
         title,
         text_content,
         raw_html: html,
+        published_at,
+        etag,
+        last_modified,
         scraped_at: Utc::now(),
-    })
+    }))
+}
+
+/// 1-6 for `h1`-`h6`.
+fn heading_level(tag: &str) -> Option<usize> {
+    match tag {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
 }
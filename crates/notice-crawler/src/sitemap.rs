@@ -0,0 +1,420 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use flate2::read::GzDecoder;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use reqwest::Client;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::io::Read;
+
+use crate::links;
+use crate::robots::RobotsChecker;
+
+/// The protocol caps a sitemap at 50 MB uncompressed.
+const MAX_SITEMAP_BYTES: usize = 50 * 1024 * 1024;
+/// Most sitemap files fetched per request, following indexes.
+const MAX_SITEMAPS: usize = 50;
+
+/// A `<url>` of a sitemap, or a `<sitemap>` of a sitemap index.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SitemapEntry {
+    pub url: String,
+    pub lastmod: Option<DateTime<Utc>>,
+    /// 0.0–1.0; the protocol's default is 0.5
+    pub priority: Option<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sitemap {
+    /// Pages (`<urlset>`)
+    Urls(Vec<SitemapEntry>),
+    /// Further sitemaps (`<sitemapindex>`)
+    Index(Vec<SitemapEntry>),
+}
+
+#[derive(Debug, Clone)]
+pub struct CollectOptions {
+    /// Stop once this many page URLs were found
+    pub max_urls: usize,
+    /// Skip pages and sitemaps last modified before this
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Outcome of seeding the crawl queue from sitemaps.
+#[derive(Debug, Default, Serialize)]
+pub struct SitemapReport {
+    /// Sitemap files fetched
+    pub sitemaps: usize,
+    /// Page URLs found
+    pub found: usize,
+    /// URLs newly added to the crawl queue
+    pub enqueued: u64,
+    /// Sitemaps that could not be fetched or parsed
+    pub errors: Vec<String>,
+}
+
+/// Find the sitemaps of the site at `url` and enqueue their pages. A URL
+/// ending in `.xml` or `.xml.gz` is taken as the sitemap itself;
+/// otherwise the site's robots.txt `Sitemap:` lines are used, falling
+/// back to `/sitemap.xml`.
+pub async fn enqueue_from(
+    db: &PgPool,
+    client: &Client,
+    robots: &RobotsChecker,
+    url: &url::Url,
+    options: &CollectOptions,
+) -> Result<SitemapReport, notice_core::Error> {
+    let start = if is_sitemap_url(url) {
+        vec![url.to_string()]
+    } else {
+        let listed = robots.rules_for(url).await.sitemaps.clone();
+        if listed.is_empty() {
+            vec![format!(
+                "{}/sitemap.xml",
+                url.origin().ascii_serialization()
+            )]
+        } else {
+            listed
+        }
+    };
+
+    let (entries, mut report) = collect(client, start, options).await;
+    report.found = entries.len();
+
+    // One batch per queue priority
+    let mut by_priority: BTreeMap<i32, Vec<String>> = BTreeMap::new();
+    for entry in entries {
+        by_priority
+            .entry(queue_priority(entry.priority))
+            .or_default()
+            .push(entry.url);
+    }
+    for (priority, urls) in by_priority {
+        report.enqueued += notice_db::crawl_queue::enqueue_batch(db, &urls, priority).await?;
+    }
+
+    tracing::info!(
+        sitemaps = report.sitemaps,
+        found = report.found,
+        enqueued = report.enqueued,
+        errors = report.errors.len(),
+        "Crawl queue seeded from sitemaps"
+    );
+    Ok(report)
+}
+
+/// Fetch sitemaps breadth-first, following indexes (newest sitemaps
+/// first), until `max_urls` pages are found. Pages on another host than
+/// their sitemap are dropped, as the protocol requires.
+pub async fn collect(
+    client: &Client,
+    start: Vec<String>,
+    options: &CollectOptions,
+) -> (Vec<SitemapEntry>, SitemapReport) {
+    let mut report = SitemapReport::default();
+    let mut entries: Vec<SitemapEntry> = vec![];
+    let mut seen_sitemaps: HashSet<String> = start.iter().cloned().collect();
+    let mut seen_urls: HashSet<String> = HashSet::new();
+    let mut queue: VecDeque<String> = start.into();
+    let is_recent = |entry: &SitemapEntry| match (entry.lastmod, options.since) {
+        (Some(lastmod), Some(since)) => lastmod >= since,
+        _ => true,
+    };
+
+    while let Some(sitemap_url) = queue.pop_front() {
+        if report.sitemaps >= MAX_SITEMAPS || entries.len() >= options.max_urls {
+            break;
+        }
+        report.sitemaps += 1;
+
+        let sitemap = match fetch(client, &sitemap_url).await {
+            Ok(sitemap) => sitemap,
+            Err(e) => {
+                tracing::warn!(url = %sitemap_url, error = %e, "Could not read sitemap");
+                report.errors.push(format!("{}: {}", sitemap_url, e));
+                continue;
+            }
+        };
+
+        match sitemap {
+            Sitemap::Index(mut children) => {
+                children.sort_by_key(|c| std::cmp::Reverse(c.lastmod));
+                for child in children {
+                    if is_recent(&child) && seen_sitemaps.insert(child.url.clone()) {
+                        queue.push_back(child.url);
+                    }
+                }
+            }
+            Sitemap::Urls(pages) => {
+                let host = host_of(&sitemap_url);
+                for page in pages {
+                    if entries.len() >= options.max_urls {
+                        break;
+                    }
+                    if is_recent(&page)
+                        && host_of(&page.url) == host
+                        && !links::should_skip_url(&page.url)
+                        && seen_urls.insert(page.url.clone())
+                    {
+                        entries.push(page);
+                    }
+                }
+            }
+        }
+    }
+
+    (entries, report)
+}
+
+/// Download a sitemap, gunzipping it if needed, and parse it.
+async fn fetch(client: &Client, url: &str) -> Result<Sitemap, notice_core::Error> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| notice_core::Error::Crawler(format!("Request failed: {}", e)))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(notice_core::Error::Crawler(format!("HTTP {}", status)));
+    }
+    if response
+        .content_length()
+        .is_some_and(|len| len as usize > MAX_SITEMAP_BYTES)
+    {
+        return Err(notice_core::Error::Crawler("Sitemap too large".into()));
+    }
+
+    // Content-Length is optional, so enforce the limit while reading
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| notice_core::Error::Crawler(format!("Read failed: {}", e)))?
+    {
+        if body.len() + chunk.len() > MAX_SITEMAP_BYTES {
+            return Err(notice_core::Error::Crawler("Sitemap too large".into()));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    // Gunzipping and parsing up to 50 MB is CPU-bound
+    tokio::task::spawn_blocking(move || parse(&decompress(&body)?))
+        .await
+        .map_err(|e| notice_core::Error::Crawler(e.to_string()))?
+}
+
+/// Gunzip `.xml.gz` sitemaps (recognised by the gzip magic bytes, since
+/// servers label them inconsistently); other bodies pass through.
+fn decompress(body: &[u8]) -> Result<Vec<u8>, notice_core::Error> {
+    if !body.starts_with(&[0x1f, 0x8b]) {
+        return Ok(body.to_vec());
+    }
+
+    let mut xml = Vec::new();
+    GzDecoder::new(body)
+        .take(MAX_SITEMAP_BYTES as u64 + 1)
+        .read_to_end(&mut xml)
+        .map_err(|e| notice_core::Error::Crawler(format!("Invalid gzip: {}", e)))?;
+    if xml.len() > MAX_SITEMAP_BYTES {
+        return Err(notice_core::Error::Crawler("Sitemap too large".into()));
+    }
+    Ok(xml)
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Loc,
+    Lastmod,
+    Priority,
+}
+
+/// Parse a `<urlset>` or `<sitemapindex>` document. Extension elements
+/// (`image:loc` and the like) are ignored.
+pub fn parse(xml: &[u8]) -> Result<Sitemap, notice_core::Error> {
+    let mut reader = Reader::from_reader(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut is_index: Option<bool> = None;
+    let mut entries: Vec<SitemapEntry> = vec![];
+    let mut current: Option<SitemapEntry> = None;
+    let mut field: Option<Field> = None;
+
+    loop {
+        let event = reader.read_event_into(&mut buf).map_err(|e| {
+            notice_core::Error::Crawler(format!(
+                "Invalid sitemap XML at byte {}: {}",
+                reader.error_position(),
+                e
+            ))
+        })?;
+        match event {
+            Event::Start(tag) => match tag.name().as_ref() {
+                b"urlset" => is_index = Some(false),
+                b"sitemapindex" => is_index = Some(true),
+                b"url" | b"sitemap" => current = Some(SitemapEntry::default()),
+                b"loc" => field = Some(Field::Loc),
+                b"lastmod" => field = Some(Field::Lastmod),
+                b"priority" => field = Some(Field::Priority),
+                _ => field = None,
+            },
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|e| {
+                    notice_core::Error::Crawler(format!("Invalid sitemap XML: {}", e))
+                })?;
+                if let (Some(entry), Some(field)) = (current.as_mut(), field) {
+                    set_field(entry, field, &text);
+                }
+            }
+            Event::CData(text) => {
+                if let (Some(entry), Some(field)) = (current.as_mut(), field) {
+                    set_field(entry, field, &String::from_utf8_lossy(&text));
+                }
+            }
+            Event::End(tag) => match tag.name().as_ref() {
+                b"url" | b"sitemap" => {
+                    if let Some(entry) = current.take()
+                        && !entry.url.is_empty()
+                    {
+                        entries.push(entry);
+                    }
+                }
+                _ => field = None,
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    match is_index {
+        Some(true) => Ok(Sitemap::Index(entries)),
+        Some(false) => Ok(Sitemap::Urls(entries)),
+        None => Err(notice_core::Error::Crawler(
+            "Not a sitemap: no <urlset> or <sitemapindex>".into(),
+        )),
+    }
+}
+
+fn set_field(entry: &mut SitemapEntry, field: Field, text: &str) {
+    let text = text.trim();
+    match field {
+        Field::Loc => entry.url = text.to_string(),
+        Field::Lastmod => entry.lastmod = parse_lastmod(text),
+        Field::Priority => {
+            entry.priority = text.parse::<f32>().ok().filter(|p| (0.0..=1.0).contains(p))
+        }
+    }
+}
+
+/// W3C datetime as sitemaps use it: a date, or a date and time with a
+/// timezone, seconds optional.
+fn parse_lastmod(text: &str) -> Option<DateTime<Utc>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+    if let Ok(datetime) = DateTime::parse_from_str(text, "%Y-%m-%dT%H:%M%:z") {
+        return Some(datetime.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|datetime| datetime.and_utc())
+}
+
+/// Crawl queue priority for a sitemap priority: -5 to 5, so the default
+/// 0.5 ranks with submitted URLs (0) and above discovered links (-1 and below).
+pub fn queue_priority(priority: Option<f32>) -> i32 {
+    (priority.unwrap_or(0.5) * 10.0).round() as i32 - 5
+}
+
+fn is_sitemap_url(url: &url::Url) -> bool {
+    let path = url.path().to_lowercase();
+    path.ends_with(".xml") || path.ends_with(".xml.gz")
+}
+
+fn host_of(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_lowercase))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    const URLSET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+        xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">
+  <url>
+    <loc>https://example.com/docs?a=1&amp;b=2</loc>
+    <lastmod>2024-05-01</lastmod>
+    <priority>0.8</priority>
+    <image:image><image:loc>https://example.com/logo.png</image:loc></image:image>
+  </url>
+  <url>
+    <loc><![CDATA[https://example.com/blog]]></loc>
+    <lastmod>2024-05-02T10:30+02:00</lastmod>
+    <priority>7</priority>
+  </url>
+  <url><lastmod>2024-05-03</lastmod></url>
+</urlset>"#;
+
+    #[test]
+    fn parses_a_urlset() {
+        let Sitemap::Urls(entries) = parse(URLSET.as_bytes()).unwrap() else {
+            panic!("expected a urlset");
+        };
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].url, "https://example.com/docs?a=1&b=2");
+        assert_eq!(
+            entries[0].lastmod,
+            Some("2024-05-01T00:00:00Z".parse().unwrap())
+        );
+        assert_eq!(entries[0].priority, Some(0.8));
+        assert_eq!(entries[1].url, "https://example.com/blog");
+        assert_eq!(
+            entries[1].lastmod,
+            Some("2024-05-02T08:30:00Z".parse().unwrap())
+        );
+        assert_eq!(entries[1].priority, None);
+    }
+
+    #[test]
+    fn parses_a_gzipped_index() {
+        let xml = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>https://example.com/a.xml.gz</loc><lastmod>2024-01-01T00:00:00Z</lastmod></sitemap>
+  <sitemap><loc>https://example.com/b.xml</loc></sitemap>
+</sitemapindex>"#;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(xml.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        let Sitemap::Index(entries) = parse(&decompress(&gzipped).unwrap()).unwrap() else {
+            panic!("expected a sitemap index");
+        };
+        let urls: Vec<&str> = entries.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            ["https://example.com/a.xml.gz", "https://example.com/b.xml"]
+        );
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(parse(b"<html><body>Not found</body></html>").is_err());
+        assert!(parse(b"<urlset><url><loc>x</url>").is_err());
+    }
+
+    #[test]
+    fn maps_priority_to_the_queue() {
+        assert_eq!(queue_priority(None), 0);
+        assert_eq!(queue_priority(Some(1.0)), 5);
+        assert_eq!(queue_priority(Some(0.0)), -5);
+        assert_eq!(queue_priority(Some(0.64)), 1);
+    }
+}
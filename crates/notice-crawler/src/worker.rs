@@ -4,13 +4,14 @@ use crate::robots::RobotsChecker;
 use crate::scraper_engine;
 
 use notice_core::config::CrawlerConfig;
-use notice_search::MeiliDocumentInput;
+use notice_db::crawl_queue::CrawlQueueRow;
 use reqwest::Client;
 use sqlx::PgPool;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use tokio::time::Duration;
 use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
 
 /// Handle to control the running crawler.
 #[derive(Clone)]
@@ -24,6 +25,8 @@ pub struct CrawlerStats {
     pub pages_crawled: AtomicU64,
     pub pages_failed: AtomicU64,
     pub links_discovered: AtomicU64,
+    pub pages_recrawled: AtomicU64,
+    pub pages_changed: AtomicU64,
     pub running: AtomicBool,
 }
 
@@ -45,6 +48,8 @@ impl CrawlerHandle {
             pages_crawled: self.stats.pages_crawled.load(Ordering::Relaxed),
             pages_failed: self.stats.pages_failed.load(Ordering::Relaxed),
             links_discovered: self.stats.links_discovered.load(Ordering::Relaxed),
+            pages_recrawled: self.stats.pages_recrawled.load(Ordering::Relaxed),
+            pages_changed: self.stats.pages_changed.load(Ordering::Relaxed),
             running: self.stats.running.load(Ordering::Relaxed),
         }
     }
@@ -55,27 +60,30 @@ pub struct CrawlerStatsSnapshot {
     pub pages_crawled: u64,
     pub pages_failed: u64,
     pub links_discovered: u64,
+    /// Indexed pages fetched again, and how many of those had changed
+    pub pages_recrawled: u64,
+    pub pages_changed: u64,
     pub running: bool,
 }
 
 /// Shared context passed to each worker.
-struct CrawlerContext {
-    db: PgPool,
-    search: notice_search::SearchClient,
-    gemini: notice_ai::GeminiClient,
-    http: Client,
-    robots: RobotsChecker,
-    rate_limiter: DomainRateLimiter,
-    config: CrawlerConfig,
-    stats: Arc<CrawlerStats>,
-    cancel: CancellationToken,
+pub(crate) struct CrawlerContext {
+    pub(crate) db: PgPool,
+    pub(crate) gemini: notice_ai::GeminiClient,
+    pub(crate) embedder: Option<notice_ai::embedding::Embedder>,
+    pub(crate) http: Client,
+    pub(crate) robots: RobotsChecker,
+    pub(crate) rate_limiter: DomainRateLimiter,
+    pub(crate) config: CrawlerConfig,
+    pub(crate) stats: Arc<CrawlerStats>,
+    pub(crate) cancel: CancellationToken,
 }
 
 /// Start the background crawler. Returns a handle for control.
 pub fn start_crawler(
     db: PgPool,
-    search: notice_search::SearchClient,
     gemini: notice_ai::GeminiClient,
+    embedder: Option<notice_ai::embedding::Embedder>,
     config: CrawlerConfig,
 ) -> CrawlerHandle {
     let cancel = CancellationToken::new();
@@ -84,6 +92,8 @@ pub fn start_crawler(
         pages_crawled: AtomicU64::new(0),
         pages_failed: AtomicU64::new(0),
         links_discovered: AtomicU64::new(0),
+        pages_recrawled: AtomicU64::new(0),
+        pages_changed: AtomicU64::new(0),
         running: AtomicBool::new(true),
     });
 
@@ -95,13 +105,17 @@ pub fn start_crawler(
     let http =
         scraper_engine::build_http_client(&config).expect("Failed to build crawler HTTP client");
 
-    let robots = RobotsChecker::new(http.clone(), &config.user_agent);
+    let robots = RobotsChecker::new(
+        http.clone(),
+        &config.user_agent,
+        Duration::from_secs(config.robots_cache_secs),
+    );
     let rate_limiter = DomainRateLimiter::new(config.politeness_delay_ms);
 
     let ctx = Arc::new(CrawlerContext {
         db,
-        search,
         gemini,
+        embedder,
         http,
         robots,
         rate_limiter,
@@ -121,6 +135,13 @@ pub fn start_crawler(
         });
     }
 
+    if config.recrawl {
+        let ctx = Arc::clone(&ctx);
+        tokio::spawn(async move {
+            crate::recrawl::run(ctx).await;
+        });
+    }
+
     // Spawn the startup cleanup task
     let ctx_cleanup = Arc::clone(&ctx);
     tokio::spawn(async move {
@@ -168,8 +189,12 @@ async fn worker_loop(worker_id: usize, ctx: Arc<CrawlerContext>) {
 
         tracing::info!(worker = worker_id, url = %item.url, "Processing URL");
 
+        // Links found on pages past the depth limit are not followed
+        let follow_links =
+            ctx.config.discover_links && item.depth <= ctx.config.max_link_depth as i32;
+
         // Process the URL
-        match process_url(&ctx, &item.url).await {
+        match process_url(&ctx, &item.url, follow_links).await {
             Ok(discovered) => {
                 // Mark completed
                 if let Err(e) = notice_db::crawl_queue::mark_completed(&ctx.db, item.id).await {
@@ -178,8 +203,8 @@ async fn worker_loop(worker_id: usize, ctx: Arc<CrawlerContext>) {
                 ctx.stats.pages_crawled.fetch_add(1, Ordering::Relaxed);
 
                 // Enqueue discovered links
-                if ctx.config.discover_links && !discovered.is_empty() {
-                    let new_count = enqueue_discovered_links(&ctx.db, &discovered).await;
+                if !discovered.is_empty() {
+                    let new_count = enqueue_discovered_links(&ctx.db, &item, &discovered).await;
                     ctx.stats
                         .links_discovered
                         .fetch_add(new_count, Ordering::Relaxed);
@@ -212,9 +237,14 @@ async fn worker_loop(worker_id: usize, ctx: Arc<CrawlerContext>) {
 async fn process_url(
     ctx: &CrawlerContext,
     target_url: &str,
+    follow_links: bool,
 ) -> Result<Vec<String>, notice_core::Error> {
+    let parsed_url = url::Url::parse(target_url)
+        .map_err(|e| notice_core::Error::Crawler(format!("Invalid URL {}: {}", target_url, e)))?;
+
     // Step 1: Check robots.txt
-    if !ctx.robots.is_allowed(target_url).await {
+    let robots = ctx.robots.rules_for(&parsed_url).await;
+    if !robots.allows(&parsed_url) {
         return Err(notice_core::Error::Crawler(format!(
             "Blocked by robots.txt: {}",
             target_url
@@ -222,12 +252,11 @@ async fn process_url(
     }
 
     // Step 2: Rate limit per domain
-    let domain = url::Url::parse(target_url)
-        .ok()
-        .and_then(|u| u.host_str().map(|h| h.to_string()))
-        .unwrap_or_default();
+    let domain = parsed_url.host_str().unwrap_or_default().to_string();
 
-    ctx.rate_limiter.wait_for_domain(&domain).await;
+    ctx.rate_limiter
+        .wait_for_domain(&domain, robots.crawl_delay)
+        .await;
 
     // Step 3: Check if already indexed
     if notice_db::documents::get_by_url(&ctx.db, target_url)
@@ -243,7 +272,7 @@ async fn process_url(
         scraper_engine::scrape_url(&ctx.http, target_url, ctx.config.max_content_size).await?;
 
     // Step 5: Extract links from raw HTML (single fetch, no double request)
-    let discovered_links = if ctx.config.discover_links {
+    let discovered_links = if follow_links {
         links::extract_links(&page.raw_html, target_url)
     } else {
         vec![]
@@ -253,79 +282,67 @@ async fn process_url(
     let quality_score = calculate_quality_score(target_url, page.title.as_deref(), &page.text_content);
 
     // Step 7: Store in PostgreSQL
-    let mut doc = notice_db::documents::insert(
+    let doc = notice_db::documents::insert(
         &ctx.db,            // 1. pool
         &page.url,          // 2. doc_url
         page.title.as_deref(), // 3. title
         &page.text_content, // 4. raw_content
         quality_score,      // 5. quality_score
+        page.published_at,  // 6. published_at
     )
     .await?;
 
     tracing::info!(doc_id = %doc.id, url = %target_url, quality = %quality_score, "Document stored");
 
-    // Step 8: Index in Meilisearch immediately (Decoupled from summarization)
-    let meili_doc = MeiliDocumentInput {
-        id: doc.id,
-        url: doc.url.clone(),
-        domain: doc.domain.clone(),
-        title: doc.title.clone(),
-        raw_content: doc.raw_content.clone(),
-        summary: doc.summary.clone(),
-        status: doc.status.clone(),
-        quality_score: doc.quality_score,
-    };
+    if page.etag.is_some() || page.last_modified.is_some() {
+        notice_db::recrawl::set_validators(
+            &ctx.db,
+            doc.id,
+            page.etag.as_deref(),
+            page.last_modified.as_deref(),
+        )
+        .await?;
+    }
 
-    if let Err(e) = ctx.search.add_document(meili_doc).await {
-        tracing::error!(doc_id = %doc.id, error = %e, "Failed to index in Meilisearch");
-    } else {
-        tracing::debug!(doc_id = %doc.id, "Document indexed in Meilisearch");
+    // Step 8: Split into passages (embedded for semantic search). The
+    // document and its passages reach the search index through the outbox.
+    if let Err(e) =
+        crate::passages::index_document(&ctx.db, ctx.embedder.as_ref(), &doc).await
+    {
+        tracing::warn!(doc_id = %doc.id, error = %e, "Failed to index passages");
     }
 
-    // Step 8: Summarize with Gemini (Now happens after indexing)
-    let content_for_summary = notice_core::truncate_utf8(&page.text_content, 8000).to_string();
-    let db_pool = ctx.db.clone();
-    let gemini = ctx.gemini.clone();
-    let search = ctx.search.clone();
-    let doc_id = doc.id;
+    // Step 9: Summarize with Gemini (Now happens after indexing)
+    summarize(ctx, doc.id, &page.text_content).await;
 
-    // We do this sequentially here for simplicity in the worker loop, 
-    // but the key is that indexing happened ALREADY.
-    match gemini.summarize(&content_for_summary).await {
+    Ok(discovered_links)
+}
+
+/// Summarize a stored document with Gemini and store the summary (which
+/// queues it for re-indexing), or mark summarization failed.
+pub(crate) async fn summarize(ctx: &CrawlerContext, doc_id: Uuid, text: &str) {
+    let content_for_summary = notice_core::truncate_utf8(text, 8000);
+
+    match ctx.gemini.summarize(content_for_summary).await {
         Ok(summary) if !summary.is_empty() => {
             tracing::debug!(doc_id = %doc_id, "Summary generated");
-            if let Ok(updated_doc) = notice_db::documents::update_summary(&db_pool, doc_id, &summary).await {
-                doc = updated_doc;
-                
-                // Update Meilisearch with the summary
-                let meili_update = MeiliDocumentInput {
-                    id: doc.id,
-                    url: doc.url.clone(),
-                    domain: doc.domain.clone(),
-                    title: doc.title.clone(),
-                    raw_content: doc.raw_content.clone(),
-                    summary: doc.summary.clone(),
-                    status: doc.status.clone(),
-                    quality_score: doc.quality_score,
-                };
-                let _ = search.add_document(meili_update).await;
+            if let Err(e) = notice_db::documents::update_summary(&ctx.db, doc_id, &summary).await {
+                tracing::warn!(doc_id = %doc_id, error = %e, "Failed to store summary");
             }
         }
         Ok(_) => {
             tracing::debug!(doc_id = %doc_id, "Empty summary from Gemini");
-            let _ = notice_db::documents::mark_summary_failed(&db_pool, doc_id).await;
+            let _ = notice_db::documents::mark_summary_failed(&ctx.db, doc_id).await;
         }
         Err(e) => {
             tracing::warn!(doc_id = %doc_id, error = %e, "Summarization failed");
-            let _ = notice_db::documents::mark_summary_failed(&db_pool, doc_id).await;
+            let _ = notice_db::documents::mark_summary_failed(&ctx.db, doc_id).await;
         }
     };
-
-    Ok(discovered_links)
 }
 
-/// Filter and enqueue newly discovered links.
-async fn enqueue_discovered_links(db: &PgPool, links: &[String]) -> u64 {
+/// Filter and enqueue links discovered on `parent`, one level deeper.
+async fn enqueue_discovered_links(db: &PgPool, parent: &CrawlQueueRow, links: &[String]) -> u64 {
     if links.is_empty() {
         return 0;
     }
@@ -348,7 +365,10 @@ async fn enqueue_discovered_links(db: &PgPool, links: &[String]) -> u64 {
 
     let _count = new_urls.len();
 
-    match notice_db::crawl_queue::enqueue_batch(db, &new_urls, -1).await {
+    // Deeper pages wait behind shallower ones
+    let priority = -(parent.depth + 1);
+
+    match notice_db::crawl_queue::enqueue_children(db, parent, &new_urls, priority).await {
         Ok(inserted) => {
             if inserted > 0 {
                 tracing::info!("Discovered and enqueued {} new URLs", inserted);
@@ -363,7 +383,7 @@ async fn enqueue_discovered_links(db: &PgPool, links: &[String]) -> u64 {
 }
 
 /// Calculate a quality score (0.5 to 3.0) based on domain and content.
-fn calculate_quality_score(url_str: &str, title: Option<&str>, content: &str) -> f64 {
+pub(crate) fn calculate_quality_score(url_str: &str, title: Option<&str>, content: &str) -> f64 {
     let mut score: f64 = 1.0;
 
     // 1. Domain Reputation
@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct BangRow {
+    pub id: i64,
+    pub user_id: Option<Uuid>,
+    pub trigger: String,
+    pub name: String,
+    pub url_template: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Find the bang for a trigger: the user's own first, then the global one.
+pub async fn resolve(
+    pool: &PgPool,
+    user_id: Option<Uuid>,
+    trigger: &str,
+) -> Result<Option<BangRow>, notice_core::Error> {
+    sqlx::query_as::<_, BangRow>(
+        r#"
+        SELECT * FROM bangs
+        WHERE trigger = $1 AND (user_id IS NULL OR user_id = $2)
+        ORDER BY user_id NULLS LAST
+        LIMIT 1
+        "#,
+    )
+    .bind(trigger)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Global bangs plus the user's own, ordered by trigger.
+pub async fn list(
+    pool: &PgPool,
+    user_id: Option<Uuid>,
+) -> Result<Vec<BangRow>, notice_core::Error> {
+    sqlx::query_as::<_, BangRow>(
+        r#"
+        SELECT * FROM bangs
+        WHERE user_id IS NULL OR user_id = $1
+        ORDER BY trigger, user_id NULLS FIRST
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Create or replace one of a user's bangs.
+pub async fn upsert(
+    pool: &PgPool,
+    user_id: Uuid,
+    trigger: &str,
+    name: &str,
+    url_template: &str,
+) -> Result<BangRow, notice_core::Error> {
+    sqlx::query_as::<_, BangRow>(
+        r#"
+        INSERT INTO bangs (user_id, trigger, name, url_template)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id, trigger) WHERE user_id IS NOT NULL DO UPDATE
+        SET name = $3, url_template = $4
+        RETURNING *
+        "#,
+    )
+    .bind(user_id)
+    .bind(trigger)
+    .bind(name)
+    .bind(url_template)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Remove one of a user's bangs. Returns false if there was nothing to
+/// delete.
+pub async fn delete(
+    pool: &PgPool,
+    user_id: Uuid,
+    trigger: &str,
+) -> Result<bool, notice_core::Error> {
+    let result = sqlx::query("DELETE FROM bangs WHERE trigger = $1 AND user_id = $2")
+        .bind(trigger)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,272 @@
+use chrono::{DateTime, Utc};
+use notice_core::types::{ParsedQuery, with_www};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::documents::DocumentRow;
+use crate::filters::day_start;
+
+// ─── Row Types ───
+
+/// A stored passage (the embedding itself is never read back).
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct ChunkRow {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub chunk_index: i32,
+    pub heading: Option<String>,
+    pub content: String,
+    pub start_offset: i32,
+    pub end_offset: i32,
+    pub embedding_model: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A passage to store; `chunk_index` is its position in the slice.
+#[derive(Debug, Clone)]
+pub struct NewChunk {
+    pub heading: Option<String>,
+    pub content: String,
+    pub start_offset: i32,
+    pub end_offset: i32,
+}
+
+/// A passage still waiting for an embedding, with what goes into it.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PendingChunk {
+    pub id: Uuid,
+    pub title: Option<String>,
+    pub heading: Option<String>,
+    pub content: String,
+}
+
+/// A document found by vector similarity of its best passage.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct SemanticHit {
+    pub id: Uuid,
+    pub url: String,
+    pub title: Option<String>,
+    /// Start of the closest passage
+    pub snippet: String,
+    /// Cosine similarity, 1.0 = same direction
+    pub similarity: f64,
+}
+
+const CHUNK_COLUMNS: &str = "id, document_id, chunk_index, heading, content, \
+                             start_offset, end_offset, embedding_model, created_at";
+
+// ─── Queries ───
+
+/// Replace all of a document's passages (and so their embeddings), and
+/// queue the document for re-indexing.
+pub async fn replace(
+    pool: &PgPool,
+    document_id: Uuid,
+    chunks: &[NewChunk],
+) -> Result<Vec<ChunkRow>, notice_core::Error> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    sqlx::query("DELETE FROM document_chunks WHERE document_id = $1")
+        .bind(document_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    let mut rows = sqlx::query_as::<_, ChunkRow>(&format!(
+        r#"
+        INSERT INTO document_chunks
+            (document_id, chunk_index, heading, content, start_offset, end_offset)
+        SELECT $1, * FROM UNNEST($2::int[], $3::text[], $4::text[], $5::int[], $6::int[])
+        RETURNING {}
+        "#,
+        CHUNK_COLUMNS
+    ))
+    .bind(document_id)
+    .bind((0..chunks.len() as i32).collect::<Vec<i32>>())
+    .bind(chunks.iter().map(|c| c.heading.clone()).collect::<Vec<_>>())
+    .bind(chunks.iter().map(|c| c.content.clone()).collect::<Vec<_>>())
+    .bind(chunks.iter().map(|c| c.start_offset).collect::<Vec<_>>())
+    .bind(chunks.iter().map(|c| c.end_offset).collect::<Vec<_>>())
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    crate::index_outbox::enqueue(&mut tx, document_id, "upsert").await?;
+    tx.commit()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    rows.sort_by_key(|r| r.chunk_index);
+    Ok(rows)
+}
+
+/// A document's passages in order.
+pub async fn for_document(
+    pool: &PgPool,
+    document_id: Uuid,
+) -> Result<Vec<ChunkRow>, notice_core::Error> {
+    sqlx::query_as::<_, ChunkRow>(&format!(
+        "SELECT {} FROM document_chunks WHERE document_id = $1 ORDER BY chunk_index",
+        CHUNK_COLUMNS
+    ))
+    .bind(document_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Store a passage's embedding.
+pub async fn set_embedding(
+    pool: &PgPool,
+    chunk_id: Uuid,
+    model: &str,
+    embedding: &[f32],
+) -> Result<(), notice_core::Error> {
+    sqlx::query(
+        r#"
+        UPDATE document_chunks
+        SET embedding_model = $2, embedding = $3::vector
+        WHERE id = $1
+        "#,
+    )
+    .bind(chunk_id)
+    .bind(model)
+    .bind(vector_literal(embedding))
+    .execute(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Passages with no embedding from `model` yet, oldest documents first.
+pub async fn list_unembedded(
+    pool: &PgPool,
+    model: &str,
+    limit: i64,
+) -> Result<Vec<PendingChunk>, notice_core::Error> {
+    sqlx::query_as::<_, PendingChunk>(
+        r#"
+        SELECT c.id, d.title, c.heading, c.content
+        FROM document_chunks c
+        JOIN documents d ON d.id = c.document_id
+        WHERE c.embedding_model IS DISTINCT FROM $1
+        ORDER BY d.created_at ASC, c.chunk_index ASC
+        LIMIT $2
+        "#,
+    )
+    .bind(model)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Documents that have not been split into passages yet, by id after
+/// `after` (documents with no text never get passages, so callers page
+/// past them instead of seeing them again).
+pub async fn list_unchunked(
+    pool: &PgPool,
+    after: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<DocumentRow>, notice_core::Error> {
+    sqlx::query_as::<_, DocumentRow>(
+        r#"
+        SELECT d.*
+        FROM documents d
+        WHERE ($1::uuid IS NULL OR d.id > $1)
+          AND NOT EXISTS (SELECT 1 FROM document_chunks c WHERE c.document_id = d.id)
+        ORDER BY d.id
+        LIMIT $2
+        "#,
+    )
+    .bind(after)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Documents whose closest passage is nearest to `embedding`, restricted by
+/// the query's operators (`site:`, `-site:`, `status:`, `after:`, `before:`).
+pub async fn nearest(
+    pool: &PgPool,
+    model: &str,
+    embedding: &[f32],
+    parsed: &ParsedQuery,
+    limit: i64,
+) -> Result<Vec<SemanticHit>, notice_core::Error> {
+    // Several passages of one document can be among the nearest
+    let candidates = limit * 4;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    // The HNSW scan returns at most ef_search rows (default 40)
+    sqlx::query(&format!(
+        "SET LOCAL hnsw.ef_search = {}",
+        candidates.clamp(40, 1000)
+    ))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    let hits = sqlx::query_as::<_, SemanticHit>(
+        r#"
+        WITH nearest AS (
+            SELECT c.document_id, c.content, c.embedding <=> $1::vector AS distance
+            FROM document_chunks c
+            JOIN documents d ON d.id = c.document_id
+            WHERE c.embedding_model = $2
+              AND (cardinality($3::text[]) = 0 OR d.domain = ANY($3))
+              AND NOT (d.domain = ANY($4::text[]))
+              AND ($5::text IS NULL OR d.status = $5)
+              AND ($6::timestamptz IS NULL OR COALESCE(d.published_at, d.created_at) >= $6)
+              AND ($7::timestamptz IS NULL OR COALESCE(d.published_at, d.created_at) < $7)
+            ORDER BY c.embedding <=> $1::vector
+            LIMIT $9
+        )
+        SELECT * FROM (
+            SELECT DISTINCT ON (n.document_id)
+                   d.id, d.url, d.title,
+                   LEFT(n.content, 300) AS snippet,
+                   1 - n.distance AS similarity
+            FROM nearest n
+            JOIN documents d ON d.id = n.document_id
+            ORDER BY n.document_id, n.distance
+        ) best
+        ORDER BY similarity DESC
+        LIMIT $8
+        "#,
+    )
+    .bind(vector_literal(embedding))
+    .bind(model)
+    .bind(with_www(&parsed.sites))
+    .bind(with_www(&parsed.excluded_sites))
+    .bind(parsed.status.as_deref())
+    .bind(parsed.after.and_then(day_start))
+    .bind(parsed.before.and_then(day_start))
+    .bind(limit)
+    .bind(candidates)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(hits)
+}
+
+// ─── Helpers ───
+
+/// pgvector's text form, "[0.1,0.2,...]", so no extra sqlx type is needed.
+fn vector_literal(embedding: &[f32]) -> String {
+    let values: Vec<String> = embedding.iter().map(|v| v.to_string()).collect();
+    format!("[{}]", values.join(","))
+}
@@ -1,8 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 use sqlx::PgPool;
+use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Most entries a crawl tree response holds.
+const MAX_TREE_NODES: i64 = 5000;
+
 // ─── Row Types ───
 
 #[derive(Debug, Clone, sqlx::FromRow, Serialize)]
@@ -17,6 +21,12 @@ pub struct CrawlQueueRow {
     pub submitted_by: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Links followed from the seed to reach this URL (0 for seeds)
+    pub depth: i32,
+    /// Page this URL was discovered on
+    pub parent_url: Option<String>,
+    /// Entry the crawl started from; None for seeds
+    pub seed_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -27,6 +37,26 @@ pub struct QueueStats {
     pub failed: i64,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct CrawlTreeNode {
+    pub id: Uuid,
+    pub url: String,
+    pub status: String,
+    pub depth: i32,
+    pub last_error: Option<String>,
+    pub children: Vec<CrawlTreeNode>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrawlTree {
+    pub seed_id: Uuid,
+    /// Entries in the seed's crawl, including the seed
+    pub total: i64,
+    /// Whether `root` leaves out entries past MAX_TREE_NODES
+    pub truncated: bool,
+    pub root: CrawlTreeNode,
+}
+
 // ─── Queries ───
 
 /// Add a URL to the crawl queue. Ignores duplicates (ON CONFLICT DO NOTHING).
@@ -84,6 +114,40 @@ pub async fn enqueue_batch(
     Ok(inserted)
 }
 
+/// Enqueue links found on `parent` one level deeper in its seed's crawl.
+/// Ignores duplicates. Returns the count of newly inserted URLs.
+pub async fn enqueue_children(
+    pool: &PgPool,
+    parent: &CrawlQueueRow,
+    urls: &[String],
+    priority: i32,
+) -> Result<u64, notice_core::Error> {
+    let seed_id = parent.seed_id.unwrap_or(parent.id);
+
+    let mut inserted: u64 = 0;
+    for url in urls {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO crawl_queue (url, priority, depth, parent_url, seed_id)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (url) DO NOTHING
+            "#,
+        )
+        .bind(url)
+        .bind(priority)
+        .bind(parent.depth + 1)
+        .bind(&parent.url)
+        .bind(seed_id)
+        .execute(pool)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+        inserted += result.rows_affected();
+    }
+
+    Ok(inserted)
+}
+
 /// Automically dequeue the next pending URL.
 /// Uses FOR UPDATE SKIP LOCKED for safe concurrent access.
 pub async fn dequeue_next(pool: &PgPool) -> Result<Option<CrawlQueueRow>, notice_core::Error> {
@@ -199,3 +263,160 @@ pub async fn stats(pool: &PgPool) -> Result<QueueStats, notice_core::Error> {
         failed: row.3,
     })
 }
+
+/// The crawl an entry belongs to, nested by the page each URL was found
+/// on. Returns None if there is no such entry.
+pub async fn crawl_tree(pool: &PgPool, id: Uuid) -> Result<Option<CrawlTree>, notice_core::Error> {
+    let seed_id: Option<Uuid> =
+        sqlx::query_scalar("SELECT COALESCE(seed_id, id) FROM crawl_queue WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    let Some(seed_id) = seed_id else {
+        return Ok(None);
+    };
+
+    let total: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM crawl_queue WHERE id = $1 OR seed_id = $1")
+            .bind(seed_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    // The seed sorts first at depth 0, and parents before their children
+    let rows = sqlx::query_as::<_, CrawlQueueRow>(
+        r#"
+        SELECT * FROM crawl_queue
+        WHERE id = $1 OR seed_id = $1
+        ORDER BY depth, created_at
+        LIMIT $2
+        "#,
+    )
+    .bind(seed_id)
+    .bind(MAX_TREE_NODES)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    let truncated = (rows.len() as i64) < total;
+    Ok(build_tree(rows).map(|root| CrawlTree {
+        seed_id,
+        total,
+        truncated,
+        root,
+    }))
+}
+
+/// Nest rows under the row whose URL is their `parent_url`, starting from
+/// the first row. Rows whose parent is missing hang off the root.
+fn build_tree(rows: Vec<CrawlQueueRow>) -> Option<CrawlTreeNode> {
+    let index: HashMap<&str, usize> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| (row.url.as_str(), i))
+        .collect();
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); rows.len()];
+    for (i, row) in rows.iter().enumerate().skip(1) {
+        let parent = row
+            .parent_url
+            .as_deref()
+            .and_then(|url| index.get(url).copied())
+            .filter(|&p| rows[p].depth < row.depth)
+            .unwrap_or(0);
+        children[parent].push(i);
+    }
+
+    fn node(rows: &[CrawlQueueRow], children: &[Vec<usize>], i: usize) -> CrawlTreeNode {
+        let row = &rows[i];
+        CrawlTreeNode {
+            id: row.id,
+            url: row.url.clone(),
+            status: row.status.clone(),
+            depth: row.depth,
+            last_error: row.last_error.clone(),
+            children: children[i]
+                .iter()
+                .map(|&c| node(rows, children, c))
+                .collect(),
+        }
+    }
+
+    (!rows.is_empty()).then(|| node(&rows, &children, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(url: &str, depth: i32, parent_url: Option<&str>) -> CrawlQueueRow {
+        CrawlQueueRow {
+            id: Uuid::new_v4(),
+            url: url.to_string(),
+            status: "completed".to_string(),
+            priority: 0,
+            retry_count: 0,
+            max_retries: 3,
+            last_error: None,
+            submitted_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            depth,
+            parent_url: parent_url.map(str::to_string),
+            seed_id: None,
+        }
+    }
+
+    /// URLs of a node's children.
+    fn child_urls(node: &CrawlTreeNode) -> Vec<&str> {
+        node.children.iter().map(|c| c.url.as_str()).collect()
+    }
+
+    #[test]
+    fn nests_rows_under_their_parents() {
+        let root = build_tree(vec![
+            row("/", 0, None),
+            row("/a", 1, Some("/")),
+            row("/b", 1, Some("/")),
+            row("/a/1", 2, Some("/a")),
+            row("/a/1/x", 3, Some("/a/1")),
+        ])
+        .unwrap();
+
+        assert_eq!(root.url, "/");
+        assert_eq!(child_urls(&root), ["/a", "/b"]);
+        assert_eq!(child_urls(&root.children[0]), ["/a/1"]);
+        assert_eq!(child_urls(&root.children[0].children[0]), ["/a/1/x"]);
+        assert!(root.children[1].children.is_empty());
+    }
+
+    #[test]
+    fn rows_with_a_missing_parent_hang_off_the_root() {
+        let root = build_tree(vec![
+            row("/", 0, None),
+            row("/orphan", 2, Some("/gone")),
+            row("/no-parent", 1, None),
+        ])
+        .unwrap();
+
+        assert_eq!(child_urls(&root), ["/orphan", "/no-parent"]);
+    }
+
+    #[test]
+    fn parents_must_be_shallower() {
+        // A parent at the same depth (or deeper) could form a cycle
+        let root = build_tree(vec![
+            row("/", 0, None),
+            row("/a", 1, Some("/b")),
+            row("/b", 1, Some("/a")),
+            row("/c", 2, Some("/d")),
+            row("/d", 3, Some("/c")),
+        ])
+        .unwrap();
+
+        assert_eq!(child_urls(&root), ["/a", "/b", "/c"]);
+        assert_eq!(child_urls(&root.children[2]), ["/d"]);
+        assert!(build_tree(vec![]).is_none());
+    }
+}
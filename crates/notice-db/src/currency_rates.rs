@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct CurrencyRateRow {
+    pub code: String,
+    /// Units of this currency per 1 USD.
+    pub per_usd: f64,
+    pub source: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Rates file format, as published by most exchange-rate APIs:
+/// `{"base": "EUR", "date": "2025-01-03", "rates": {"USD": 1.03, ...}}`
+#[derive(Debug, Deserialize)]
+pub struct RatesFile {
+    pub base: String,
+    #[serde(default)]
+    pub date: Option<String>,
+    pub rates: HashMap<String, f64>,
+}
+
+/// Get a single rate by ISO 4217 code.
+pub async fn get(pool: &PgPool, code: &str) -> Result<Option<CurrencyRateRow>, notice_core::Error> {
+    sqlx::query_as::<_, CurrencyRateRow>("SELECT * FROM currency_rates WHERE code = $1")
+        .bind(code)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// List all stored rates.
+pub async fn list(pool: &PgPool) -> Result<Vec<CurrencyRateRow>, notice_core::Error> {
+    sqlx::query_as::<_, CurrencyRateRow>("SELECT * FROM currency_rates ORDER BY code")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Upsert every rate in a rates file.
+/// Rates are rebased to USD, so the file may use any base currency
+/// as long as it includes USD. Returns the number of rates written.
+pub async fn import(
+    pool: &PgPool,
+    file: &RatesFile,
+    source: &str,
+) -> Result<usize, notice_core::Error> {
+    let base = file.base.to_uppercase();
+
+    let mut rates: HashMap<String, f64> = file
+        .rates
+        .iter()
+        .map(|(code, rate)| (code.to_uppercase(), *rate))
+        .collect();
+    rates.insert(base.clone(), 1.0);
+
+    let usd = *rates.get("USD").ok_or_else(|| {
+        notice_core::Error::Validation(format!("Rates file (base {}) has no USD rate", base))
+    })?;
+    if usd <= 0.0 {
+        return Err(notice_core::Error::Validation(
+            "USD rate must be positive".into(),
+        ));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    let mut written = 0;
+    for (code, rate) in &rates {
+        if code.len() != 3 || !rate.is_finite() || *rate <= 0.0 {
+            tracing::warn!("Skipping invalid rate {} = {}", code, rate);
+            continue;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO currency_rates (code, per_usd, source, updated_at)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (code) DO UPDATE
+            SET per_usd = $2, source = $3, updated_at = NOW()
+            "#,
+        )
+        .bind(code)
+        .bind(rate / usd)
+        .bind(source)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+        written += 1;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    Ok(written)
+}
@@ -0,0 +1,256 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use sqlx::types::Json;
+
+/// Source tag for definitions generated by the AI fallback.
+pub const GENERATED_SOURCE: &str = "generated";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sense {
+    pub gloss: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<String>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct DefinitionRow {
+    pub id: i64,
+    pub term: String,
+    pub term_normalized: String,
+    pub part_of_speech: String,
+    pub pronunciation: Option<String>,
+    pub senses: Json<Vec<Sense>>,
+    pub synonyms: Vec<String>,
+    pub source: String,
+    pub created_at: DateTime<Utc>,
+    /// Set for generated definitions, which are only cached
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A definition ready to insert (also the shape returned to clients).
+#[derive(Debug, Clone, Serialize)]
+pub struct NewDefinition {
+    pub term: String,
+    pub part_of_speech: String,
+    pub pronunciation: Option<String>,
+    pub senses: Vec<Sense>,
+    pub synonyms: Vec<String>,
+}
+
+impl NewDefinition {
+    /// Whether `other` is the same headword and part of speech, i.e. a
+    /// second etymology that should be folded into this entry.
+    pub fn same_entry(&self, other: &NewDefinition) -> bool {
+        self.part_of_speech == other.part_of_speech
+            && normalize_term(&self.term) == normalize_term(&other.term)
+    }
+
+    pub fn merge(&mut self, other: NewDefinition) {
+        self.senses.extend(other.senses);
+        if self.pronunciation.is_none() {
+            self.pronunciation = other.pronunciation;
+        }
+        for synonym in other.synonyms {
+            if !self.synonyms.contains(&synonym) {
+                self.synonyms.push(synonym);
+            }
+        }
+    }
+}
+
+impl From<DefinitionRow> for NewDefinition {
+    fn from(row: DefinitionRow) -> Self {
+        Self {
+            term: row.term,
+            part_of_speech: row.part_of_speech,
+            pronunciation: row.pronunciation,
+            senses: row.senses.0,
+            synonyms: row.synonyms,
+        }
+    }
+}
+
+/// Lookup key for a term: trimmed and lowercased.
+pub fn normalize_term(term: &str) -> String {
+    term.trim().to_lowercase()
+}
+
+/// All unexpired entries for a term, dictionary sources before generated
+/// ones.
+pub async fn lookup(pool: &PgPool, term: &str) -> Result<Vec<DefinitionRow>, notice_core::Error> {
+    sqlx::query_as::<_, DefinitionRow>(
+        r#"
+        SELECT * FROM definitions
+        WHERE term_normalized = $1
+          AND (expires_at IS NULL OR expires_at > NOW())
+        ORDER BY (source = $2), id
+        "#,
+    )
+    .bind(normalize_term(term))
+    .bind(GENERATED_SOURCE)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Upsert a batch of definitions in one transaction.
+/// Re-importing a dump replaces existing entries from the same source.
+pub async fn insert_batch(
+    pool: &PgPool,
+    entries: &[NewDefinition],
+    source: &str,
+) -> Result<usize, notice_core::Error> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    for entry in entries {
+        sqlx::query(
+            r#"
+            INSERT INTO definitions
+                (term, term_normalized, part_of_speech, pronunciation, senses, synonyms, source)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (term_normalized, part_of_speech, source) DO UPDATE
+            SET term = $1, pronunciation = $4, senses = $5, synonyms = $6
+            "#,
+        )
+        .bind(&entry.term)
+        .bind(normalize_term(&entry.term))
+        .bind(&entry.part_of_speech)
+        .bind(&entry.pronunciation)
+        .bind(Json(&entry.senses))
+        .bind(&entry.synonyms)
+        .bind(source)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    Ok(entries.len())
+}
+
+/// Cache a generated definition for `ttl_secs`, replacing an earlier one
+/// for the same term and part of speech.
+pub async fn insert_generated(
+    pool: &PgPool,
+    entry: &NewDefinition,
+    ttl_secs: i64,
+) -> Result<(), notice_core::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO definitions
+            (term, term_normalized, part_of_speech, senses, synonyms, source, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6, NOW() + make_interval(secs => $7))
+        ON CONFLICT (term_normalized, part_of_speech, source) DO UPDATE
+        SET term = $1, senses = $4, synonyms = $5, created_at = NOW(),
+            expires_at = EXCLUDED.expires_at
+        "#,
+    )
+    .bind(&entry.term)
+    .bind(normalize_term(&entry.term))
+    .bind(&entry.part_of_speech)
+    .bind(Json(&entry.senses))
+    .bind(&entry.synonyms)
+    .bind(GENERATED_SOURCE)
+    .bind(ttl_secs as f64)
+    .execute(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+// ─── Wiktionary import (wiktextract / kaikki.org JSONL) ───
+
+#[derive(Deserialize)]
+struct WiktextractEntry {
+    word: String,
+    pos: String,
+    #[serde(default)]
+    lang_code: Option<String>,
+    #[serde(default)]
+    senses: Vec<WiktextractSense>,
+    #[serde(default)]
+    sounds: Vec<WiktextractSound>,
+    #[serde(default)]
+    synonyms: Vec<WiktextractWord>,
+}
+
+#[derive(Deserialize)]
+struct WiktextractSense {
+    #[serde(default)]
+    glosses: Vec<String>,
+    #[serde(default)]
+    examples: Vec<WiktextractExample>,
+    #[serde(default)]
+    synonyms: Vec<WiktextractWord>,
+}
+
+#[derive(Deserialize)]
+struct WiktextractExample {
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct WiktextractSound {
+    ipa: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct WiktextractWord {
+    word: String,
+}
+
+/// Parse one line of a wiktextract dump. Returns None for other languages,
+/// malformed lines and entries without any glosses.
+pub fn parse_wiktextract_line(line: &str, lang_code: &str) -> Option<NewDefinition> {
+    let entry: WiktextractEntry = serde_json::from_str(line).ok()?;
+
+    if entry
+        .lang_code
+        .as_deref()
+        .is_some_and(|code| code != lang_code)
+    {
+        return None;
+    }
+
+    let mut synonyms: Vec<String> = Vec::new();
+    let mut senses = Vec::new();
+
+    for sense in entry.senses {
+        // The last gloss is the most specific; earlier ones are parent senses
+        let Some(gloss) = sense.glosses.into_iter().last() else {
+            continue;
+        };
+        let examples = sense
+            .examples
+            .into_iter()
+            .filter_map(|e| e.text)
+            .take(2)
+            .collect();
+        senses.push(Sense { gloss, examples });
+
+        synonyms.extend(sense.synonyms.into_iter().map(|s| s.word));
+    }
+
+    if senses.is_empty() {
+        return None;
+    }
+
+    synonyms.extend(entry.synonyms.into_iter().map(|s| s.word));
+    let mut seen = std::collections::HashSet::new();
+    synonyms.retain(|s| seen.insert(s.clone()));
+
+    Some(NewDefinition {
+        term: entry.word,
+        part_of_speech: entry.pos,
+        pronunciation: entry.sounds.into_iter().find_map(|s| s.ipa),
+        senses,
+        synonyms,
+    })
+}
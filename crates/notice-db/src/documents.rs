@@ -1,8 +1,14 @@
 use chrono::{DateTime, Utc};
+use notice_core::types::ScrapedPage;
 use serde::Serialize;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::index_outbox;
+use crate::index_sync::Watermark;
+use crate::recrawl;
+use crate::revisions;
+
 // ─── Row Types ───
 
 /// Full document row (includes raw_content).
@@ -16,6 +22,7 @@ pub struct DocumentRow {
     pub summary: Option<String>,
     pub status: String,
     pub quality_score: f64,
+    pub published_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -30,6 +37,7 @@ pub struct DocumentListRow {
     pub summary: Option<String>,
     pub status: String,
     pub quality_score: f64,
+    pub published_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -44,13 +52,18 @@ pub async fn insert(
     title: Option<&str>,
     raw_content: &str,
     quality_score: f64,
+    published_at: Option<DateTime<Utc>>,
 ) -> Result<DocumentRow, notice_core::Error> {
     let domain = extract_domain(doc_url)?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
 
-    sqlx::query_as::<_, DocumentRow>(
+    let doc = sqlx::query_as::<_, DocumentRow>(
         r#"
-        INSERT INTO documents (url, domain, title, raw_content, quality_score)
-        VALUES ($1, $2, $3, $4, $5)
+        INSERT INTO documents (url, domain, title, raw_content, quality_score, published_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
         RETURNING *
         "#,
     )
@@ -59,14 +72,67 @@ pub async fn insert(
     .bind(title)
     .bind(raw_content)
     .bind(quality_score)
-    .fetch_one(pool)
+    .bind(published_at)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|e| match &e {
         sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
             notice_core::Error::Conflict(format!("Document already exists: {}", doc_url))
         }
         _ => notice_core::Error::Database(e.to_string()),
-    })
+    })?;
+
+    index_outbox::enqueue(&mut tx, doc.id, "upsert").await?;
+    recrawl::track(&mut tx, doc.id, raw_content).await?;
+    tx.commit()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(doc)
+}
+
+/// Replace a recrawled document's content, saving the old version as a
+/// revision. Its summary is kept until a new one is stored, but the status
+/// goes back to 'pending_summary'. The page's validators are stored with it.
+pub async fn update_content(
+    pool: &PgPool,
+    id: Uuid,
+    page: &ScrapedPage,
+    quality_score: f64,
+) -> Result<DocumentRow, notice_core::Error> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    revisions::record(&mut tx, id).await?;
+
+    let doc = sqlx::query_as::<_, DocumentRow>(
+        r#"
+        UPDATE documents
+        SET title = $2,
+            raw_content = $3,
+            quality_score = $4,
+            published_at = COALESCE($5, published_at),
+            status = 'pending_summary'
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(page.title.as_deref())
+    .bind(&page.text_content)
+    .bind(quality_score)
+    .bind(page.published_at)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    index_outbox::enqueue(&mut tx, id, "upsert").await?;
+    recrawl::set_content(&mut tx, id, page).await?;
+    tx.commit()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(doc)
 }
 
 /// Get a document by ID.
@@ -78,6 +144,15 @@ pub async fn get_by_id(pool: &PgPool, id: Uuid) -> Result<Option<DocumentRow>, n
         .map_err(|e| notice_core::Error::Database(e.to_string()))
 }
 
+/// Get documents by ID, in no particular order. Missing IDs are skipped.
+pub async fn get_many(pool: &PgPool, ids: &[Uuid]) -> Result<Vec<DocumentRow>, notice_core::Error> {
+    sqlx::query_as::<_, DocumentRow>("SELECT * FROM documents WHERE id = ANY($1)")
+        .bind(ids)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
 /// Get a document by URL.
 pub async fn get_by_url(
     pool: &PgPool,
@@ -96,7 +171,12 @@ pub async fn update_summary(
     id: Uuid,
     summary: &str,
 ) -> Result<DocumentRow, notice_core::Error> {
-    sqlx::query_as::<_, DocumentRow>(
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    let doc = sqlx::query_as::<_, DocumentRow>(
         r#"
         UPDATE documents
         SET summary = $2, status = 'summarized'
@@ -106,21 +186,61 @@ pub async fn update_summary(
     )
     .bind(id)
     .bind(summary)
-    .fetch_one(pool)
+    .fetch_one(&mut *tx)
     .await
-    .map_err(|e| notice_core::Error::Database(e.to_string()))
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    index_outbox::enqueue(&mut tx, id, "upsert").await?;
+    tx.commit()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(doc)
 }
 
 /// Mark a document's summarization as failed.
 pub async fn mark_summary_failed(pool: &PgPool, id: Uuid) -> Result<(), notice_core::Error> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
     sqlx::query("UPDATE documents SET status = 'failed' WHERE id = $1")
         .bind(id)
-        .execute(pool)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    index_outbox::enqueue(&mut tx, id, "upsert").await?;
+    tx.commit()
         .await
         .map_err(|e| notice_core::Error::Database(e.to_string()))?;
     Ok(())
 }
 
+/// Delete a document (its passages go with it). Returns false if it
+/// didn't exist.
+pub async fn delete(pool: &PgPool, id: Uuid) -> Result<bool, notice_core::Error> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    let result = sqlx::query("DELETE FROM documents WHERE id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    index_outbox::enqueue(&mut tx, id, "delete").await?;
+    tx.commit()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(true)
+}
+
 /// List documents with pagination (lightweight — no raw_content).
 pub async fn list(
     pool: &PgPool,
@@ -129,7 +249,8 @@ pub async fn list(
 ) -> Result<Vec<DocumentListRow>, notice_core::Error> {
     sqlx::query_as::<_, DocumentListRow>(
         r#"
-        SELECT id, url, domain, title, summary, status, quality_score, created_at, updated_at
+        SELECT id, url, domain, title, summary, status, quality_score, published_at, created_at,
+               updated_at
         FROM documents
         ORDER BY created_at DESC
         LIMIT $1 OFFSET $2
@@ -151,17 +272,102 @@ pub async fn count(pool: &PgPool) -> Result<i64, notice_core::Error> {
     Ok(row.0)
 }
 
-/// List full documents (including raw_content) for Meilisearch sync.
-/// Only used by the resync endpoint — avoid using in regular API responses.
-pub async fn list_full(
+/// Full documents changed after `after` (an `(updated_at, id)` position),
+/// oldest change first. For the incremental index resync.
+pub async fn list_changed_since(
     pool: &PgPool,
+    after: Option<Watermark>,
     limit: i64,
-    offset: i64,
 ) -> Result<Vec<DocumentRow>, notice_core::Error> {
     sqlx::query_as::<_, DocumentRow>(
         r#"
         SELECT *
         FROM documents
+        WHERE $1::timestamptz IS NULL OR (updated_at, id) > ($1, $2)
+        ORDER BY updated_at, id
+        LIMIT $3
+        "#,
+    )
+    .bind(after.map(|m| m.updated_at_mark))
+    .bind(after.map(|m| m.id_mark))
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Count documents changed after `after`.
+pub async fn count_changed_since(
+    pool: &PgPool,
+    after: Option<Watermark>,
+) -> Result<i64, notice_core::Error> {
+    let row: (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*)
+        FROM documents
+        WHERE $1::timestamptz IS NULL OR (updated_at, id) > ($1, $2)
+        "#,
+    )
+    .bind(after.map(|m| m.updated_at_mark))
+    .bind(after.map(|m| m.id_mark))
+    .fetch_one(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(row.0)
+}
+
+/// IDs and `updated_at` of documents after `after` in ID order, for
+/// comparing with the search index.
+pub async fn list_versions(
+    pool: &PgPool,
+    after: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<(Uuid, DateTime<Utc>)>, notice_core::Error> {
+    sqlx::query_as::<_, (Uuid, DateTime<Utc>)>(
+        r#"
+        SELECT id, updated_at
+        FROM documents
+        WHERE $1::uuid IS NULL OR id > $1
+        ORDER BY id
+        LIMIT $2
+        "#,
+    )
+    .bind(after)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Titles of the best documents, for query suggestions.
+pub async fn list_titles(pool: &PgPool, limit: i64) -> Result<Vec<String>, notice_core::Error> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT title
+        FROM documents
+        WHERE title IS NOT NULL AND title <> ''
+        ORDER BY quality_score DESC, created_at DESC
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    Ok(rows.into_iter().map(|(title,)| title).collect())
+}
+
+/// Titles and raw text, for building the spelling dictionary.
+pub async fn list_text(
+    pool: &PgPool,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<(Option<String>, String)>, notice_core::Error> {
+    sqlx::query_as::<_, (Option<String>, String)>(
+        r#"
+        SELECT title, raw_content
+        FROM documents
         ORDER BY created_at ASC
         LIMIT $1 OFFSET $2
         "#,
@@ -0,0 +1,5 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+pub(crate) fn day_start(date: NaiveDate) -> Option<DateTime<Utc>> {
+    date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc())
+}
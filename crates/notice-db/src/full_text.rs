@@ -0,0 +1,83 @@
+use notice_core::types::{ParsedQuery, with_www};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::filters::day_start;
+
+/// A document matched by PostgreSQL full-text search.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct FullTextHit {
+    pub id: Uuid,
+    pub url: String,
+    pub title: Option<String>,
+    /// Content around the matched words
+    pub snippet: String,
+    /// `ts_rank_cd` normalized to 0.0–1.0
+    pub score: f64,
+    /// Matches across all pages
+    pub total: i64,
+}
+
+/// Search `documents.search_vector`, the fallback for when the search
+/// backend is unavailable. Supports the same operators as the backend;
+/// `newest_first` orders by published date instead of rank.
+pub async fn search(
+    pool: &PgPool,
+    parsed: &ParsedQuery,
+    limit: i64,
+    offset: i64,
+    newest_first: bool,
+) -> Result<Vec<FullTextHit>, notice_core::Error> {
+    sqlx::query_as::<_, FullTextHit>(
+        r#"
+        WITH q AS (SELECT websearch_to_tsquery('english', $1) AS query),
+        page AS (
+            SELECT d.id, d.url, d.title, d.summary, d.raw_content,
+                   COALESCE(d.published_at, d.created_at) AS freshness,
+                   ts_rank_cd(d.search_vector, q.query, 32) AS rank,
+                   COUNT(*) OVER () AS total
+            FROM documents d, q
+            WHERE ($1 = '' OR d.search_vector @@ q.query)
+              AND (cardinality($2::text[]) = 0 OR d.domain = ANY($2))
+              AND NOT (d.domain = ANY($3::text[]))
+              AND ($4::text IS NULL OR d.status = $4)
+              AND ($5::timestamptz IS NULL OR COALESCE(d.published_at, d.created_at) >= $5)
+              AND ($6::timestamptz IS NULL OR COALESCE(d.published_at, d.created_at) < $6)
+            ORDER BY CASE WHEN $7 THEN COALESCE(d.published_at, d.created_at) END DESC NULLS LAST,
+                     rank DESC
+            LIMIT $8 OFFSET $9
+        )
+        SELECT page.id, page.url, page.title,
+               CASE WHEN $1 = '' THEN COALESCE(page.summary, LEFT(page.raw_content, 300))
+                    ELSE ts_headline('english', LEFT(page.raw_content, 20000), q.query,
+                                     'MaxWords=40, MinWords=20, StartSel="", StopSel=""')
+               END AS snippet,
+               page.rank::float8 AS score,
+               page.total
+        FROM page, q
+        ORDER BY CASE WHEN $7 THEN page.freshness END DESC NULLS LAST, page.rank DESC
+        "#,
+    )
+    .bind(websearch_text(parsed))
+    .bind(with_www(&parsed.sites))
+    .bind(with_www(&parsed.excluded_sites))
+    .bind(parsed.status.as_deref())
+    .bind(parsed.after.and_then(day_start))
+    .bind(parsed.before.and_then(day_start))
+    .bind(newest_first)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// The free text in `websearch_to_tsquery` syntax: quoted phrases and
+/// `-word` exclusions carry over as-is.
+fn websearch_text(parsed: &ParsedQuery) -> String {
+    let mut parts: Vec<String> = parsed.terms.clone();
+    parts.extend(parsed.phrases.iter().map(|p| format!("\"{}\"", p)));
+    parts.extend(parsed.excluded.iter().map(|e| format!("-{}", e)));
+    parts.join(" ")
+}
@@ -0,0 +1,202 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+// ─── Row Types ───
+
+/// A pending change to push to the search backend.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct OutboxEntry {
+    pub id: i64,
+    pub document_id: Uuid,
+    /// "upsert" or "delete"
+    pub op: String,
+    pub attempts: i32,
+}
+
+/// An entry that ran out of attempts.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct FailedEntry {
+    pub id: i64,
+    pub document_id: Uuid,
+    pub op: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub failed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct OutboxStats {
+    pub pending: i64,
+    /// Pending entries that have failed at least once
+    pub retrying: i64,
+    pub failed: i64,
+    pub oldest_pending_at: Option<DateTime<Utc>>,
+}
+
+// ─── Queries ───
+
+/// Record a document change. Call inside the transaction making it.
+pub(crate) async fn enqueue(
+    conn: &mut PgConnection,
+    document_id: Uuid,
+    op: &str,
+) -> Result<(), notice_core::Error> {
+    sqlx::query("INSERT INTO index_outbox (document_id, op) VALUES ($1, $2)")
+        .bind(document_id)
+        .bind(op)
+        .execute(conn)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Queue documents for re-indexing ("upsert") or removal from the index
+/// ("delete") outside of a document change, e.g. to repair drift.
+pub async fn enqueue_many(
+    pool: &PgPool,
+    document_ids: &[Uuid],
+    op: &str,
+) -> Result<u64, notice_core::Error> {
+    if document_ids.is_empty() {
+        return Ok(0);
+    }
+    let result =
+        sqlx::query("INSERT INTO index_outbox (document_id, op) SELECT UNNEST($1::uuid[]), $2")
+            .bind(document_ids)
+            .bind(op)
+            .execute(pool)
+            .await
+            .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(result.rows_affected())
+}
+
+/// Claim up to `limit` due entries, oldest first. Claimed entries are not
+/// handed out again for `lease_secs`, so a crashed dispatcher's work is
+/// picked up later and concurrent dispatchers don't share entries.
+pub async fn claim(
+    pool: &PgPool,
+    limit: i64,
+    lease_secs: i64,
+) -> Result<Vec<OutboxEntry>, notice_core::Error> {
+    let mut entries = sqlx::query_as::<_, OutboxEntry>(
+        r#"
+        UPDATE index_outbox
+        SET next_attempt_at = NOW() + make_interval(secs => $2)
+        WHERE id IN (
+            SELECT id
+            FROM index_outbox
+            WHERE failed_at IS NULL AND next_attempt_at <= NOW()
+            ORDER BY id
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, document_id, op, attempts
+        "#,
+    )
+    .bind(limit)
+    .bind(lease_secs as f64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    entries.sort_by_key(|e| e.id);
+    Ok(entries)
+}
+
+/// Remove entries that reached the search backend.
+pub async fn complete(pool: &PgPool, ids: &[i64]) -> Result<(), notice_core::Error> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    sqlx::query("DELETE FROM index_outbox WHERE id = ANY($1)")
+        .bind(ids)
+        .execute(pool)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Record a failed attempt: retry after `retry_in_secs`, or with
+/// `give_up`, keep the entry as failed until it is retried by hand.
+pub async fn record_failure(
+    pool: &PgPool,
+    id: i64,
+    error: &str,
+    retry_in_secs: i64,
+    give_up: bool,
+) -> Result<(), notice_core::Error> {
+    sqlx::query(
+        r#"
+        UPDATE index_outbox
+        SET attempts = attempts + 1,
+            last_error = $2,
+            next_attempt_at = NOW() + make_interval(secs => $3),
+            failed_at = CASE WHEN $4 THEN NOW() END
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(error)
+    .bind(retry_in_secs as f64)
+    .bind(give_up)
+    .execute(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Pending, retrying and failed counts.
+pub async fn stats(pool: &PgPool) -> Result<OutboxStats, notice_core::Error> {
+    sqlx::query_as::<_, OutboxStats>(
+        r#"
+        SELECT
+            COUNT(*) FILTER (WHERE failed_at IS NULL) AS pending,
+            COUNT(*) FILTER (WHERE failed_at IS NULL AND attempts > 0) AS retrying,
+            COUNT(*) FILTER (WHERE failed_at IS NOT NULL) AS failed,
+            MIN(created_at) FILTER (WHERE failed_at IS NULL) AS oldest_pending_at
+        FROM index_outbox
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Most recent failed entries.
+pub async fn list_failed(
+    pool: &PgPool,
+    limit: i64,
+) -> Result<Vec<FailedEntry>, notice_core::Error> {
+    sqlx::query_as::<_, FailedEntry>(
+        r#"
+        SELECT id, document_id, op, attempts, last_error, failed_at, created_at
+        FROM index_outbox
+        WHERE failed_at IS NOT NULL
+        ORDER BY failed_at DESC
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Put every failed entry back in the queue with fresh attempts.
+/// Returns how many were requeued.
+pub async fn retry_failed(pool: &PgPool) -> Result<u64, notice_core::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE index_outbox
+        SET attempts = 0, failed_at = NULL, next_attempt_at = NOW()
+        WHERE failed_at IS NOT NULL
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(result.rows_affected())
+}
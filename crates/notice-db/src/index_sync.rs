@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Position of the last document pushed by an incremental resync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::FromRow)]
+pub struct Watermark {
+    pub updated_at_mark: DateTime<Utc>,
+    pub id_mark: Uuid,
+}
+
+/// The backend's watermark, None if it was never resynced.
+pub async fn watermark(
+    pool: &PgPool,
+    backend: &str,
+) -> Result<Option<Watermark>, notice_core::Error> {
+    sqlx::query_as::<_, Watermark>(
+        "SELECT updated_at_mark, id_mark FROM index_sync_state WHERE backend = $1",
+    )
+    .bind(backend)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Move the backend's watermark.
+pub async fn set_watermark(
+    pool: &PgPool,
+    backend: &str,
+    mark: Watermark,
+) -> Result<(), notice_core::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO index_sync_state (backend, updated_at_mark, id_mark)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (backend) DO UPDATE
+        SET updated_at_mark = EXCLUDED.updated_at_mark,
+            id_mark = EXCLUDED.id_mark,
+            synced_at = NOW()
+        "#,
+    )
+    .bind(backend)
+    .bind(mark.updated_at_mark)
+    .bind(mark.id_mark)
+    .execute(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct IntentCacheRow {
+    pub query: String,
+    pub intent: String,
+    pub argument: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Look up a cached classification by normalized query.
+pub async fn get(pool: &PgPool, query: &str) -> Result<Option<IntentCacheRow>, notice_core::Error> {
+    sqlx::query_as::<_, IntentCacheRow>("SELECT * FROM intent_cache WHERE query = $1")
+        .bind(query)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Store (or refresh) a classification.
+pub async fn upsert(
+    pool: &PgPool,
+    query: &str,
+    intent: &str,
+    argument: Option<&str>,
+) -> Result<(), notice_core::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO intent_cache (query, intent, argument)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (query) DO UPDATE
+        SET intent = $2, argument = $3, created_at = NOW()
+        "#,
+    )
+    .bind(query)
+    .bind(intent)
+    .bind(argument)
+    .execute(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    Ok(())
+}
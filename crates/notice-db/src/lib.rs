@@ -1,8 +1,20 @@
+pub mod bangs;
+pub mod chunks;
 pub mod crawl_queue;
+pub mod currency_rates;
+pub mod definitions;
 pub mod documents;
+mod filters;
+pub mod full_text;
+pub mod index_outbox;
+pub mod index_sync;
+pub mod intent_cache;
 pub mod query_summaries;
+pub mod recrawl;
+pub mod revisions;
 pub mod search_history;
 pub mod users;
+pub mod vocabulary;
 
 use sqlx::postgres::{PgPool, PgPoolOptions};
 
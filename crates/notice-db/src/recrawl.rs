@@ -0,0 +1,183 @@
+use chrono::{DateTime, Utc};
+use notice_core::types::ScrapedPage;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+// ─── Row Types ───
+
+/// A document due for a recrawl, with what the last fetch saw.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct DueDocument {
+    pub document_id: Uuid,
+    pub url: String,
+    pub content_hash: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub interval_secs: i32,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct RecrawlStats {
+    pub tracked: i64,
+    pub due: i64,
+    /// Documents whose last recrawl failed
+    pub failing: i64,
+    pub next_crawl_at: Option<DateTime<Utc>>,
+}
+
+// ─── Queries ───
+
+/// SHA-256 (hex) of extracted text, as stored in `recrawl_state`.
+pub fn content_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Schedule a new document's first recrawl. Call inside the transaction
+/// inserting it.
+pub(crate) async fn track(
+    conn: &mut PgConnection,
+    document_id: Uuid,
+    raw_content: &str,
+) -> Result<(), notice_core::Error> {
+    sqlx::query("INSERT INTO recrawl_state (document_id, content_hash) VALUES ($1, $2)")
+        .bind(document_id)
+        .bind(content_hash(raw_content))
+        .execute(conn)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Record new content for a document, with the validators of the fetch
+/// that returned it. Call inside the transaction updating it.
+pub(crate) async fn set_content(
+    conn: &mut PgConnection,
+    document_id: Uuid,
+    page: &ScrapedPage,
+) -> Result<(), notice_core::Error> {
+    sqlx::query(
+        r#"
+        UPDATE recrawl_state
+        SET content_hash = $2, etag = $3, last_modified = $4, last_changed_at = NOW()
+        WHERE document_id = $1
+        "#,
+    )
+    .bind(document_id)
+    .bind(content_hash(&page.text_content))
+    .bind(page.etag.as_deref())
+    .bind(page.last_modified.as_deref())
+    .execute(conn)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Store the `ETag` and `Last-Modified` a fetch returned.
+pub async fn set_validators(
+    pool: &PgPool,
+    document_id: Uuid,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> Result<(), notice_core::Error> {
+    sqlx::query("UPDATE recrawl_state SET etag = $2, last_modified = $3 WHERE document_id = $1")
+        .bind(document_id)
+        .bind(etag)
+        .bind(last_modified)
+        .execute(pool)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Claim up to `limit` due documents, most overdue first. Claimed
+/// documents are not handed out again for `lease_secs`.
+pub async fn claim_due(
+    pool: &PgPool,
+    limit: i64,
+    lease_secs: i64,
+) -> Result<Vec<DueDocument>, notice_core::Error> {
+    sqlx::query_as::<_, DueDocument>(
+        r#"
+        UPDATE recrawl_state r
+        SET next_crawl_at = NOW() + make_interval(secs => $2)
+        FROM documents d
+        WHERE d.id = r.document_id
+          AND r.document_id IN (
+            SELECT document_id
+            FROM recrawl_state
+            WHERE next_crawl_at <= NOW()
+            ORDER BY next_crawl_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+          )
+        RETURNING r.document_id, d.url, r.content_hash, r.etag, r.last_modified, r.interval_secs
+        "#,
+    )
+    .bind(limit)
+    .bind(lease_secs as f64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Record a finished recrawl and schedule the next one `interval_secs`
+/// from now.
+pub async fn complete(
+    pool: &PgPool,
+    document_id: Uuid,
+    interval_secs: i32,
+) -> Result<(), notice_core::Error> {
+    sqlx::query(
+        r#"
+        UPDATE recrawl_state
+        SET interval_secs = $2,
+            next_crawl_at = NOW() + make_interval(secs => $2),
+            last_crawled_at = NOW(),
+            last_error = NULL
+        WHERE document_id = $1
+        "#,
+    )
+    .bind(document_id)
+    .bind(interval_secs)
+    .execute(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Record a failed recrawl. The interval is kept, so the page is tried
+/// again when it would have been anyway.
+pub async fn fail(pool: &PgPool, document_id: Uuid, error: &str) -> Result<(), notice_core::Error> {
+    sqlx::query(
+        r#"
+        UPDATE recrawl_state
+        SET next_crawl_at = NOW() + make_interval(secs => interval_secs),
+            last_error = $2
+        WHERE document_id = $1
+        "#,
+    )
+    .bind(document_id)
+    .bind(error)
+    .execute(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+pub async fn stats(pool: &PgPool) -> Result<RecrawlStats, notice_core::Error> {
+    sqlx::query_as::<_, RecrawlStats>(
+        r#"
+        SELECT
+            COUNT(*) AS tracked,
+            COUNT(*) FILTER (WHERE next_crawl_at <= NOW()) AS due,
+            COUNT(*) FILTER (WHERE last_error IS NOT NULL) AS failing,
+            MIN(next_crawl_at) AS next_crawl_at
+        FROM recrawl_state
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
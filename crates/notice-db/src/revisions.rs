@@ -0,0 +1,177 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+/// Revisions kept per document; older ones are dropped.
+const MAX_REVISIONS: i64 = 50;
+
+// ─── Row Types ───
+
+/// An earlier version of a document (without its content).
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct RevisionRow {
+    pub id: i64,
+    pub document_id: Uuid,
+    pub content_hash: String,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    /// When this version was first seen
+    pub fetched_at: DateTime<Utc>,
+    /// When a recrawl replaced it
+    pub replaced_at: DateTime<Utc>,
+}
+
+/// A version of a document with its content, for comparing.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct RevisionContent {
+    /// None for the document's current version
+    pub id: Option<i64>,
+    pub content_hash: String,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    pub raw_content: String,
+    pub fetched_at: DateTime<Utc>,
+}
+
+// ─── Queries ───
+
+/// Save a document's current version before it is replaced. Call inside
+/// the transaction replacing it.
+pub(crate) async fn record(
+    conn: &mut PgConnection,
+    document_id: Uuid,
+) -> Result<(), notice_core::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO document_revisions
+            (document_id, content_hash, title, summary, raw_content, fetched_at)
+        SELECT
+            d.id,
+            COALESCE(r.content_hash, encode(sha256(convert_to(d.raw_content, 'UTF8')), 'hex')),
+            d.title,
+            d.summary,
+            d.raw_content,
+            COALESCE(r.last_changed_at, d.created_at)
+        FROM documents d
+        LEFT JOIN recrawl_state r ON r.document_id = d.id
+        WHERE d.id = $1
+        "#,
+    )
+    .bind(document_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM document_revisions
+        WHERE document_id = $1
+          AND id NOT IN (
+            SELECT id FROM document_revisions
+            WHERE document_id = $1
+            ORDER BY id DESC
+            LIMIT $2
+          )
+        "#,
+    )
+    .bind(document_id)
+    .bind(MAX_REVISIONS)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// A document's earlier versions, newest first.
+pub async fn list(
+    pool: &PgPool,
+    document_id: Uuid,
+) -> Result<Vec<RevisionRow>, notice_core::Error> {
+    sqlx::query_as::<_, RevisionRow>(
+        r#"
+        SELECT id, document_id, content_hash, title, summary, fetched_at, replaced_at
+        FROM document_revisions
+        WHERE document_id = $1
+        ORDER BY id DESC
+        "#,
+    )
+    .bind(document_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// One of a document's earlier versions.
+pub async fn get(
+    pool: &PgPool,
+    document_id: Uuid,
+    id: i64,
+) -> Result<Option<RevisionContent>, notice_core::Error> {
+    sqlx::query_as::<_, RevisionContent>(
+        r#"
+        SELECT id, content_hash, title, summary, raw_content, fetched_at
+        FROM document_revisions
+        WHERE document_id = $1 AND id = $2
+        "#,
+    )
+    .bind(document_id)
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// The version that replaced revision `id`: the next revision, or the
+/// current document if `id` is the latest.
+pub async fn get_next(
+    pool: &PgPool,
+    document_id: Uuid,
+    id: i64,
+) -> Result<Option<RevisionContent>, notice_core::Error> {
+    let next = sqlx::query_as::<_, RevisionContent>(
+        r#"
+        SELECT id, content_hash, title, summary, raw_content, fetched_at
+        FROM document_revisions
+        WHERE document_id = $1 AND id > $2
+        ORDER BY id
+        LIMIT 1
+        "#,
+    )
+    .bind(document_id)
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    match next {
+        Some(next) => Ok(Some(next)),
+        None => get_current(pool, document_id).await,
+    }
+}
+
+/// The document as it is now.
+pub async fn get_current(
+    pool: &PgPool,
+    document_id: Uuid,
+) -> Result<Option<RevisionContent>, notice_core::Error> {
+    sqlx::query_as::<_, RevisionContent>(
+        r#"
+        SELECT
+            NULL::bigint AS id,
+            COALESCE(r.content_hash, encode(sha256(convert_to(d.raw_content, 'UTF8')), 'hex'))
+                AS content_hash,
+            d.title,
+            d.summary,
+            d.raw_content,
+            COALESCE(r.last_changed_at, d.created_at) AS fetched_at
+        FROM documents d
+        LEFT JOIN recrawl_state r ON r.document_id = d.id
+        WHERE d.id = $1
+        "#,
+    )
+    .bind(document_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
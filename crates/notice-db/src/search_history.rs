@@ -39,3 +39,109 @@ pub async fn record(
     .await
     .map_err(|e| notice_core::Error::Database(e.to_string()))
 }
+
+/// Queries that returned results for at least `min_searchers` different
+/// logged-in users, with how often they were searched. Requiring several
+/// searchers keeps one person's queries out of everyone's suggestions.
+/// Session IDs come from the client unchecked, so anonymous searches
+/// never count as a searcher.
+pub async fn popular(
+    pool: &PgPool,
+    min_searchers: i64,
+    limit: i64,
+) -> Result<Vec<(String, i64)>, notice_core::Error> {
+    sqlx::query_as::<_, (String, i64)>(
+        r#"
+        SELECT MIN(query) AS query, COUNT(*) AS uses
+        FROM search_history
+        WHERE intent = 'search' AND results_count > 0
+        GROUP BY lower(query)
+        HAVING COUNT(DISTINCT user_id) >= $1
+        ORDER BY uses DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(min_searchers)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// A user's own past queries starting with `prefix` (case-insensitive),
+/// most recent first.
+pub async fn user_prefix(
+    pool: &PgPool,
+    user_id: Uuid,
+    prefix: &str,
+    limit: i64,
+) -> Result<Vec<String>, notice_core::Error> {
+    let pattern = format!(
+        "{}%",
+        prefix
+            .to_lowercase()
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+
+    let rows: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT MIN(query)
+        FROM search_history
+        WHERE user_id = $1 AND lower(query) LIKE $2
+        GROUP BY lower(query)
+        ORDER BY MAX(created_at) DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(pattern)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    Ok(rows.into_iter().map(|(query,)| query).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "../../migrations")]
+    #[ignore = "needs DATABASE_URL pointing at a PostgreSQL server with pgvector"]
+    async fn only_logged_in_users_make_a_query_popular(pool: PgPool) {
+        let query = "rust async runtime";
+        let is_popular = || async {
+            popular(&pool, 2, 10)
+                .await
+                .unwrap()
+                .iter()
+                .any(|(q, _)| q == query)
+        };
+
+        // Anonymous searches count for nothing, whatever session they claim
+        record(&pool, None, None, query, "search", 1).await.unwrap();
+        for session in ["a", "b", "c"] {
+            record(&pool, None, Some(session), query, "search", 1)
+                .await
+                .unwrap();
+        }
+        assert!(!is_popular().await);
+
+        let alice = crate::users::create(&pool, "alice", "x").await.unwrap().id;
+        for _ in 0..2 {
+            record(&pool, Some(alice), None, query, "search", 1)
+                .await
+                .unwrap();
+        }
+        assert!(!is_popular().await);
+
+        let bob = crate::users::create(&pool, "bob", "x").await.unwrap().id;
+        record(&pool, Some(bob), Some("b"), query, "search", 1)
+            .await
+            .unwrap();
+        assert!(is_popular().await);
+    }
+}
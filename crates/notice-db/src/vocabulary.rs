@@ -0,0 +1,162 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+// ─── Row Types ───
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct SynonymGroupRow {
+    pub id: i64,
+    pub terms: Vec<String>,
+    /// Only the first term expands to the others
+    pub one_way: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewSynonymGroup {
+    pub terms: Vec<String>,
+    #[serde(default)]
+    pub one_way: bool,
+}
+
+impl From<SynonymGroupRow> for NewSynonymGroup {
+    fn from(row: SynonymGroupRow) -> Self {
+        Self {
+            terms: row.terms,
+            one_way: row.one_way,
+        }
+    }
+}
+
+// ─── Synonyms ───
+
+pub async fn list_synonyms(pool: &PgPool) -> Result<Vec<SynonymGroupRow>, notice_core::Error> {
+    sqlx::query_as::<_, SynonymGroupRow>("SELECT * FROM synonym_groups ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+pub async fn create_synonym(
+    pool: &PgPool,
+    group: &NewSynonymGroup,
+) -> Result<SynonymGroupRow, notice_core::Error> {
+    sqlx::query_as::<_, SynonymGroupRow>(
+        "INSERT INTO synonym_groups (terms, one_way) VALUES ($1, $2) RETURNING *",
+    )
+    .bind(&group.terms)
+    .bind(group.one_way)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Replace a group's terms. Returns None if there is no such group.
+pub async fn update_synonym(
+    pool: &PgPool,
+    id: i64,
+    group: &NewSynonymGroup,
+) -> Result<Option<SynonymGroupRow>, notice_core::Error> {
+    sqlx::query_as::<_, SynonymGroupRow>(
+        r#"
+        UPDATE synonym_groups
+        SET terms = $2, one_way = $3, updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(&group.terms)
+    .bind(group.one_way)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Returns false if there was nothing to delete.
+pub async fn delete_synonym(pool: &PgPool, id: i64) -> Result<bool, notice_core::Error> {
+    let result = sqlx::query("DELETE FROM synonym_groups WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(result.rows_affected() > 0)
+}
+
+// ─── Stop words ───
+
+/// All stop words, alphabetically.
+pub async fn list_stop_words(pool: &PgPool) -> Result<Vec<String>, notice_core::Error> {
+    sqlx::query_scalar("SELECT word FROM stop_words ORDER BY word")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
+
+/// Add stop words, skipping ones already there. Returns how many were new.
+pub async fn add_stop_words(pool: &PgPool, words: &[String]) -> Result<u64, notice_core::Error> {
+    let result = sqlx::query(
+        "INSERT INTO stop_words (word) SELECT UNNEST($1::text[]) ON CONFLICT (word) DO NOTHING",
+    )
+    .bind(words)
+    .execute(pool)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(result.rows_affected())
+}
+
+/// Returns false if there was nothing to delete.
+pub async fn delete_stop_word(pool: &PgPool, word: &str) -> Result<bool, notice_core::Error> {
+    let result = sqlx::query("DELETE FROM stop_words WHERE word = $1")
+        .bind(word)
+        .execute(pool)
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    Ok(result.rows_affected() > 0)
+}
+
+// ─── Import ───
+
+/// Add synonym groups and stop words in one transaction. With `replace`,
+/// everything already stored is dropped first.
+pub async fn import(
+    pool: &PgPool,
+    synonyms: &[NewSynonymGroup],
+    stop_words: &[String],
+    replace: bool,
+) -> Result<(), notice_core::Error> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    if replace {
+        sqlx::query("TRUNCATE synonym_groups, stop_words")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    }
+
+    for group in synonyms {
+        sqlx::query("INSERT INTO synonym_groups (terms, one_way) VALUES ($1, $2)")
+            .bind(&group.terms)
+            .bind(group.one_way)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+    }
+
+    sqlx::query(
+        "INSERT INTO stop_words (word) SELECT UNNEST($1::text[]) ON CONFLICT (word) DO NOTHING",
+    )
+    .bind(stop_words)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| notice_core::Error::Database(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| notice_core::Error::Database(e.to_string()))
+}
@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use notice_core::types::ParsedQuery;
+use uuid::Uuid;
+
+use crate::chunk::PassageHit;
+use crate::vocabulary::Vocabulary;
+use crate::{MeiliDocumentInput, MeiliPassageInput, SearchPage, SearchSort};
+
+/// Words left out of relevance scoring by the embedded backend, which
+/// fixes them when the index is built. Meilisearch uses the stored
+/// vocabulary (see [`crate::vocabulary`]).
+#[rustfmt::skip]
+pub const STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "is", "of", "and", "or", "in", "on", "at", "to", "for",
+    "with", "by", "about", "what", "how", "why", "where", "when", "which",
+    "who", "whom", "this", "that", "these", "those", "it", "they", "them",
+    "he", "she", "his", "her", "i", "you", "we", "me", "us", "my", "your", "our",
+];
+
+/// A page of hits as a backend returns it, before recency decay.
+#[derive(Debug, Clone)]
+pub struct BackendPage {
+    pub page: SearchPage,
+    /// `freshness_ts` of each result, in order
+    pub freshness: Vec<Option<i64>>,
+}
+
+/// Where documents and passages are indexed and searched.
+/// [`SearchClient`](crate::SearchClient) wraps one of these.
+#[async_trait]
+pub trait SearchBackend: Send + Sync {
+    /// Short name for logs and the health endpoint.
+    fn name(&self) -> &'static str;
+
+    async fn health(&self) -> Result<(), notice_core::Error>;
+
+    /// Create the indexes and apply their settings. Idempotent — safe to
+    /// call on every startup.
+    async fn configure(&self) -> Result<(), notice_core::Error>;
+
+    /// Replace the synonyms and stop words. Backends that fix them when
+    /// the index is built keep their own.
+    async fn set_vocabulary(&self, _vocabulary: &Vocabulary) -> Result<(), notice_core::Error> {
+        Ok(())
+    }
+
+    /// Whether the live indexes were built with older settings than this
+    /// build applies, so a reindex is due.
+    async fn needs_reindex(&self) -> Result<bool, notice_core::Error> {
+        Ok(false)
+    }
+
+    /// Create empty indexes with the current settings next to the live
+    /// ones and return a backend writing to them. Searches keep using the
+    /// live indexes until [`finish_reindex`](Self::finish_reindex).
+    async fn begin_reindex(&self) -> Result<Box<dyn SearchBackend>, notice_core::Error> {
+        Err(notice_core::Error::Search(format!(
+            "The {} backend does not support reindexing",
+            self.name()
+        )))
+    }
+
+    /// Atomically make the indexes built since `begin_reindex` live.
+    async fn finish_reindex(&self) -> Result<(), notice_core::Error> {
+        Err(notice_core::Error::Search(format!(
+            "The {} backend does not support reindexing",
+            self.name()
+        )))
+    }
+
+    /// Add or update documents, visible to searches once this returns.
+    async fn index_documents(&self, docs: &[MeiliDocumentInput]) -> Result<(), notice_core::Error>;
+
+    /// Remove a document and its passages.
+    async fn delete_document(&self, id: Uuid) -> Result<(), notice_core::Error>;
+
+    /// Replace all of a document's passages.
+    async fn replace_passages(
+        &self,
+        document_id: Uuid,
+        passages: &[MeiliPassageInput],
+    ) -> Result<(), notice_core::Error>;
+
+    async fn document_count(&self) -> Result<usize, notice_core::Error>;
+
+    /// Every indexed document's ID with the `updated_at_ms` it was
+    /// indexed with (None if indexed before that was recorded).
+    async fn indexed_versions(&self) -> Result<Vec<(Uuid, Option<i64>)>, notice_core::Error>;
+
+    /// Search documents with the query's operators applied as filters,
+    /// with domain and status facet counts for all matches. Scores are in
+    /// 0.0–1.0, higher is better.
+    async fn search(
+        &self,
+        parsed: &ParsedQuery,
+        limit: usize,
+        offset: usize,
+        sort: SearchSort,
+    ) -> Result<BackendPage, notice_core::Error>;
+
+    /// Best-matching passages for plain text, best first. With
+    /// `document_ids`, only passages of those documents are searched.
+    async fn search_passages(
+        &self,
+        text: &str,
+        document_ids: &[Uuid],
+        limit: usize,
+    ) -> Result<Vec<PassageHit>, notice_core::Error>;
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Passages grow up to about this many bytes before a new one starts.
+const TARGET_LENGTH: usize = 1200;
+
+/// No passage is longer than this; longer lines are split at a sentence
+/// or word boundary.
+const MAX_LENGTH: usize = 2000;
+
+/// A trailing piece shorter than this joins the passage before it.
+const MIN_LENGTH: usize = 200;
+
+/// A slice of a document's text, under its nearest heading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Passage {
+    pub index: usize,
+    pub heading: Option<String>,
+    pub content: String,
+    /// Byte offsets of `content` in the document text
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Split document text into passages. Headings are lines starting with
+/// `#`..`######` and a space, as the scraper writes them; a heading always
+/// starts a new passage and labels the passages under it. Within a section
+/// lines are packed up to `TARGET_LENGTH`.
+pub fn split(text: &str) -> Vec<Passage> {
+    let mut passages = Vec::new();
+    let mut heading: Option<String> = None;
+    // Byte range of the passage being built
+    let mut current: Option<(usize, usize)> = None;
+
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some(title) = heading_text(trimmed) {
+            flush(text, &mut current, &heading, &mut passages);
+            heading = Some(title.to_string());
+            continue;
+        }
+
+        let end = start + line.trim_end().len();
+        let start = start + (line.len() - line.trim_start().len());
+
+        // One overlong line becomes several passages
+        if end - start > MAX_LENGTH {
+            flush(text, &mut current, &heading, &mut passages);
+            let mut piece_start = start;
+            while end - piece_start > MAX_LENGTH {
+                let piece_end = break_point(text, piece_start, piece_start + MAX_LENGTH);
+                current = Some((piece_start, piece_end));
+                flush(text, &mut current, &heading, &mut passages);
+                piece_start = skip_whitespace(text, piece_end);
+            }
+            current = Some((piece_start, end));
+            continue;
+        }
+
+        current = match current {
+            Some((passage_start, _)) if end - passage_start <= TARGET_LENGTH => {
+                Some((passage_start, end))
+            }
+            Some(_) => {
+                flush(text, &mut current, &heading, &mut passages);
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    flush(text, &mut current, &heading, &mut passages);
+
+    passages
+}
+
+/// Close the current passage. A short one is merged into the previous
+/// passage when both sit under the same heading and the result still fits.
+fn flush(
+    text: &str,
+    current: &mut Option<(usize, usize)>,
+    heading: &Option<String>,
+    passages: &mut Vec<Passage>,
+) {
+    let Some((start, end)) = current.take() else {
+        return;
+    };
+
+    if end - start < MIN_LENGTH
+        && let Some(previous) = passages.last_mut()
+        && previous.heading == *heading
+        && end - previous.start <= MAX_LENGTH
+    {
+        previous.end = end;
+        previous.content = text[previous.start..end].to_string();
+        return;
+    }
+
+    passages.push(Passage {
+        index: passages.len(),
+        heading: heading.clone(),
+        content: text[start..end].to_string(),
+        start,
+        end,
+    });
+}
+
+/// "## Installation" → "Installation"
+fn heading_text(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    line[level..]
+        .strip_prefix(' ')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
+
+/// Where to cut text before `limit`: after the last sentence end, else at
+/// the last space, else at `limit` itself (on a char boundary).
+fn break_point(text: &str, start: usize, limit: usize) -> usize {
+    let mut limit = limit.min(text.len());
+    while !text.is_char_boundary(limit) {
+        limit -= 1;
+    }
+    let window = &text[start..limit];
+    let min = window.len() / 2;
+
+    if let Some(i) = window.rfind(". ").filter(|&i| i > min) {
+        return start + i + 1;
+    }
+    if let Some(i) = window.rfind(' ').filter(|&i| i > min) {
+        return start + i;
+    }
+    limit
+}
+
+fn skip_whitespace(text: &str, from: usize) -> usize {
+    from + (text[from..].len() - text[from..].trim_start().len())
+}
+
+/// A passage search hit.
+#[derive(Debug, Clone)]
+pub struct PassageHit {
+    pub document_id: Uuid,
+    pub chunk_index: usize,
+    pub heading: Option<String>,
+    /// The whole passage
+    pub content: String,
+    /// Cropped around the matched words
+    pub snippet: String,
+    pub score: Option<f64>,
+}
+
+/// Group passage hits by document, keeping each document's best
+/// `per_document` passages. Documents come out in order of their best hit.
+pub fn group_by_document(
+    hits: Vec<PassageHit>,
+    per_document: usize,
+) -> Vec<(Uuid, Vec<PassageHit>)> {
+    let mut order: Vec<Uuid> = Vec::new();
+    let mut groups: HashMap<Uuid, Vec<PassageHit>> = HashMap::new();
+
+    // Hits arrive best first, so the first passage seen per document is its best
+    for hit in hits {
+        let group = groups.entry(hit.document_id).or_insert_with(|| {
+            order.push(hit.document_id);
+            Vec::new()
+        });
+        if group.len() < per_document {
+            group.push(hit);
+        }
+    }
+
+    order
+        .into_iter()
+        .filter_map(|id| groups.remove(&id).map(|group| (id, group)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_headings_with_offsets() {
+        let text = "Intro line.\n## Install\nRun cargo add tokio.\n\n## Usage\nCall block_on.\n";
+        let passages = split(text);
+
+        assert_eq!(passages.len(), 3);
+        assert_eq!(passages[0].heading, None);
+        assert_eq!(passages[1].heading.as_deref(), Some("Install"));
+        assert_eq!(passages[1].content, "Run cargo add tokio.");
+        assert_eq!(passages[2].heading.as_deref(), Some("Usage"));
+        for passage in &passages {
+            assert_eq!(&text[passage.start..passage.end], passage.content);
+        }
+    }
+
+    #[test]
+    fn packs_and_splits_long_text() {
+        let paragraph = "word ".repeat(100);
+        let text = [paragraph.trim(); 10].join("\n");
+        let passages = split(&text);
+        assert!(passages.len() > 1);
+        assert!(passages.iter().all(|p| p.content.len() <= MAX_LENGTH));
+
+        let long_line = "Sentence one is here. ".repeat(300);
+        let passages = split(&long_line);
+        assert!(passages.len() >= 3);
+        assert!(passages.iter().all(|p| p.content.len() <= MAX_LENGTH));
+        assert!(passages[0].content.ends_with('.'));
+    }
+
+    #[test]
+    fn groups_hits_by_document() {
+        let hit = |doc: u128, chunk: usize| PassageHit {
+            document_id: Uuid::from_u128(doc),
+            chunk_index: chunk,
+            heading: None,
+            content: String::new(),
+            snippet: String::new(),
+            score: None,
+        };
+        let groups = group_by_document(vec![hit(2, 0), hit(1, 4), hit(2, 3), hit(2, 1)], 2);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, Uuid::from_u128(2));
+        let chunks: Vec<usize> = groups[0].1.iter().map(|h| h.chunk_index).collect();
+        assert_eq!(chunks, vec![0, 3]);
+    }
+}
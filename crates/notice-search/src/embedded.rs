@@ -0,0 +1,880 @@
+use async_trait::async_trait;
+use notice_core::types::{ParsedQuery, SearchFacets, SearchResult, with_www};
+use std::collections::HashMap;
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tantivy::aggregation::AggregationCollector;
+use tantivy::aggregation::agg_req::Aggregations;
+use tantivy::collector::{Count, DocSetCollector, TopDocs};
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{AllQuery, BooleanQuery, Occur, Query, QueryParser, RangeQuery, TermQuery};
+use tantivy::schema::{
+    FAST, Field, INDEXED, IndexRecordOption, STORED, STRING, Schema, TextFieldIndexing,
+    TextOptions, Value,
+};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::tokenizer::{
+    AsciiFoldingFilter, LowerCaser, RemoveLongFilter, SimpleTokenizer, StopWordFilter, TextAnalyzer,
+};
+use tantivy::{
+    DocAddress, Index, IndexReader, IndexWriter, Order, ReloadPolicy, TantivyDocument, Term,
+};
+use uuid::Uuid;
+
+use crate::backend::{BackendPage, STOP_WORDS, SearchBackend};
+use crate::chunk::PassageHit;
+use crate::{
+    MAX_FACET_VALUES, MeiliDocumentInput, MeiliPassageInput, SearchPage, SearchSort, query,
+    top_facets,
+};
+
+/// Analyzer for all text fields: lowercased, accents folded, stop words
+/// removed. No stemming or typo tolerance, unlike Meilisearch.
+const TOKENIZER: &str = "notice";
+
+/// Indexing memory budget per index, shared by the writer threads.
+const WRITER_MEMORY_BYTES: usize = 50_000_000;
+
+/// BM25 scores are unbounded; `s / (s + K)` maps them into 0.0–1.0 so
+/// they compare with Meilisearch ranking scores.
+const SCORE_SATURATION: f32 = 4.0;
+
+/// Deepest hit a search may reach: TopDocs reserves room for
+/// `limit + offset` hits up front.
+const MAX_RESULT_DEPTH: usize = 10_000;
+
+const SNIPPET_CHARS: usize = 300;
+const PASSAGE_SNIPPET_CHARS: usize = 400;
+
+/// Embedded Tantivy backend: the documents and passages indexes live in
+/// local directories, so no search server is needed.
+#[derive(Clone)]
+pub struct TantivyBackend {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    documents: Store,
+    passages: Store,
+    doc_fields: DocumentFields,
+    passage_fields: PassageFields,
+}
+
+/// One Tantivy index with its reader and (single) writer.
+struct Store {
+    index: Index,
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+}
+
+#[derive(Clone, Copy)]
+struct DocumentFields {
+    id: Field,
+    url: Field,
+    domain: Field,
+    title: Field,
+    summary: Field,
+    raw_content: Field,
+    status: Field,
+    quality_score: Field,
+    crawled_at_ts: Field,
+    published_at_ts: Field,
+    freshness_ts: Field,
+    updated_at_ms: Field,
+}
+
+#[derive(Clone, Copy)]
+struct PassageFields {
+    document_id: Field,
+    chunk_index: Field,
+    title: Field,
+    heading: Field,
+    content: Field,
+}
+
+impl TantivyBackend {
+    /// Open the indexes under `dir`, creating them if missing.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, notice_core::Error> {
+        let dir = dir.as_ref();
+        let backend = Self::build(Some(&dir.join("documents")), Some(&dir.join("passages")))?;
+        tracing::info!("Tantivy index opened at {}", dir.display());
+        Ok(backend)
+    }
+
+    /// Indexes held in memory, for tests.
+    pub fn in_memory() -> Result<Self, notice_core::Error> {
+        Self::build(None, None)
+    }
+
+    fn build(
+        documents_dir: Option<&Path>,
+        passages_dir: Option<&Path>,
+    ) -> Result<Self, notice_core::Error> {
+        let (doc_schema, doc_fields) = document_schema();
+        let (passage_schema, passage_fields) = passage_schema();
+        Ok(Self {
+            inner: Arc::new(Inner {
+                documents: Store::open(documents_dir, doc_schema)?,
+                passages: Store::open(passages_dir, passage_schema)?,
+                doc_fields,
+                passage_fields,
+            }),
+        })
+    }
+
+    /// Run index work off the async runtime: Tantivy reads and commits
+    /// block on disk.
+    async fn blocking<T, F>(&self, work: F) -> Result<T, notice_core::Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Inner) -> Result<T, notice_core::Error> + Send + 'static,
+    {
+        let inner = self.inner.clone();
+        tokio::task::spawn_blocking(move || work(&inner))
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?
+    }
+}
+
+impl Store {
+    fn open(dir: Option<&Path>, schema: Schema) -> Result<Self, notice_core::Error> {
+        let index = match dir {
+            Some(dir) => {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+                let directory = MmapDirectory::open(dir)
+                    .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+                match Index::open_or_create(directory, schema.clone()) {
+                    Ok(index) => index,
+                    // Built with an older schema. The index only mirrors
+                    // PostgreSQL, so start over and let a resync refill it.
+                    Err(tantivy::TantivyError::SchemaError(e)) => {
+                        tracing::warn!(
+                            dir = %dir.display(),
+                            error = %e,
+                            "Tantivy schema changed, recreating the index: run a full resync"
+                        );
+                        std::fs::remove_dir_all(dir)
+                            .and_then(|_| std::fs::create_dir_all(dir))
+                            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+                        let directory = MmapDirectory::open(dir)
+                            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+                        Index::create(directory, schema, Default::default())
+                            .map_err(|e| notice_core::Error::Search(e.to_string()))?
+                    }
+                    Err(e) => return Err(notice_core::Error::Search(e.to_string())),
+                }
+            }
+            None => Index::create_in_ram(schema),
+        };
+
+        let analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(RemoveLongFilter::limit(40))
+            .filter(LowerCaser)
+            .filter(AsciiFoldingFilter)
+            .filter(StopWordFilter::remove(
+                STOP_WORDS.iter().map(|w| w.to_string()),
+            ))
+            .build();
+        index.tokenizers().register(TOKENIZER, analyzer);
+
+        // Reloaded by hand after each commit, so writes are visible as
+        // soon as they return
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .map_err(|e: tantivy::TantivyError| notice_core::Error::Search(e.to_string()))?;
+        let writer = index
+            .writer(WRITER_MEMORY_BYTES)
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        Ok(Self {
+            index,
+            reader,
+            writer: Mutex::new(writer),
+        })
+    }
+
+    /// Apply changes through the writer, commit them and refresh the reader.
+    fn write(
+        &self,
+        changes: impl FnOnce(&IndexWriter) -> tantivy::Result<()>,
+    ) -> Result<(), notice_core::Error> {
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        changes(&writer)
+            .and_then(|_| writer.commit().map(|_| ()))
+            .and_then(|_| self.reader.reload())
+            .map_err(|e| notice_core::Error::Search(e.to_string()))
+    }
+}
+
+fn text_options() -> TextOptions {
+    TextOptions::default().set_stored().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer(TOKENIZER)
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+    )
+}
+
+fn document_schema() -> (Schema, DocumentFields) {
+    let mut builder = Schema::builder();
+    let fields = DocumentFields {
+        id: builder.add_text_field("id", STRING | STORED),
+        url: builder.add_text_field("url", text_options()),
+        domain: builder.add_text_field("domain", STRING | STORED | FAST),
+        title: builder.add_text_field("title", text_options()),
+        summary: builder.add_text_field("summary", text_options()),
+        raw_content: builder.add_text_field("raw_content", text_options()),
+        status: builder.add_text_field("status", STRING | STORED | FAST),
+        quality_score: builder.add_f64_field("quality_score", STORED | FAST),
+        crawled_at_ts: builder.add_i64_field("crawled_at_ts", STORED),
+        published_at_ts: builder.add_i64_field("published_at_ts", STORED),
+        freshness_ts: builder.add_i64_field("freshness_ts", INDEXED | STORED | FAST),
+        updated_at_ms: builder.add_i64_field("updated_at_ms", STORED),
+    };
+    (builder.build(), fields)
+}
+
+fn passage_schema() -> (Schema, PassageFields) {
+    let mut builder = Schema::builder();
+    let fields = PassageFields {
+        document_id: builder.add_text_field("document_id", STRING | STORED),
+        chunk_index: builder.add_u64_field("chunk_index", STORED),
+        title: builder.add_text_field("title", text_options()),
+        heading: builder.add_text_field("heading", text_options()),
+        content: builder.add_text_field("content", text_options()),
+    };
+    (builder.build(), fields)
+}
+
+impl Inner {
+    fn index_documents(&self, docs: &[MeiliDocumentInput]) -> Result<(), notice_core::Error> {
+        let f = self.doc_fields;
+        self.documents.write(|writer| {
+            for doc in docs {
+                let id = doc.id.to_string();
+                writer.delete_term(Term::from_field_text(f.id, &id));
+
+                let mut row = TantivyDocument::default();
+                row.add_text(f.id, &id);
+                row.add_text(f.url, &doc.url);
+                row.add_text(f.domain, &doc.domain);
+                if let Some(title) = &doc.title {
+                    row.add_text(f.title, title);
+                }
+                if let Some(summary) = &doc.summary {
+                    row.add_text(f.summary, summary);
+                }
+                row.add_text(f.raw_content, &doc.raw_content);
+                row.add_text(f.status, &doc.status);
+                row.add_f64(f.quality_score, doc.quality_score);
+                row.add_i64(f.crawled_at_ts, doc.crawled_at_ts);
+                if let Some(published) = doc.published_at_ts {
+                    row.add_i64(f.published_at_ts, published);
+                }
+                row.add_i64(f.freshness_ts, doc.freshness_ts);
+                row.add_i64(f.updated_at_ms, doc.updated_at_ms);
+                writer.add_document(row)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn replace_passages(
+        &self,
+        document_id: Uuid,
+        passages: &[MeiliPassageInput],
+    ) -> Result<(), notice_core::Error> {
+        let f = self.passage_fields;
+        self.passages.write(|writer| {
+            writer.delete_term(Term::from_field_text(
+                f.document_id,
+                &document_id.to_string(),
+            ));
+            for passage in passages {
+                let mut row = TantivyDocument::default();
+                row.add_text(f.document_id, passage.document_id.to_string());
+                row.add_u64(f.chunk_index, passage.chunk_index as u64);
+                if let Some(title) = &passage.title {
+                    row.add_text(f.title, title);
+                }
+                if let Some(heading) = &passage.heading {
+                    row.add_text(f.heading, heading);
+                }
+                row.add_text(f.content, &passage.content);
+                writer.add_document(row)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn delete_document(&self, id: Uuid) -> Result<(), notice_core::Error> {
+        let id_text = id.to_string();
+        let id_field = self.doc_fields.id;
+        self.documents.write(|writer| {
+            writer.delete_term(Term::from_field_text(id_field, &id_text));
+            Ok(())
+        })?;
+        self.replace_passages(id, &[])
+    }
+
+    fn indexed_versions(&self) -> Result<Vec<(Uuid, Option<i64>)>, notice_core::Error> {
+        let f = self.doc_fields;
+        let searcher = self.documents.reader.searcher();
+        let addresses = searcher
+            .search(&AllQuery, &DocSetCollector)
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        let mut versions = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let doc: TantivyDocument = searcher
+                .doc(address)
+                .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+            let id = doc
+                .get_first(f.id)
+                .and_then(|v| v.as_str())
+                .and_then(|id| id.parse::<Uuid>().ok());
+            if let Some(id) = id {
+                versions.push((id, doc.get_first(f.updated_at_ms).and_then(|v| v.as_i64())));
+            }
+        }
+        Ok(versions)
+    }
+
+    fn search(
+        &self,
+        parsed: &ParsedQuery,
+        limit: usize,
+        offset: usize,
+        sort: SearchSort,
+    ) -> Result<BackendPage, notice_core::Error> {
+        let f = self.doc_fields;
+        let searcher = self.documents.reader.searcher();
+
+        let mut parser = QueryParser::for_index(
+            &self.documents.index,
+            vec![f.title, f.summary, f.raw_content, f.url],
+        );
+        parser.set_field_boost(f.title, 3.0);
+        parser.set_field_boost(f.summary, 1.5);
+
+        let mut words: Vec<String> = parsed.terms.iter().map(|t| quoted(t)).collect();
+        words.extend(parsed.phrases.iter().map(|p| quoted(p)));
+        let text_query: Box<dyn Query> = if words.is_empty() {
+            Box::new(AllQuery)
+        } else {
+            parser.parse_query_lenient(&words.join(" ")).0
+        };
+
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(Occur::Must, text_query.box_clone())];
+        if !parsed.excluded.is_empty() {
+            let excluded: Vec<String> = parsed.excluded.iter().map(|e| quoted(e)).collect();
+            clauses.push((
+                Occur::MustNot,
+                parser.parse_query_lenient(&excluded.join(" ")).0,
+            ));
+        }
+        if !parsed.sites.is_empty() {
+            clauses.push((Occur::Must, any_of(f.domain, &with_www(&parsed.sites))));
+        }
+        for site in with_www(&parsed.excluded_sites) {
+            clauses.push((Occur::MustNot, Box::new(term_query(f.domain, &site))));
+        }
+        if let Some(status) = &parsed.status {
+            clauses.push((Occur::Must, Box::new(term_query(f.status, status))));
+        }
+        if parsed.after.is_some() || parsed.before.is_some() {
+            let bound = |date: Option<chrono::NaiveDate>, inclusive: bool| match date {
+                Some(date) => {
+                    let term = Term::from_field_i64(f.freshness_ts, query::day_start(date));
+                    if inclusive {
+                        Bound::Included(term)
+                    } else {
+                        Bound::Excluded(term)
+                    }
+                }
+                None => Bound::Unbounded,
+            };
+            clauses.push((
+                Occur::Must,
+                Box::new(RangeQuery::new(
+                    bound(parsed.after, true),
+                    bound(parsed.before, false),
+                )),
+            ));
+        }
+        let query = BooleanQuery::new(clauses);
+
+        let aggregations: Aggregations = serde_json::from_value(serde_json::json!({
+            "domain": { "terms": { "field": "domain", "size": MAX_FACET_VALUES } },
+            "status": { "terms": { "field": "status", "size": MAX_FACET_VALUES } },
+        }))
+        .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        let facet_collector = AggregationCollector::from_aggs(aggregations, Default::default());
+
+        if limit.saturating_add(offset) > MAX_RESULT_DEPTH {
+            return Err(notice_core::Error::Validation(format!(
+                "Cannot page past {} results",
+                MAX_RESULT_DEPTH
+            )));
+        }
+        // TopDocs needs a limit of at least one
+        let top = TopDocs::with_limit(limit.max(1)).and_offset(offset);
+        let (addresses, total, aggregated): (Vec<DocAddress>, usize, _) = match sort {
+            SearchSort::Relevance => {
+                let (hits, total, aggregated) = searcher
+                    .search(&query, &(top, Count, facet_collector))
+                    .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+                (
+                    hits.into_iter().map(|(_, address)| address).collect(),
+                    total,
+                    aggregated,
+                )
+            }
+            SearchSort::Recent => {
+                let top = top.order_by_fast_field::<i64>("freshness_ts", Order::Desc);
+                let (hits, total, aggregated) = searcher
+                    .search(&query, &(top, Count, facet_collector))
+                    .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+                (
+                    hits.into_iter().map(|(_, address)| address).collect(),
+                    total,
+                    aggregated,
+                )
+            }
+        };
+
+        let mut snippets = SnippetGenerator::create(&searcher, &*text_query, f.raw_content)
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        snippets.set_max_num_chars(SNIPPET_CHARS);
+
+        let mut results = Vec::new();
+        let mut freshness = Vec::new();
+        for address in addresses.into_iter().take(limit) {
+            let doc: TantivyDocument = searcher
+                .doc(address)
+                .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+            let score = query
+                .explain(&searcher, address)
+                .map(|explanation| normalize_score(explanation.value()))
+                .ok();
+
+            let text = |field: Field| {
+                doc.get_first(field)
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            };
+            let Some(id) = text(f.id).and_then(|id| id.parse::<Uuid>().ok()) else {
+                continue;
+            };
+            let raw_content = text(f.raw_content).unwrap_or_default();
+            let summary = text(f.summary);
+
+            let fragment = snippets.snippet(&raw_content).fragment().trim().to_string();
+            let snippet = if !fragment.is_empty() {
+                fragment
+            } else if let Some(summary) = &summary {
+                summary.clone()
+            } else if !raw_content.is_empty() {
+                notice_core::truncate_utf8(&raw_content, SNIPPET_CHARS).to_string()
+            } else {
+                "No preview available".to_string()
+            };
+
+            freshness.push(doc.get_first(f.freshness_ts).and_then(|v| v.as_i64()));
+            results.push(SearchResult {
+                id,
+                url: text(f.url).unwrap_or_default(),
+                title: text(f.title),
+                snippet,
+                score,
+            });
+        }
+
+        let aggregated = serde_json::to_value(aggregated)
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        let facets = SearchFacets {
+            domains: top_facets(Some(bucket_counts(&aggregated, "domain"))),
+            statuses: top_facets(Some(bucket_counts(&aggregated, "status"))),
+        };
+
+        Ok(BackendPage {
+            page: SearchPage {
+                results,
+                total,
+                facets,
+            },
+            freshness,
+        })
+    }
+
+    fn search_passages(
+        &self,
+        text: &str,
+        document_ids: &[Uuid],
+        limit: usize,
+    ) -> Result<Vec<PassageHit>, notice_core::Error> {
+        let f = self.passage_fields;
+        let searcher = self.passages.reader.searcher();
+
+        let mut parser =
+            QueryParser::for_index(&self.passages.index, vec![f.heading, f.content, f.title]);
+        parser.set_field_boost(f.heading, 2.0);
+
+        let words: Vec<String> = text.split_whitespace().map(quoted).collect();
+        let text_query: Box<dyn Query> = if words.is_empty() {
+            Box::new(AllQuery)
+        } else {
+            parser.parse_query_lenient(&words.join(" ")).0
+        };
+
+        let mut clauses = vec![(Occur::Must, text_query.box_clone())];
+        if !document_ids.is_empty() {
+            let ids: Vec<String> = document_ids.iter().map(Uuid::to_string).collect();
+            clauses.push((Occur::Must, any_of(f.document_id, &ids)));
+        }
+        let query = BooleanQuery::new(clauses);
+
+        let hits = searcher
+            .search(&query, &TopDocs::with_limit(limit.max(1)))
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        let mut snippets = SnippetGenerator::create(&searcher, &*text_query, f.content)
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        snippets.set_max_num_chars(PASSAGE_SNIPPET_CHARS);
+
+        let mut passages = Vec::new();
+        for (score, address) in hits.into_iter().take(limit) {
+            let doc: TantivyDocument = searcher
+                .doc(address)
+                .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+            let text = |field: Field| {
+                doc.get_first(field)
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            };
+            let Some(document_id) = text(f.document_id).and_then(|id| id.parse::<Uuid>().ok())
+            else {
+                continue;
+            };
+            let content = text(f.content).unwrap_or_default();
+            let fragment = snippets.snippet(&content).fragment().trim().to_string();
+
+            passages.push(PassageHit {
+                document_id,
+                chunk_index: doc
+                    .get_first(f.chunk_index)
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0) as usize,
+                heading: text(f.heading),
+                snippet: if fragment.is_empty() {
+                    content.clone()
+                } else {
+                    fragment
+                },
+                content,
+                score: Some(normalize_score(score)),
+            });
+        }
+        Ok(passages)
+    }
+}
+
+#[async_trait]
+impl SearchBackend for TantivyBackend {
+    fn name(&self) -> &'static str {
+        "tantivy"
+    }
+
+    async fn health(&self) -> Result<(), notice_core::Error> {
+        Ok(())
+    }
+
+    /// The schema is fixed when the index is opened; nothing to apply.
+    async fn configure(&self) -> Result<(), notice_core::Error> {
+        Ok(())
+    }
+
+    async fn index_documents(&self, docs: &[MeiliDocumentInput]) -> Result<(), notice_core::Error> {
+        if docs.is_empty() {
+            return Ok(());
+        }
+        let docs = docs.to_vec();
+        self.blocking(move |inner| inner.index_documents(&docs))
+            .await?;
+        tracing::debug!("Indexed document(s) in Tantivy");
+        Ok(())
+    }
+
+    async fn delete_document(&self, id: Uuid) -> Result<(), notice_core::Error> {
+        self.blocking(move |inner| inner.delete_document(id)).await
+    }
+
+    async fn replace_passages(
+        &self,
+        document_id: Uuid,
+        passages: &[MeiliPassageInput],
+    ) -> Result<(), notice_core::Error> {
+        let passages = passages.to_vec();
+        self.blocking(move |inner| inner.replace_passages(document_id, &passages))
+            .await
+    }
+
+    async fn document_count(&self) -> Result<usize, notice_core::Error> {
+        Ok(self.inner.documents.reader.searcher().num_docs() as usize)
+    }
+
+    async fn indexed_versions(&self) -> Result<Vec<(Uuid, Option<i64>)>, notice_core::Error> {
+        self.blocking(|inner| inner.indexed_versions()).await
+    }
+
+    async fn search(
+        &self,
+        parsed: &ParsedQuery,
+        limit: usize,
+        offset: usize,
+        sort: SearchSort,
+    ) -> Result<BackendPage, notice_core::Error> {
+        let parsed = parsed.clone();
+        self.blocking(move |inner| inner.search(&parsed, limit, offset, sort))
+            .await
+    }
+
+    async fn search_passages(
+        &self,
+        text: &str,
+        document_ids: &[Uuid],
+        limit: usize,
+    ) -> Result<Vec<PassageHit>, notice_core::Error> {
+        let text = text.to_string();
+        let document_ids = document_ids.to_vec();
+        self.blocking(move |inner| inner.search_passages(&text, &document_ids, limit))
+            .await
+    }
+}
+
+// ─── Helpers ───
+
+/// Quote a word or phrase for the query parser, so characters like `+`,
+/// `:` or `(` are searched for rather than parsed.
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace(['"', '\\'], " "))
+}
+
+fn term_query(field: Field, value: &str) -> TermQuery {
+    TermQuery::new(
+        Term::from_field_text(field, value),
+        IndexRecordOption::Basic,
+    )
+}
+
+/// Matches documents whose `field` is any of `values`.
+fn any_of(field: Field, values: &[String]) -> Box<dyn Query> {
+    Box::new(BooleanQuery::new(
+        values
+            .iter()
+            .map(|v| {
+                (
+                    Occur::Should,
+                    Box::new(term_query(field, v)) as Box<dyn Query>,
+                )
+            })
+            .collect(),
+    ))
+}
+
+fn normalize_score(score: f32) -> f64 {
+    (score / (score + SCORE_SATURATION)) as f64
+}
+
+/// `{"domain": {"buckets": [{"key": "docs.rs", "doc_count": 3}]}}` →
+/// counts by value.
+fn bucket_counts(aggregated: &serde_json::Value, name: &str) -> HashMap<String, usize> {
+    aggregated[name]["buckets"]
+        .as_array()
+        .map(|buckets| {
+            buckets
+                .iter()
+                .filter_map(|b| {
+                    Some((
+                        b["key"].as_str()?.to_string(),
+                        b["doc_count"].as_u64()? as usize,
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(
+        id: u128,
+        domain: &str,
+        title: &str,
+        content: &str,
+        freshness_ts: i64,
+    ) -> MeiliDocumentInput {
+        MeiliDocumentInput {
+            id: Uuid::from_u128(id),
+            url: format!("https://{}/{}", domain, id),
+            domain: domain.to_string(),
+            title: Some(title.to_string()),
+            raw_content: content.to_string(),
+            summary: None,
+            status: "pending".to_string(),
+            quality_score: 0.5,
+            crawled_at_ts: freshness_ts,
+            published_at_ts: None,
+            freshness_ts,
+            updated_at_ms: freshness_ts * 1000,
+        }
+    }
+
+    #[tokio::test]
+    async fn indexes_searches_and_filters() {
+        let backend = TantivyBackend::in_memory().unwrap();
+        backend
+            .index_documents(&[
+                doc(
+                    1,
+                    "docs.rs",
+                    "Tokio",
+                    "Tokio is an asynchronous runtime for Rust.",
+                    100,
+                ),
+                doc(
+                    2,
+                    "python.org",
+                    "asyncio",
+                    "asyncio is the async runtime of Python.",
+                    300,
+                ),
+                doc(
+                    3,
+                    "www.docs.rs",
+                    "Serde",
+                    "Serde serializes Rust data structures.",
+                    200,
+                ),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(backend.document_count().await.unwrap(), 3);
+
+        let page = backend
+            .search(&query::parse("runtime"), 10, 0, SearchSort::Relevance)
+            .await
+            .unwrap()
+            .page;
+        assert_eq!(page.total, 2);
+        assert!(
+            page.results
+                .iter()
+                .all(|r| r.score.is_some_and(|s| s > 0.0 && s < 1.0))
+        );
+        assert!(page.results[0].snippet.contains("runtime"));
+        assert_eq!(page.facets.domains.len(), 2);
+
+        let page = backend
+            .search(
+                &query::parse("rust site:docs.rs"),
+                10,
+                0,
+                SearchSort::Relevance,
+            )
+            .await
+            .unwrap()
+            .page;
+        assert_eq!(page.total, 2);
+
+        let page = backend
+            .search(
+                &query::parse("runtime -python"),
+                10,
+                0,
+                SearchSort::Relevance,
+            )
+            .await
+            .unwrap()
+            .page;
+        assert_eq!(page.results.len(), 1);
+        assert_eq!(page.results[0].id, Uuid::from_u128(1));
+
+        let recent = backend
+            .search(&query::parse("site:docs.rs"), 10, 0, SearchSort::Recent)
+            .await
+            .unwrap();
+        let ids: Vec<Uuid> = recent.page.results.iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![Uuid::from_u128(3), Uuid::from_u128(1)]);
+        assert_eq!(recent.freshness, vec![Some(200), Some(100)]);
+
+        backend.delete_document(Uuid::from_u128(2)).await.unwrap();
+        assert_eq!(backend.document_count().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn replaces_and_searches_passages() {
+        let backend = TantivyBackend::in_memory().unwrap();
+        let passage = |doc: u128, index: usize, content: &str| MeiliPassageInput {
+            id: format!("{}-{}", Uuid::from_u128(doc), index),
+            document_id: Uuid::from_u128(doc),
+            url: "https://docs.rs".to_string(),
+            domain: "docs.rs".to_string(),
+            title: None,
+            heading: Some("Install".to_string()),
+            content: content.to_string(),
+            chunk_index: index,
+        };
+
+        backend
+            .replace_passages(
+                Uuid::from_u128(1),
+                &[
+                    passage(1, 0, "cargo add tokio"),
+                    passage(1, 1, "tokio spawn tasks"),
+                ],
+            )
+            .await
+            .unwrap();
+        backend
+            .replace_passages(Uuid::from_u128(2), &[passage(2, 0, "tokio console")])
+            .await
+            .unwrap();
+
+        let hits = backend
+            .search_passages("tokio", &[Uuid::from_u128(1)], 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|h| h.document_id == Uuid::from_u128(1)));
+
+        backend
+            .replace_passages(Uuid::from_u128(1), &[passage(1, 0, "cargo add tokio")])
+            .await
+            .unwrap();
+        let hits = backend.search_passages("tokio", &[], 10).await.unwrap();
+        assert_eq!(hits.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_pages_past_max_depth() {
+        let backend = TantivyBackend::in_memory().unwrap();
+        let result = backend
+            .search(
+                &query::parse("rust"),
+                10,
+                1_000_000_000,
+                SearchSort::Relevance,
+            )
+            .await;
+        assert!(matches!(result, Err(notice_core::Error::Validation(_))));
+    }
+}
@@ -0,0 +1,103 @@
+use notice_core::types::SearchResult;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// How `/api/search` finds documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    /// Meilisearch only
+    Keyword,
+    /// Vector similarity only
+    Semantic,
+    /// Both, with blended scores
+    Hybrid,
+}
+
+impl std::str::FromStr for SearchMode {
+    type Err = notice_core::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "keyword" => Ok(SearchMode::Keyword),
+            "semantic" => Ok(SearchMode::Semantic),
+            "hybrid" => Ok(SearchMode::Hybrid),
+            other => Err(notice_core::Error::Validation(format!(
+                "Unknown mode '{}': use 'hybrid', 'semantic' or 'keyword'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Merge keyword and semantic results into one ranking. Each document's
+/// score is `(1 - semantic_ratio) * keyword + semantic_ratio * similarity`,
+/// where a list the document is missing from contributes 0.
+pub fn blend(
+    keyword: Vec<SearchResult>,
+    semantic: Vec<SearchResult>,
+    semantic_ratio: f64,
+) -> Vec<SearchResult> {
+    let ratio = semantic_ratio.clamp(0.0, 1.0);
+    let mut merged: HashMap<Uuid, (f64, SearchResult)> = HashMap::new();
+
+    for result in keyword {
+        let score = (1.0 - ratio) * result.score.unwrap_or(0.0);
+        merged.insert(result.id, (score, result));
+    }
+    for result in semantic {
+        let score = ratio * result.score.unwrap_or(0.0).clamp(0.0, 1.0);
+        merged
+            .entry(result.id)
+            .and_modify(|(total, _)| *total += score)
+            .or_insert((score, result));
+    }
+
+    let mut blended: Vec<SearchResult> = merged
+        .into_values()
+        .map(|(score, mut result)| {
+            result.score = Some(score);
+            result
+        })
+        .collect();
+    blended.sort_by(|a, b| {
+        b.score
+            .unwrap_or(0.0)
+            .total_cmp(&a.score.unwrap_or(0.0))
+            .then_with(|| a.url.cmp(&b.url))
+    });
+    blended
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: u128, score: f64) -> SearchResult {
+        SearchResult {
+            id: Uuid::from_u128(id),
+            url: format!("https://example.com/{}", id),
+            title: None,
+            snippet: String::new(),
+            score: Some(score),
+        }
+    }
+
+    #[test]
+    fn blends_both_lists() {
+        let keyword = vec![result(1, 0.9), result(2, 0.5)];
+        let semantic = vec![result(2, 0.9), result(3, 0.8)];
+
+        let blended = blend(keyword.clone(), semantic.clone(), 0.5);
+        let ids: Vec<u128> = blended.iter().map(|r| r.id.as_u128()).collect();
+        assert_eq!(ids, vec![2, 1, 3]);
+        assert!((blended[0].score.unwrap() - 0.7).abs() < 1e-9);
+
+        let keyword_only = blend(keyword, semantic, 0.0);
+        assert_eq!(keyword_only[0].id, Uuid::from_u128(1));
+        assert_eq!(
+            "Semantic".parse::<SearchMode>().unwrap(),
+            SearchMode::Semantic
+        );
+        assert!("fuzzy".parse::<SearchMode>().is_err());
+    }
+}
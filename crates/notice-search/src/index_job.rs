@@ -0,0 +1,400 @@
+use chrono::{DateTime, Utc};
+use notice_db::documents::DocumentRow;
+use notice_db::index_sync::Watermark;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::{MeiliDocumentInput, SearchClient};
+
+/// Documents pushed per batch by a resync.
+const RESYNC_BATCH: i64 = 100;
+/// Document versions read per query by a check.
+const CHECK_BATCH: i64 = 1000;
+/// IDs listed per kind of problem in a job's status.
+const SAMPLE_IDS: usize = 20;
+/// How far before its watermark an incremental resync starts. `updated_at`
+/// is set at transaction start, so a document can commit with an
+/// `updated_at` older than documents pushed before it became visible.
+const RESYNC_OVERLAP: chrono::TimeDelta = chrono::TimeDelta::minutes(5);
+
+/// What an index job does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IndexJobKind {
+    /// Push documents changed since the last resync, or all with `full`
+    Resync { full: bool },
+    /// Compare PostgreSQL with the index; with `repair`, queue fixes in
+    /// the outbox
+    Check { repair: bool },
+    /// Build new indexes from PostgreSQL, swap them in, then check
+    Reindex,
+}
+
+/// Handle to a running or finished index job.
+#[derive(Clone)]
+pub struct IndexJob {
+    kind: IndexJobKind,
+    started_at: DateTime<Utc>,
+    progress: Arc<JobProgress>,
+}
+
+#[derive(Default)]
+struct JobProgress {
+    running: AtomicBool,
+    total: AtomicU64,
+    processed: AtomicU64,
+    failed: AtomicU64,
+    repaired: AtomicU64,
+    report: Mutex<Option<ConsistencyReport>>,
+    error: Mutex<Option<String>>,
+    finished_at: Mutex<Option<DateTime<Utc>>>,
+}
+
+/// Documents that differ between PostgreSQL and the index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// In PostgreSQL, not in the index
+    pub missing: Vec<Uuid>,
+    /// Indexed from an older version of the row
+    pub stale: Vec<Uuid>,
+    /// In the index, no longer in PostgreSQL
+    pub orphaned: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsistencySummary {
+    pub missing: usize,
+    pub stale: usize,
+    pub orphaned: usize,
+    pub sample_missing: Vec<Uuid>,
+    pub sample_stale: Vec<Uuid>,
+    pub sample_orphaned: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexJobSnapshot {
+    #[serde(flatten)]
+    pub kind: IndexJobKind,
+    pub running: bool,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Documents to go through
+    pub total: u64,
+    pub processed: u64,
+    /// Documents whose passages failed to push (queued in the outbox)
+    pub failed: u64,
+    /// Outbox entries queued to fix a check's findings
+    pub repaired: u64,
+    /// Set once a check has compared
+    pub consistency: Option<ConsistencySummary>,
+    pub error: Option<String>,
+}
+
+impl IndexJob {
+    /// Run a job in the background.
+    pub fn start(db: PgPool, search: SearchClient, kind: IndexJobKind) -> Self {
+        let job = Self {
+            kind,
+            started_at: Utc::now(),
+            progress: Arc::new(JobProgress {
+                running: AtomicBool::new(true),
+                ..Default::default()
+            }),
+        };
+
+        let progress = job.progress.clone();
+        tokio::spawn(async move {
+            tracing::info!(job = ?kind, "Index job started");
+            let result = match kind {
+                IndexJobKind::Resync { full } => resync(&db, &search, full, &progress).await,
+                IndexJobKind::Check { repair } => check(&db, &search, repair, &progress).await,
+                IndexJobKind::Reindex => reindex(&db, &search, &progress).await,
+            };
+            match result {
+                Ok(()) => tracing::info!(job = ?kind, "Index job finished"),
+                Err(e) => {
+                    tracing::error!(job = ?kind, error = %e, "Index job failed");
+                    if let Ok(mut error) = progress.error.lock() {
+                        *error = Some(e.to_string());
+                    }
+                }
+            }
+            if let Ok(mut finished_at) = progress.finished_at.lock() {
+                *finished_at = Some(Utc::now());
+            }
+            progress.running.store(false, Ordering::Relaxed);
+        });
+
+        job
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.progress.running.load(Ordering::Relaxed)
+    }
+
+    pub fn snapshot(&self) -> IndexJobSnapshot {
+        let p = &self.progress;
+        IndexJobSnapshot {
+            kind: self.kind,
+            running: p.running.load(Ordering::Relaxed),
+            started_at: self.started_at,
+            finished_at: p.finished_at.lock().ok().and_then(|t| *t),
+            total: p.total.load(Ordering::Relaxed),
+            processed: p.processed.load(Ordering::Relaxed),
+            failed: p.failed.load(Ordering::Relaxed),
+            repaired: p.repaired.load(Ordering::Relaxed),
+            consistency: p
+                .report
+                .lock()
+                .ok()
+                .and_then(|r| r.as_ref().map(ConsistencyReport::summary)),
+            error: p.error.lock().ok().and_then(|e| e.clone()),
+        }
+    }
+}
+
+/// Move a watermark back by RESYNC_OVERLAP, to before every document
+/// updated in that window.
+fn rewind(mark: Watermark) -> Watermark {
+    Watermark {
+        updated_at_mark: mark.updated_at_mark - RESYNC_OVERLAP,
+        id_mark: Uuid::nil(),
+    }
+}
+
+impl ConsistencyReport {
+    fn summary(&self) -> ConsistencySummary {
+        let sample = |ids: &[Uuid]| ids.iter().take(SAMPLE_IDS).copied().collect();
+        ConsistencySummary {
+            missing: self.missing.len(),
+            stale: self.stale.len(),
+            orphaned: self.orphaned.len(),
+            sample_missing: sample(&self.missing),
+            sample_stale: sample(&self.stale),
+            sample_orphaned: sample(&self.orphaned),
+        }
+    }
+}
+
+/// Push documents in `(updated_at, id)` order from the backend's
+/// watermark (less RESYNC_OVERLAP), moving it after each batch so an
+/// interrupted resync picks up where it stopped.
+async fn resync(
+    db: &PgPool,
+    search: &SearchClient,
+    full: bool,
+    progress: &JobProgress,
+) -> Result<(), notice_core::Error> {
+    let backend = search.backend_name();
+    let mut mark = if full {
+        None
+    } else {
+        notice_db::index_sync::watermark(db, backend)
+            .await?
+            .map(rewind)
+    };
+
+    let total = notice_db::documents::count_changed_since(db, mark).await?;
+    progress.total.store(total as u64, Ordering::Relaxed);
+
+    loop {
+        let docs = notice_db::documents::list_changed_since(db, mark, RESYNC_BATCH).await?;
+        let Some(last) = docs.last() else {
+            break;
+        };
+        let next = Watermark {
+            updated_at_mark: last.updated_at,
+            id_mark: last.id,
+        };
+
+        let failed = push_batch(db, search, &docs, progress).await?;
+        notice_db::index_outbox::enqueue_many(db, &failed, "upsert").await?;
+
+        notice_db::index_sync::set_watermark(db, backend, next).await?;
+        mark = Some(next);
+    }
+
+    Ok(())
+}
+
+/// Fill fresh indexes from PostgreSQL while searches keep using the live
+/// ones, swap them in, then check for changes the outbox pushed to the old
+/// indexes during the build and push the vocabulary again.
+async fn reindex(
+    db: &PgPool,
+    search: &SearchClient,
+    progress: &JobProgress,
+) -> Result<(), notice_core::Error> {
+    let staging = search.begin_reindex().await?;
+    crate::vocabulary::push(db, &staging).await?;
+
+    let total = notice_db::documents::count_changed_since(db, None).await?;
+    progress.total.store(total as u64, Ordering::Relaxed);
+
+    let mut mark = None;
+    let mut failed = vec![];
+    loop {
+        let docs = notice_db::documents::list_changed_since(db, mark, RESYNC_BATCH).await?;
+        let Some(last) = docs.last() else {
+            break;
+        };
+        mark = Some(Watermark {
+            updated_at_mark: last.updated_at,
+            id_mark: last.id,
+        });
+        failed.extend(push_batch(db, &staging, &docs, progress).await?);
+    }
+
+    search.finish_reindex().await?;
+    // Vocabulary edits made during the build only reached the old indexes
+    crate::vocabulary::push(db, search).await?;
+    // Queued only now, so the retries reach the new indexes
+    notice_db::index_outbox::enqueue_many(db, &failed, "upsert").await?;
+
+    progress.processed.store(0, Ordering::Relaxed);
+    check(db, search, true, progress).await
+}
+
+/// Push documents and their passages. Returns the documents whose
+/// passages failed, for the caller to queue in the outbox.
+async fn push_batch(
+    db: &PgPool,
+    search: &SearchClient,
+    docs: &[DocumentRow],
+    progress: &JobProgress,
+) -> Result<Vec<Uuid>, notice_core::Error> {
+    let inputs: Vec<MeiliDocumentInput> = docs.iter().map(MeiliDocumentInput::from).collect();
+    search.add_documents(&inputs).await?;
+
+    let mut failed = vec![];
+    for doc in docs {
+        if let Err(e) = crate::outbox::sync_passages(db, search, doc).await {
+            tracing::warn!(doc_id = %doc.id, error = %e, "Could not push passages, queued for retry");
+            failed.push(doc.id);
+            progress.failed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    progress
+        .processed
+        .fetch_add(docs.len() as u64, Ordering::Relaxed);
+    Ok(failed)
+}
+
+/// Compare every document's `updated_at` with what the index holds.
+async fn check(
+    db: &PgPool,
+    search: &SearchClient,
+    repair: bool,
+    progress: &JobProgress,
+) -> Result<(), notice_core::Error> {
+    let total = notice_db::documents::count(db).await?;
+    progress.total.store(total as u64, Ordering::Relaxed);
+
+    let mut documents: HashMap<Uuid, i64> = HashMap::new();
+    let mut after = None;
+    loop {
+        let rows = notice_db::documents::list_versions(db, after, CHECK_BATCH).await?;
+        let Some(&(last, _)) = rows.last() else {
+            break;
+        };
+        after = Some(last);
+        documents.extend(rows.into_iter().map(|(id, t)| (id, t.timestamp_millis())));
+        progress
+            .processed
+            .store(documents.len() as u64, Ordering::Relaxed);
+    }
+
+    let indexed = search.indexed_versions().await?;
+    let report = compare(&documents, &indexed);
+    tracing::info!(
+        missing = report.missing.len(),
+        stale = report.stale.len(),
+        orphaned = report.orphaned.len(),
+        "Index consistency check done"
+    );
+
+    if repair {
+        let mut outdated = report.missing.clone();
+        outdated.extend_from_slice(&report.stale);
+        let queued = notice_db::index_outbox::enqueue_many(db, &outdated, "upsert").await?
+            + notice_db::index_outbox::enqueue_many(db, &report.orphaned, "delete").await?;
+        progress.repaired.store(queued, Ordering::Relaxed);
+    }
+
+    if let Ok(mut slot) = progress.report.lock() {
+        *slot = Some(report);
+    }
+    Ok(())
+}
+
+/// Diff PostgreSQL's `id → updated_at_ms` with the index's versions.
+/// Each list is sorted.
+fn compare(documents: &HashMap<Uuid, i64>, indexed: &[(Uuid, Option<i64>)]) -> ConsistencyReport {
+    let mut report = ConsistencyReport::default();
+    let mut seen: HashSet<Uuid> = HashSet::with_capacity(indexed.len());
+
+    for &(id, version) in indexed {
+        seen.insert(id);
+        match documents.get(&id) {
+            None => report.orphaned.push(id),
+            Some(&updated) if version != Some(updated) => report.stale.push(id),
+            Some(_) => {}
+        }
+    }
+    report.missing = documents
+        .keys()
+        .filter(|id| !seen.contains(id))
+        .copied()
+        .collect();
+
+    report.missing.sort();
+    report.stale.sort();
+    report.orphaned.sort();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_missing_stale_and_orphaned_documents() {
+        let id = Uuid::from_u128;
+        let documents = HashMap::from([(id(1), 100), (id(2), 200), (id(3), 300)]);
+        let indexed = [
+            (id(1), Some(100)),
+            (id(2), Some(150)),
+            (id(4), Some(400)),
+            (id(3), None),
+        ];
+
+        assert_eq!(
+            compare(&documents, &indexed),
+            ConsistencyReport {
+                missing: vec![],
+                stale: vec![id(2), id(3)],
+                orphaned: vec![id(4)],
+            }
+        );
+        assert_eq!(compare(&documents, &[]).missing, vec![id(1), id(2), id(3)]);
+    }
+
+    #[test]
+    fn resync_starts_before_the_watermark() {
+        let mark = Watermark {
+            updated_at_mark: "2025-01-04T12:00:00Z".parse().unwrap(),
+            id_mark: Uuid::from_u128(42),
+        };
+        let start = rewind(mark);
+        assert_eq!(
+            start.updated_at_mark,
+            "2025-01-04T11:55:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(start.id_mark, Uuid::nil());
+    }
+}
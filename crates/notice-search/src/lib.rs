@@ -1,22 +1,69 @@
-use meilisearch_sdk::client::Client;
-use meilisearch_sdk::search::Selectors;
-use notice_core::types::SearchResult;
-use serde::{Deserialize, Serialize};
-use std::time::Duration;
+pub mod backend;
+pub mod chunk;
+pub mod embedded;
+pub mod hybrid;
+pub mod index_job;
+pub mod meili;
+pub mod outbox;
+pub mod query;
+pub mod recency;
+pub mod spelling;
+pub mod suggest;
+pub mod vocabulary;
+
+pub use backend::SearchBackend;
+
+use chunk::PassageHit;
+use notice_core::types::{FacetCount, ParsedQuery, SearchFacets, SearchResult};
+use notice_db::chunks::ChunkRow;
+use notice_db::documents::DocumentRow;
+use recency::RecencyDecay;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 use uuid::Uuid;
 
-const DOCUMENTS_INDEX: &str = "documents";
+/// Most facet values returned per attribute.
+pub(crate) const MAX_FACET_VALUES: usize = 10;
 
-/// Wrapper around the Meilisearch client.
+/// Search over the configured backend: Meilisearch, or the embedded
+/// Tantivy index for single-machine setups and tests.
 #[derive(Clone)]
 pub struct SearchClient {
-    client: Client,
+    backend: Arc<dyn SearchBackend>,
+    recency: Option<RecencyDecay>,
 }
 
-// ─── Meilisearch Document Types ───
+/// Result ordering for [`SearchClient::search_parsed`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchSort {
+    /// Backend relevance, with recency decay if configured
+    #[default]
+    Relevance,
+    /// Newest first by published date (crawl time when unknown)
+    Recent,
+}
+
+impl std::str::FromStr for SearchSort {
+    type Err = notice_core::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "relevance" => Ok(SearchSort::Relevance),
+            "recent" => Ok(SearchSort::Recent),
+            other => Err(notice_core::Error::Validation(format!(
+                "Unknown sort '{}': use 'relevance' or 'recent'",
+                other
+            ))),
+        }
+    }
+}
 
-/// What we SEND to Meilisearch (all indexed fields).
-/// Used for direct sync and must match what MeiliBridge sends.
+// ─── Document Types ───
+
+/// What we SEND to the search backend (all indexed fields).
+/// Must match what MeiliBridge sends when it is used.
 #[derive(Debug, Clone, Serialize)]
 pub struct MeiliDocumentInput {
     pub id: Uuid,
@@ -27,348 +74,254 @@ pub struct MeiliDocumentInput {
     pub summary: Option<String>,
     pub status: String,
     pub quality_score: f64,
+    /// When we first crawled the page (unix seconds)
+    pub crawled_at_ts: i64,
+    /// Publication date from the page's metadata (unix seconds)
+    pub published_at_ts: Option<i64>,
+    /// Published date if known, else crawl time: what `sort=recent`,
+    /// `after:`/`before:` and recency decay use
+    pub freshness_ts: i64,
+    /// `documents.updated_at` (unix milliseconds), so the consistency
+    /// check can spot stale copies
+    pub updated_at_ms: i64,
 }
 
-/// What we READ from Meilisearch search results.
-/// Must match displayed_attributes — does NOT include raw_content.
-#[derive(Debug, Clone, Deserialize)]
-pub struct MeiliDocumentOutput {
-    pub id: Uuid,
+impl From<&DocumentRow> for MeiliDocumentInput {
+    fn from(doc: &DocumentRow) -> Self {
+        Self {
+            id: doc.id,
+            url: doc.url.clone(),
+            domain: doc.domain.clone(),
+            title: doc.title.clone(),
+            raw_content: doc.raw_content.clone(),
+            summary: doc.summary.clone(),
+            status: doc.status.clone(),
+            quality_score: doc.quality_score,
+            crawled_at_ts: doc.created_at.timestamp(),
+            published_at_ts: doc.published_at.map(|t| t.timestamp()),
+            freshness_ts: doc.published_at.unwrap_or(doc.created_at).timestamp(),
+            updated_at_ms: doc.updated_at.timestamp_millis(),
+        }
+    }
+}
+
+/// A passage of a document, indexed separately so searches can point at
+/// the part of a long page that matches.
+#[derive(Debug, Clone, Serialize)]
+pub struct MeiliPassageInput {
+    /// "{document_id}-{chunk_index}", stable across re-chunking
+    pub id: String,
+    pub document_id: Uuid,
     pub url: String,
     pub domain: String,
     pub title: Option<String>,
-    pub summary: Option<String>,
-    pub status: String,
-    pub quality_score: f64,
+    pub heading: Option<String>,
+    pub content: String,
+    pub chunk_index: usize,
 }
 
-impl SearchClient {
-    /// Create a new Meilisearch client.
-    pub fn new(url: &str, api_key: &str) -> Result<Self, notice_core::Error> {
-        let client = Client::new(url, Some(api_key))
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
-
-        tracing::info!("Meilisearch client created for {}", url);
-        Ok(Self { client })
-    }
-
-    /// Health check.
-    pub async fn health(&self) -> Result<(), notice_core::Error> {
-        self.client
-            .health()
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
-        Ok(())
+impl MeiliPassageInput {
+    pub fn new(doc: &DocumentRow, chunk: &ChunkRow) -> Self {
+        Self {
+            id: format!("{}-{}", doc.id, chunk.chunk_index),
+            document_id: doc.id,
+            url: doc.url.clone(),
+            domain: doc.domain.clone(),
+            title: doc.title.clone(),
+            heading: chunk.heading.clone(),
+            content: chunk.content.clone(),
+            chunk_index: chunk.chunk_index as usize,
+        }
     }
+}
 
-    /// Configure the documents index with optimal settings.
-    /// Idempotent — safe to call on every startup.
-    pub async fn configure_index(&self) -> Result<(), notice_core::Error> {
-        // Create index (ignore if already exists)
-        let task = self
-            .client
-            .create_index(DOCUMENTS_INDEX, Some("id"))
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
-        let _ = task.wait_for_completion(&self.client, None, None).await;
+/// One page of search results with facet counts.
+#[derive(Debug, Clone)]
+pub struct SearchPage {
+    pub results: Vec<SearchResult>,
+    pub total: usize,
+    pub facets: SearchFacets,
+}
 
-        let index = self.client.index(DOCUMENTS_INDEX);
+impl SearchClient {
+    /// Wrap a backend.
+    pub fn new(backend: impl SearchBackend + 'static) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            recency: None,
+        }
+    }
 
-        // Searchable: what fields are searched (order = priority)
-        index
-            .set_searchable_attributes(["title", "summary", "raw_content", "url", "domain"])
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+    /// Meilisearch server at `url`.
+    pub fn meilisearch(url: &str, api_key: &str) -> Result<Self, notice_core::Error> {
+        Ok(Self::new(meili::MeiliBackend::new(url, api_key)?))
+    }
 
-        // Displayed: what fields are returned in results
-        // NOTE: we include raw_content in displayed_attributes ONLY to allow
-        // Meilisearch to return cropped snippets in formatted_result.
-        index
-            .set_displayed_attributes(["id", "url", "domain", "title", "summary", "status", "quality_score", "raw_content"])
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+    /// Embedded Tantivy index stored in `dir` (created if missing).
+    pub fn tantivy(dir: impl AsRef<Path>) -> Result<Self, notice_core::Error> {
+        Ok(Self::new(embedded::TantivyBackend::open(dir)?))
+    }
 
-        // Filterable: for faceted search / filtering
-        index
-            .set_filterable_attributes(["domain", "status"])
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
-
-        // Ranking rules
-        index
-            .set_ranking_rules([
-                "words",
-                "typo",
-                "proximity",
-                "attribute",
-                "sort",
-                "quality_score:desc",
-                "exactness",
-            ])
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+    /// Down-weight older documents in relevance-sorted results.
+    pub fn with_recency_decay(mut self, decay: RecencyDecay) -> Self {
+        self.recency = Some(decay);
+        self
+    }
 
-        // IMPORTANT: quality_score MUST be sortable to work as a ranking rule
-        index
-            .set_sortable_attributes(["quality_score"])
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
-
-        // Stop words: common words to ignore for relevance scoring
-        index
-            .set_stop_words([
-                "a", "an", "the", "is", "of", "and", "or", "in", "on", "at", "to", "for", 
-                "with", "by", "about", "what", "how", "why", "where", "when", "which", 
-                "who", "whom", "this", "that", "these", "those", "it", "they", "them", 
-                "he", "she", "his", "her", "i", "you", "we", "me", "us", "my", "your", "our"
-            ])
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+    /// "meilisearch" or "tantivy".
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
 
-        tracing::info!("Meilisearch '{}' index configured", DOCUMENTS_INDEX);
-        Ok(())
+    /// Health check.
+    pub async fn health(&self) -> Result<(), notice_core::Error> {
+        self.backend.health().await
     }
 
-    /// Configure synonyms for common abbreviations and aliases.
-    /// Meilisearch handles these transparently during search.
-    pub async fn configure_synonyms(&self) -> Result<(), notice_core::Error> {
-        let index = self.client.index(DOCUMENTS_INDEX);
+    /// Create and configure the indexes.
+    /// Idempotent — safe to call on every startup.
+    pub async fn configure(&self) -> Result<(), notice_core::Error> {
+        self.backend.configure().await
+    }
 
-        let mut synonyms = std::collections::HashMap::new();
+    /// Replace the synonyms and stop words searches use.
+    pub async fn set_vocabulary(
+        &self,
+        vocabulary: &vocabulary::Vocabulary,
+    ) -> Result<(), notice_core::Error> {
+        self.backend.set_vocabulary(vocabulary).await
+    }
 
-        // Helper to reduce boilerplate
-        let mut add = |key: &str, vals: &[&str]| {
-            synonyms.insert(
-                key.to_string(),
-                vals.iter().map(|v| v.to_string()).collect::<Vec<String>>(),
-            );
-        };
+    /// Whether the live indexes need rebuilding with current settings.
+    pub async fn needs_reindex(&self) -> Result<bool, notice_core::Error> {
+        self.backend.needs_reindex().await
+    }
 
-        // Programming languages
-        add("js", &["javascript"]);
-        add("javascript", &["js"]);
-        add("ts", &["typescript"]);
-        add("typescript", &["ts"]);
-        add("py", &["python"]);
-        add("python", &["py"]);
-        add("rb", &["ruby"]);
-        add("ruby", &["rb"]);
-        add("cpp", &["c++"]);
-        add("c++", &["cpp"]);
-        add("golang", &["go"]);
-        add("go", &["golang"]);
-
-        // Databases
-        add("pg", &["postgresql", "postgres"]);
-        add("postgresql", &["postgres", "pg"]);
-        add("postgres", &["postgresql", "pg"]);
-        add("mongo", &["mongodb"]);
-        add("mongodb", &["mongo"]);
-
-        // Technologies
-        add("k8s", &["kubernetes"]);
-        add("kubernetes", &["k8s"]);
-        add("wasm", &["webassembly"]);
-        add("webassembly", &["wasm"]);
-        add("ml", &["machine learning"]);
-        add("ai", &["artificial intelligence"]);
-        add("os", &["operating system"]);
-        add("db", &["database"]);
-        add("api", &["application programming interface"]);
-        add("cli", &["command line interface"]);
-        add("gui", &["graphical user interface"]);
-        add("tui", &["terminal user interface"]);
-        add("ui", &["user interface"]);
-        add("ux", &["user experience"]);
-        add("oop", &["object oriented programming"]);
-        add("fp", &["functional programming"]);
-
-        // Common abbreviations
-        add("docs", &["documentation"]);
-        add("doc", &["documentation"]);
-        add("config", &["configuration"]);
-        add("auth", &["authentication"]);
-        add("env", &["environment"]);
-        add("repo", &["repository"]);
-        add("lib", &["library"]);
-        add("pkg", &["package"]);
-        add("deps", &["dependencies"]);
-        add("dev", &["development"]);
-        add("prod", &["production"]);
-        add("impl", &["implementation"]);
-        add("fn", &["function"]);
-        add("func", &["function"]);
-        add("var", &["variable"]);
-        add("arg", &["argument"]);
-        add("param", &["parameter"]);
-        add("err", &["error"]);
-        add("msg", &["message"]);
-        add("async", &["asynchronous"]);
-        add("sync", &["synchronous"]);
-
-        let count = synonyms.len();
-
-        index
-            .set_synonyms(&synonyms)
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+    /// Start building new indexes; writes through the returned client go
+    /// to them, searches here still hit the live ones.
+    pub async fn begin_reindex(&self) -> Result<SearchClient, notice_core::Error> {
+        Ok(Self {
+            backend: Arc::from(self.backend.begin_reindex().await?),
+            recency: None,
+        })
+    }
 
-        tracing::info!("Meilisearch synonyms configured ({} entries)", count);
-        Ok(())
+    /// Swap the indexes built since [`begin_reindex`](Self::begin_reindex)
+    /// in for the live ones.
+    pub async fn finish_reindex(&self) -> Result<(), notice_core::Error> {
+        self.backend.finish_reindex().await
     }
 
     // ─── Write Operations (Direct Sync) ───
 
-    /// Add or update documents in Meilisearch.
-    /// Waits for the indexing task to complete (up to 30s).
+    /// Add or update documents.
     pub async fn add_documents(
         &self,
         docs: &[MeiliDocumentInput],
     ) -> Result<(), notice_core::Error> {
-        if docs.is_empty() {
-            return Ok(());
-        }
-
-        let index = self.client.index(DOCUMENTS_INDEX);
-
-        let task = index
-            .add_documents(docs, Some("id"))
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
-
-        let task = task
-            .wait_for_completion(
-                &self.client,
-                Some(Duration::from_millis(200)),
-                Some(Duration::from_secs(30)),
-            )
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
-
-        // Check if the task succeeded
-        if task.is_failure() {
-            let error_msg = format!("Meilisearch indexing task failed: {:?}", task);
-            tracing::error!("{}", error_msg);
-            return Err(notice_core::Error::Search(error_msg));
-        }
-
-        tracing::debug!("Indexed {} document(s) in Meilisearch", docs.len());
-        Ok(())
+        self.backend.index_documents(docs).await
     }
 
-    /// Add a single document to Meilisearch.
+    /// Add a single document.
     pub async fn add_document(&self, doc: MeiliDocumentInput) -> Result<(), notice_core::Error> {
         self.add_documents(&[doc]).await
     }
 
-    /// Delete a document from Meilisearch by ID.
+    /// Delete a document and its passages by ID.
     pub async fn delete_document(&self, id: Uuid) -> Result<(), notice_core::Error> {
-        let index = self.client.index(DOCUMENTS_INDEX);
+        self.backend.delete_document(id).await
+    }
 
-        let task = index
-            .delete_document(&id.to_string())
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
-
-        task.wait_for_completion(
-            &self.client,
-            Some(Duration::from_millis(200)),
-            Some(Duration::from_secs(10)),
-        )
-        .await
-        .map_err(|e| notice_core::Error::Search(e.to_string()))?;
-
-        tracing::debug!("Deleted document {} from Meilisearch", id);
-        Ok(())
+    /// Replace a document's passages: upsert the new ones and drop any
+    /// left over from a longer earlier version.
+    pub async fn replace_passages(
+        &self,
+        document_id: Uuid,
+        passages: &[MeiliPassageInput],
+    ) -> Result<(), notice_core::Error> {
+        self.backend.replace_passages(document_id, passages).await
     }
 
     /// Get the number of documents in the index.
     pub async fn document_count(&self) -> Result<usize, notice_core::Error> {
-        let index = self.client.index(DOCUMENTS_INDEX);
-        let stats = index
-            .get_stats()
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
-        Ok(stats.number_of_documents)
+        self.backend.document_count().await
+    }
+
+    /// Every indexed document's ID and `updated_at_ms`.
+    pub async fn indexed_versions(&self) -> Result<Vec<(Uuid, Option<i64>)>, notice_core::Error> {
+        self.backend.indexed_versions().await
     }
 
     // ─── Search ───
 
     /// Search documents. Returns results with snippets.
+    /// The query may use operators, see [`query::parse`].
     pub async fn search(
         &self,
         query: &str,
         limit: usize,
         offset: usize,
     ) -> Result<(Vec<SearchResult>, usize), notice_core::Error> {
-        let index = self.client.index(DOCUMENTS_INDEX);
-
-        let results = index
-            .search()
-            .with_query(query)
-            .with_limit(limit)
-            .with_offset(offset)
-            .with_show_ranking_score(true)
-            .with_attributes_to_retrieve(Selectors::All)
-            // Request crops for both summary and raw_content to have backup context
-            .with_attributes_to_crop(Selectors::Some(&[
-                ("summary", Some(250)),
-                ("raw_content", Some(500)),
-            ]))
-            .with_attributes_to_highlight(Selectors::Some(&["title", "summary"]))
-            .execute::<MeiliDocumentOutput>()
-            .await
-            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
-
-        let total = results.estimated_total_hits.unwrap_or(0);
-        
-        tracing::debug!(
-            query = query,
-            hits = results.hits.len(),
-            total = total,
-            "Meilisearch search results received"
+        let page = self
+            .search_parsed(&query::parse(query), limit, offset, SearchSort::Relevance)
+            .await?;
+        Ok((page.results, page.total))
+    }
+
+    /// Search with an already-parsed query: operators become filters.
+    /// Also returns domain and status facet counts for the matches.
+    pub async fn search_parsed(
+        &self,
+        parsed: &ParsedQuery,
+        limit: usize,
+        offset: usize,
+        sort: SearchSort,
+    ) -> Result<SearchPage, notice_core::Error> {
+        let decay = match self.recency {
+            Some(decay) if sort == SearchSort::Relevance && offset < recency::CANDIDATE_WINDOW => {
+                decay
+            }
+            _ => return Ok(self.backend.search(parsed, limit, offset, sort).await?.page),
+        };
+
+        // Decay can lift a hit from below this page, so rank the whole
+        // candidate window and slice the page out of it.
+        let depth = offset.saturating_add(limit).max(recency::CANDIDATE_WINDOW);
+        let hits = self.backend.search(parsed, depth, 0, sort).await?;
+        let mut page = hits.page;
+        page.results = decay.page(
+            page.results,
+            &hits.freshness,
+            chrono::Utc::now().timestamp(),
+            offset,
+            limit,
         );
+        Ok(page)
+    }
 
-        let search_results: Vec<SearchResult> = results
-            .hits
-            .into_iter()
-            .map(|hit| {
-                let doc = hit.result;
-
-                // Priority for snippet in search results:
-                // 1. Crop of raw_content (if significant)
-                // 2. Crop of summary
-                // 3. Full summary
-                let snippet = hit.formatted_result
-                    .as_ref()
-                    .and_then(|f| {
-                        // Try raw_content crop first
-                        f.get("raw_content")
-                            .and_then(|v| v.as_str())
-                            .filter(|s| s.len() > 10 && *s != "…")
-                            .map(|s| s.to_string())
-                            .or_else(|| {
-                                // Fallback to summary crop
-                                f.get("summary")
-                                    .and_then(|v| v.as_str())
-                                    .filter(|s| s.len() > 10 && *s != "…")
-                                    .map(|s| s.to_string())
-                            })
-                    })
-                    .unwrap_or_else(|| {
-                        doc.summary.clone().unwrap_or_else(|| "No preview available".to_string())
-                    });
-
-                SearchResult {
-                    id: doc.id,
-                    url: doc.url,
-                    title: doc.title,
-                    snippet,
-                    score: hit.ranking_score,
-                }
-            })
-            .collect();
-
-        Ok((search_results, total))
+    /// Best-matching passages for a plain-text query, best first. With
+    /// `document_ids`, only passages of those documents are searched.
+    pub async fn search_passages(
+        &self,
+        text: &str,
+        document_ids: &[Uuid],
+        limit: usize,
+    ) -> Result<Vec<PassageHit>, notice_core::Error> {
+        self.backend
+            .search_passages(text, document_ids, limit)
+            .await
     }
 }
+
+/// Sort a facet distribution by count (then value) and keep the top entries.
+pub(crate) fn top_facets(counts: Option<HashMap<String, usize>>) -> Vec<FacetCount> {
+    let mut facets: Vec<FacetCount> = counts
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(value, count)| FacetCount { value, count })
+        .collect();
+    facets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    facets.truncate(MAX_FACET_VALUES);
+    facets
+}
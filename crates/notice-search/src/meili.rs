@@ -0,0 +1,711 @@
+use async_trait::async_trait;
+use meilisearch_sdk::client::{Client, SwapIndexes};
+use meilisearch_sdk::documents::{DocumentDeletionQuery, DocumentsQuery};
+use meilisearch_sdk::search::Selectors;
+use notice_core::types::{ParsedQuery, SearchFacets, SearchResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::backend::{BackendPage, SearchBackend};
+use crate::chunk::PassageHit;
+use crate::vocabulary::Vocabulary;
+use crate::{MeiliDocumentInput, MeiliPassageInput, SearchPage, SearchSort, query, top_facets};
+
+const DOCUMENTS_INDEX: &str = "documents";
+const PASSAGES_INDEX: &str = "passages";
+/// Which settings version the live indexes were built with.
+const VERSIONS_INDEX: &str = "index_versions";
+
+/// Bump when the settings applied in `configure_index` or
+/// `configure_passages_index` change. Live indexes built with an older
+/// version are rebuilt as `documents_v{N}` and `passages_v{N}` and
+/// swapped in, instead of changing their settings in place under traffic.
+const SETTINGS_VERSION: u32 = 3;
+
+/// Documents fetched per request when listing the whole index.
+const BROWSE_PAGE: usize = 1000;
+
+/// Attributes we return facet distributions for.
+const FACET_ATTRIBUTES: &[&str] = &["domain", "status"];
+
+/// Meilisearch server backend.
+#[derive(Clone)]
+pub struct MeiliBackend {
+    client: Client,
+    documents: String,
+    passages: String,
+}
+
+/// What we READ from Meilisearch search results.
+/// Must match displayed_attributes — does NOT include raw_content.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MeiliDocumentOutput {
+    pub id: Uuid,
+    pub url: String,
+    pub domain: String,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    pub status: String,
+    pub quality_score: f64,
+    /// Missing on documents indexed before dates were added
+    #[serde(default)]
+    pub freshness_ts: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MeiliVersionOutput {
+    id: Uuid,
+    #[serde(default)]
+    updated_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexVersion {
+    id: String,
+    settings_version: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MeiliPassageOutput {
+    document_id: Uuid,
+    chunk_index: usize,
+    heading: Option<String>,
+    content: String,
+}
+
+impl MeiliBackend {
+    /// Create a new Meilisearch client.
+    pub fn new(url: &str, api_key: &str) -> Result<Self, notice_core::Error> {
+        let client = Client::new(url, Some(api_key))
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        tracing::info!("Meilisearch client created for {}", url);
+        Ok(Self {
+            client,
+            documents: DOCUMENTS_INDEX.to_string(),
+            passages: PASSAGES_INDEX.to_string(),
+        })
+    }
+
+    /// The same server, writing to the indexes a reindex builds.
+    fn staging(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            documents: format!("{}_v{}", DOCUMENTS_INDEX, SETTINGS_VERSION),
+            passages: format!("{}_v{}", PASSAGES_INDEX, SETTINGS_VERSION),
+        }
+    }
+
+    /// Create an index. Returns false if it already existed.
+    async fn create_index(&self, uid: &str) -> Result<bool, notice_core::Error> {
+        let task = self
+            .client
+            .create_index(uid, Some("id"))
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?
+            .wait_for_completion(&self.client, None, None)
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        Ok(task.is_success())
+    }
+
+    /// Delete the documents and passages indexes, if they exist.
+    async fn delete_indexes(&self) -> Result<(), notice_core::Error> {
+        for uid in [&self.documents, &self.passages] {
+            self.client
+                .delete_index(uid)
+                .await
+                .map_err(|e| notice_core::Error::Search(e.to_string()))?
+                .wait_for_completion(&self.client, None, None)
+                .await
+                .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Settings version of the live indexes; None if never recorded.
+    async fn live_version(&self) -> Result<Option<u32>, notice_core::Error> {
+        let index = self.client.index(VERSIONS_INDEX);
+        let page = DocumentsQuery::new(&index)
+            .with_limit(BROWSE_PAGE)
+            .execute::<IndexVersion>()
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        Ok(page
+            .results
+            .into_iter()
+            .find(|v| v.id == DOCUMENTS_INDEX)
+            .map(|v| v.settings_version))
+    }
+
+    async fn set_live_version(&self, version: u32) -> Result<(), notice_core::Error> {
+        let record = IndexVersion {
+            id: DOCUMENTS_INDEX.to_string(),
+            settings_version: version,
+        };
+        let task = self
+            .client
+            .index(VERSIONS_INDEX)
+            .add_or_replace(&[record], Some("id"))
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?
+            .wait_for_completion(&self.client, None, None)
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        if task.is_failure() {
+            return Err(notice_core::Error::Search(format!(
+                "Meilisearch could not record the index version: {:?}",
+                task
+            )));
+        }
+        Ok(())
+    }
+
+    /// Apply every setting to the documents and passages indexes, except
+    /// the vocabulary.
+    async fn apply_settings(&self) -> Result<(), notice_core::Error> {
+        self.configure_index().await?;
+        self.configure_passages_index().await
+    }
+
+    /// Configure the documents index with optimal settings.
+    async fn configure_index(&self) -> Result<(), notice_core::Error> {
+        let index = self.client.index(&self.documents);
+
+        // Searchable: what fields are searched (order = priority)
+        index
+            .set_searchable_attributes(["title", "summary", "raw_content", "url", "domain"])
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        // Displayed: what fields are returned in results
+        // NOTE: we include raw_content in displayed_attributes ONLY to allow
+        // Meilisearch to return cropped snippets in formatted_result.
+        index
+            .set_displayed_attributes([
+                "id",
+                "url",
+                "domain",
+                "title",
+                "summary",
+                "status",
+                "quality_score",
+                "freshness_ts",
+                "updated_at_ms",
+                "raw_content",
+            ])
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        // Filterable: for faceted search / filtering
+        index
+            .set_filterable_attributes([
+                "domain",
+                "status",
+                "crawled_at_ts",
+                "published_at_ts",
+                "freshness_ts",
+            ])
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        // Ranking rules
+        index
+            .set_ranking_rules([
+                "words",
+                "typo",
+                "proximity",
+                "attribute",
+                "sort",
+                "quality_score:desc",
+                "exactness",
+            ])
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        // IMPORTANT: quality_score MUST be sortable to work as a ranking rule
+        index
+            .set_sortable_attributes(["quality_score", "crawled_at_ts", "freshness_ts"])
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        tracing::info!("Meilisearch '{}' index configured", self.documents);
+        Ok(())
+    }
+
+    async fn configure_passages_index(&self) -> Result<(), notice_core::Error> {
+        let index = self.client.index(&self.passages);
+
+        index
+            .set_searchable_attributes(["heading", "content", "title"])
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        index
+            .set_filterable_attributes(["document_id", "chunk_index", "domain"])
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        tracing::info!("Meilisearch '{}' index configured", self.passages);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SearchBackend for MeiliBackend {
+    fn name(&self) -> &'static str {
+        "meilisearch"
+    }
+
+    /// Health check.
+    async fn health(&self) -> Result<(), notice_core::Error> {
+        self.client
+            .health()
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Settings only go onto freshly created indexes: changing them on a
+    /// live index reindexes it in place. Outdated live indexes are left
+    /// for [`SearchBackend::begin_reindex`].
+    async fn configure(&self) -> Result<(), notice_core::Error> {
+        self.create_index(VERSIONS_INDEX).await?;
+        let documents_created = self.create_index(&self.documents).await?;
+        if self.create_index(&self.passages).await? && !documents_created {
+            self.configure_passages_index().await?;
+        }
+        if documents_created {
+            self.apply_settings().await?;
+            self.set_live_version(SETTINGS_VERSION).await?;
+        } else if self.needs_reindex().await? {
+            tracing::warn!(
+                version = SETTINGS_VERSION,
+                "Meilisearch indexes were built with older settings, a reindex is due"
+            );
+        }
+        Ok(())
+    }
+
+    /// Changing either setting reindexes the documents index, so both are
+    /// left alone when they already match.
+    async fn set_vocabulary(&self, vocabulary: &Vocabulary) -> Result<(), notice_core::Error> {
+        let index = self.client.index(&self.documents);
+
+        let synonyms = index
+            .get_synonyms()
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        if !same_synonyms(&synonyms, &vocabulary.synonyms) {
+            index
+                .set_synonyms(&vocabulary.synonyms)
+                .await
+                .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        }
+
+        let mut stop_words = index
+            .get_stop_words()
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        let mut wanted = vocabulary.stop_words.clone();
+        stop_words.sort();
+        wanted.sort();
+        if stop_words != wanted {
+            index
+                .set_stop_words(&wanted)
+                .await
+                .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    async fn needs_reindex(&self) -> Result<bool, notice_core::Error> {
+        Ok(self.live_version().await? < Some(SETTINGS_VERSION))
+    }
+
+    /// Recreate `documents_v{N}`/`passages_v{N}` empty, dropping what an
+    /// interrupted reindex left behind, and apply the current settings.
+    async fn begin_reindex(&self) -> Result<Box<dyn SearchBackend>, notice_core::Error> {
+        let staging = self.staging();
+        staging.delete_indexes().await?;
+        staging.create_index(&staging.documents).await?;
+        staging.create_index(&staging.passages).await?;
+        staging.apply_settings().await?;
+        Ok(Box::new(staging))
+    }
+
+    /// Swap both staging indexes with the live ones in one task, then drop
+    /// the old ones (now under the staging names).
+    async fn finish_reindex(&self) -> Result<(), notice_core::Error> {
+        let staging = self.staging();
+        let swaps = [
+            SwapIndexes {
+                indexes: (self.documents.clone(), staging.documents.clone()),
+            },
+            SwapIndexes {
+                indexes: (self.passages.clone(), staging.passages.clone()),
+            },
+        ];
+        let task = self
+            .client
+            .swap_indexes(&swaps)
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?
+            .wait_for_completion(&self.client, None, Some(Duration::from_secs(60)))
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        if task.is_failure() {
+            return Err(notice_core::Error::Search(format!(
+                "Meilisearch index swap failed: {:?}",
+                task
+            )));
+        }
+
+        self.set_live_version(SETTINGS_VERSION).await?;
+        staging.delete_indexes().await?;
+        tracing::info!(version = SETTINGS_VERSION, "Meilisearch indexes swapped in");
+        Ok(())
+    }
+
+    // ─── Write Operations (Direct Sync) ───
+
+    /// Add or update documents in Meilisearch.
+    /// Waits for the indexing task to complete (up to 30s).
+    async fn index_documents(&self, docs: &[MeiliDocumentInput]) -> Result<(), notice_core::Error> {
+        if docs.is_empty() {
+            return Ok(());
+        }
+
+        let index = self.client.index(&self.documents);
+
+        let task = index
+            .add_documents(docs, Some("id"))
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        let task = task
+            .wait_for_completion(
+                &self.client,
+                Some(Duration::from_millis(200)),
+                Some(Duration::from_secs(30)),
+            )
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        // Check if the task succeeded
+        if task.is_failure() {
+            let error_msg = format!("Meilisearch indexing task failed: {:?}", task);
+            tracing::error!("{}", error_msg);
+            return Err(notice_core::Error::Search(error_msg));
+        }
+
+        tracing::debug!("Indexed {} document(s) in Meilisearch", docs.len());
+        Ok(())
+    }
+
+    /// Delete a document from Meilisearch by ID.
+    async fn delete_document(&self, id: Uuid) -> Result<(), notice_core::Error> {
+        let index = self.client.index(&self.documents);
+
+        let task = index
+            .delete_document(&id.to_string())
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        let task = task
+            .wait_for_completion(
+                &self.client,
+                Some(Duration::from_millis(200)),
+                Some(Duration::from_secs(10)),
+            )
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        // The outbox drops the entry on success, so a failure must surface
+        if task.is_failure() {
+            return Err(notice_core::Error::Search(format!(
+                "Meilisearch deletion task failed: {:?}",
+                task
+            )));
+        }
+        self.replace_passages(id, &[]).await?;
+
+        tracing::debug!("Deleted document {} from Meilisearch", id);
+        Ok(())
+    }
+
+    /// Upsert the new passages and drop any left over from a longer
+    /// earlier version.
+    async fn replace_passages(
+        &self,
+        document_id: Uuid,
+        passages: &[MeiliPassageInput],
+    ) -> Result<(), notice_core::Error> {
+        let index = self.client.index(&self.passages);
+
+        if !passages.is_empty() {
+            let task = index
+                .add_documents(passages, Some("id"))
+                .await
+                .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+            let task = task
+                .wait_for_completion(
+                    &self.client,
+                    Some(Duration::from_millis(200)),
+                    Some(Duration::from_secs(30)),
+                )
+                .await
+                .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+            if task.is_failure() {
+                return Err(notice_core::Error::Search(format!(
+                    "Meilisearch passage indexing task failed: {:?}",
+                    task
+                )));
+            }
+        }
+
+        let filter = format!(
+            "document_id = \"{}\" AND chunk_index >= {}",
+            document_id,
+            passages.len()
+        );
+        let mut deletion = DocumentDeletionQuery::new(&index);
+        deletion.with_filter(&filter);
+        let task = index
+            .delete_documents_with(&deletion)
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?
+            .wait_for_completion(
+                &self.client,
+                Some(Duration::from_millis(200)),
+                Some(Duration::from_secs(30)),
+            )
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        if task.is_failure() {
+            return Err(notice_core::Error::Search(format!(
+                "Meilisearch passage deletion task failed: {:?}",
+                task
+            )));
+        }
+
+        tracing::debug!("Indexed {} passage(s) of {}", passages.len(), document_id);
+        Ok(())
+    }
+
+    /// Get the number of documents in the index.
+    async fn document_count(&self) -> Result<usize, notice_core::Error> {
+        let index = self.client.index(&self.documents);
+        let stats = index
+            .get_stats()
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+        Ok(stats.number_of_documents)
+    }
+
+    async fn indexed_versions(&self) -> Result<Vec<(Uuid, Option<i64>)>, notice_core::Error> {
+        let index = self.client.index(&self.documents);
+        let mut versions = Vec::new();
+        loop {
+            let mut query = DocumentsQuery::new(&index);
+            query
+                .with_offset(versions.len())
+                .with_limit(BROWSE_PAGE)
+                .with_fields(["id", "updated_at_ms"]);
+            let page = query
+                .execute::<MeiliVersionOutput>()
+                .await
+                .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+            let fetched = page.results.len();
+            versions.extend(page.results.into_iter().map(|d| (d.id, d.updated_at_ms)));
+            if fetched < BROWSE_PAGE {
+                break;
+            }
+        }
+        Ok(versions)
+    }
+
+    // ─── Search ───
+
+    /// Operators become Meilisearch filters, phrases and exclusions go
+    /// into the query string.
+    async fn search(
+        &self,
+        parsed: &ParsedQuery,
+        limit: usize,
+        offset: usize,
+        sort: SearchSort,
+    ) -> Result<BackendPage, notice_core::Error> {
+        let index = self.client.index(&self.documents);
+        let query = query::meili_query(parsed);
+        let filter = query::meili_filter(parsed);
+
+        let mut request = index.search();
+        request
+            .with_query(&query)
+            .with_limit(limit)
+            .with_offset(offset)
+            .with_show_ranking_score(true)
+            .with_attributes_to_retrieve(Selectors::All)
+            // Request crops for both summary and raw_content to have backup context
+            .with_attributes_to_crop(Selectors::Some(&[
+                ("summary", Some(250)),
+                ("raw_content", Some(500)),
+            ]))
+            .with_attributes_to_highlight(Selectors::Some(&["title", "summary"]))
+            .with_facets(Selectors::Some(FACET_ATTRIBUTES));
+        if let Some(filter) = &filter {
+            request.with_filter(filter);
+        }
+        if sort == SearchSort::Recent {
+            request.with_sort(&["freshness_ts:desc"]);
+        }
+
+        let results = request
+            .execute::<MeiliDocumentOutput>()
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        let total = results.estimated_total_hits.unwrap_or(0);
+
+        tracing::debug!(
+            query = %query,
+            filter = ?filter,
+            hits = results.hits.len(),
+            total = total,
+            "Meilisearch search results received"
+        );
+
+        let freshness: Vec<Option<i64>> = results
+            .hits
+            .iter()
+            .map(|hit| hit.result.freshness_ts)
+            .collect();
+
+        let search_results: Vec<SearchResult> = results
+            .hits
+            .into_iter()
+            .map(|hit| {
+                let doc = hit.result;
+
+                // Priority for snippet in search results:
+                // 1. Crop of raw_content (if significant)
+                // 2. Crop of summary
+                // 3. Full summary
+                let snippet = hit
+                    .formatted_result
+                    .as_ref()
+                    .and_then(|f| {
+                        // Try raw_content crop first
+                        f.get("raw_content")
+                            .and_then(|v| v.as_str())
+                            .filter(|s| s.len() > 10 && *s != "…")
+                            .map(|s| s.to_string())
+                            .or_else(|| {
+                                // Fallback to summary crop
+                                f.get("summary")
+                                    .and_then(|v| v.as_str())
+                                    .filter(|s| s.len() > 10 && *s != "…")
+                                    .map(|s| s.to_string())
+                            })
+                    })
+                    .unwrap_or_else(|| {
+                        doc.summary
+                            .clone()
+                            .unwrap_or_else(|| "No preview available".to_string())
+                    });
+
+                SearchResult {
+                    id: doc.id,
+                    url: doc.url,
+                    title: doc.title,
+                    snippet,
+                    score: hit.ranking_score,
+                }
+            })
+            .collect();
+
+        let mut distribution = results.facet_distribution.unwrap_or_default();
+        let facets = SearchFacets {
+            domains: top_facets(distribution.remove("domain")),
+            statuses: top_facets(distribution.remove("status")),
+        };
+
+        Ok(BackendPage {
+            page: SearchPage {
+                results: search_results,
+                total,
+                facets,
+            },
+            freshness,
+        })
+    }
+
+    async fn search_passages(
+        &self,
+        text: &str,
+        document_ids: &[Uuid],
+        limit: usize,
+    ) -> Result<Vec<PassageHit>, notice_core::Error> {
+        let index = self.client.index(&self.passages);
+        let filter = (!document_ids.is_empty()).then(|| {
+            let ids: Vec<String> = document_ids
+                .iter()
+                .map(|id| format!("\"{}\"", id))
+                .collect();
+            format!("document_id IN [{}]", ids.join(", "))
+        });
+
+        let mut request = index.search();
+        request
+            .with_query(text)
+            .with_limit(limit)
+            .with_show_ranking_score(true)
+            .with_attributes_to_crop(Selectors::Some(&[("content", Some(60))]));
+        if let Some(filter) = &filter {
+            request.with_filter(filter);
+        }
+
+        let results = request
+            .execute::<MeiliPassageOutput>()
+            .await
+            .map_err(|e| notice_core::Error::Search(e.to_string()))?;
+
+        Ok(results
+            .hits
+            .into_iter()
+            .map(|hit| {
+                let snippet = hit
+                    .formatted_result
+                    .as_ref()
+                    .and_then(|f| f.get("content"))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| hit.result.content.clone());
+                PassageHit {
+                    document_id: hit.result.document_id,
+                    chunk_index: hit.result.chunk_index,
+                    heading: hit.result.heading,
+                    content: hit.result.content,
+                    snippet,
+                    score: hit.ranking_score,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Whether two synonym maps match, ignoring the order of each term's
+/// synonyms.
+fn same_synonyms(a: &HashMap<String, Vec<String>>, b: &HashMap<String, Vec<String>>) -> bool {
+    let sorted = |terms: &Vec<String>| {
+        let mut terms = terms.clone();
+        terms.sort();
+        terms
+    };
+    a.len() == b.len()
+        && a.iter()
+            .all(|(term, synonyms)| b.get(term).map(sorted) == Some(sorted(synonyms)))
+}
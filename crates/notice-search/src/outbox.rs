@@ -0,0 +1,250 @@
+use notice_db::documents::DocumentRow;
+use notice_db::index_outbox::OutboxEntry;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::{MeiliDocumentInput, MeiliPassageInput, SearchClient};
+
+/// How long claimed entries are hidden from other dispatchers. A batch
+/// that takes longer (or a dispatcher that dies) gets pushed again.
+const LEASE_SECS: i64 = 300;
+
+/// First retry delay; doubles with each failed attempt.
+const RETRY_BASE: Duration = Duration::from_secs(5);
+const RETRY_MAX: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone)]
+pub struct DispatcherOptions {
+    /// Most entries claimed per batch
+    pub batch_size: usize,
+    /// Sleep between polls when the outbox is drained
+    pub poll_interval: Duration,
+    /// Failed attempts before an entry is kept as failed
+    pub max_attempts: u32,
+}
+
+/// Start pushing changes recorded in `index_outbox` to the search backend
+/// in the background. Entries are written in the same transaction as the
+/// document change, so the index catches up with every commit even if the
+/// backend was down at the time.
+pub fn spawn_dispatcher(db: PgPool, search: SearchClient, options: DispatcherOptions) {
+    tokio::spawn(async move {
+        loop {
+            match dispatch_batch(&db, &search, &options).await {
+                // A full batch means more is probably waiting
+                Ok(claimed) if claimed >= options.batch_size => continue,
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = %e, "Index outbox dispatch failed"),
+            }
+            tokio::time::sleep(options.poll_interval).await;
+        }
+    });
+}
+
+/// Claim one batch and push it. Returns how many entries were claimed.
+pub async fn dispatch_batch(
+    db: &PgPool,
+    search: &SearchClient,
+    options: &DispatcherOptions,
+) -> Result<usize, notice_core::Error> {
+    let entries =
+        notice_db::index_outbox::claim(db, options.batch_size.max(1) as i64, LEASE_SECS).await?;
+    if entries.is_empty() {
+        return Ok(0);
+    }
+
+    let changes = collapse(&entries);
+    let upsert_ids: Vec<Uuid> = changes
+        .iter()
+        .filter(|c| !c.delete)
+        .map(|c| c.document_id)
+        .collect();
+    let mut docs: HashMap<Uuid, DocumentRow> = notice_db::documents::get_many(db, &upsert_ids)
+        .await?
+        .into_iter()
+        .map(|doc| (doc.id, doc))
+        .collect();
+
+    let mut done: Vec<i64> = vec![];
+    let mut failed: Vec<(&[i64], String)> = vec![];
+
+    // Upserts first in one call, then each document's passages
+    let (upserts, deletes): (Vec<&Change>, Vec<&Change>) = changes
+        .iter()
+        .partition(|c| !c.delete && docs.contains_key(&c.document_id));
+    if !upserts.is_empty() {
+        let inputs: Vec<MeiliDocumentInput> = upserts
+            .iter()
+            .map(|c| MeiliDocumentInput::from(&docs[&c.document_id]))
+            .collect();
+        match search.add_documents(&inputs).await {
+            Ok(()) => {
+                for change in &upserts {
+                    let Some(doc) = docs.remove(&change.document_id) else {
+                        continue;
+                    };
+                    match sync_passages(db, search, &doc).await {
+                        Ok(()) => done.extend_from_slice(&change.entry_ids),
+                        Err(e) => failed.push((&change.entry_ids, e.to_string())),
+                    }
+                }
+            }
+            Err(e) => {
+                let error = e.to_string();
+                failed.extend(
+                    upserts
+                        .iter()
+                        .map(|c| (c.entry_ids.as_slice(), error.clone())),
+                );
+            }
+        }
+    }
+
+    // Deletes, including upserts of documents deleted since
+    for change in deletes {
+        match search.delete_document(change.document_id).await {
+            Ok(()) => done.extend_from_slice(&change.entry_ids),
+            Err(e) => failed.push((&change.entry_ids, e.to_string())),
+        }
+    }
+
+    notice_db::index_outbox::complete(db, &done).await?;
+
+    let attempts: HashMap<i64, i32> = entries.iter().map(|e| (e.id, e.attempts)).collect();
+    for (ids, error) in &failed {
+        for id in ids.iter() {
+            let attempt = attempts.get(id).copied().unwrap_or(0) as u32 + 1;
+            let give_up = attempt >= options.max_attempts;
+            if give_up {
+                tracing::error!(entry = id, attempts = attempt, error = %error, "Index outbox entry failed, giving up");
+            }
+            notice_db::index_outbox::record_failure(
+                db,
+                *id,
+                error,
+                retry_delay(attempt).as_secs() as i64,
+                give_up,
+            )
+            .await?;
+        }
+    }
+
+    if failed.is_empty() {
+        tracing::debug!(entries = entries.len(), "Index outbox batch pushed");
+    } else {
+        tracing::warn!(
+            entries = entries.len(),
+            failed = failed.len(),
+            error = %failed[0].1,
+            "Index outbox batch partly failed, will retry"
+        );
+    }
+
+    Ok(entries.len())
+}
+
+/// Push a document's stored passages to the passages index.
+pub(crate) async fn sync_passages(
+    db: &PgPool,
+    search: &SearchClient,
+    doc: &DocumentRow,
+) -> Result<(), notice_core::Error> {
+    let chunks = notice_db::chunks::for_document(db, doc.id).await?;
+    let passages: Vec<MeiliPassageInput> = chunks
+        .iter()
+        .map(|chunk| MeiliPassageInput::new(doc, chunk))
+        .collect();
+    search.replace_passages(doc.id, &passages).await
+}
+
+/// What to do for one document, and the entries that asked for it.
+#[derive(Debug, Clone, PartialEq)]
+struct Change {
+    document_id: Uuid,
+    /// The latest entry was a delete
+    delete: bool,
+    entry_ids: Vec<i64>,
+}
+
+/// One change per document, in order of first appearance; the latest
+/// entry decides whether it is an upsert or a delete. Entries must be
+/// sorted by id.
+fn collapse(entries: &[OutboxEntry]) -> Vec<Change> {
+    let mut changes: Vec<Change> = vec![];
+    let mut positions: HashMap<Uuid, usize> = HashMap::new();
+
+    for entry in entries {
+        let delete = entry.op == "delete";
+        match positions.get(&entry.document_id) {
+            Some(&i) => {
+                changes[i].delete = delete;
+                changes[i].entry_ids.push(entry.id);
+            }
+            None => {
+                positions.insert(entry.document_id, changes.len());
+                changes.push(Change {
+                    document_id: entry.document_id,
+                    delete,
+                    entry_ids: vec![entry.id],
+                });
+            }
+        }
+    }
+    changes
+}
+
+/// Delay before retrying after the `attempt`th failure (1-based).
+fn retry_delay(attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    RETRY_BASE.saturating_mul(factor).min(RETRY_MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: i64, doc: u128, op: &str) -> OutboxEntry {
+        OutboxEntry {
+            id,
+            document_id: Uuid::from_u128(doc),
+            op: op.to_string(),
+            attempts: 0,
+        }
+    }
+
+    #[test]
+    fn collapses_entries_per_document() {
+        let changes = collapse(&[
+            entry(1, 1, "upsert"),
+            entry(2, 2, "upsert"),
+            entry(3, 1, "upsert"),
+            entry(4, 2, "delete"),
+        ]);
+        assert_eq!(
+            changes,
+            vec![
+                Change {
+                    document_id: Uuid::from_u128(1),
+                    delete: false,
+                    entry_ids: vec![1, 3],
+                },
+                Change {
+                    document_id: Uuid::from_u128(2),
+                    delete: true,
+                    entry_ids: vec![2, 4],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap() {
+        assert_eq!(retry_delay(1), Duration::from_secs(5));
+        assert_eq!(retry_delay(2), Duration::from_secs(10));
+        assert_eq!(retry_delay(4), Duration::from_secs(40));
+        assert_eq!(retry_delay(30), RETRY_MAX);
+        assert_eq!(retry_delay(200), RETRY_MAX);
+    }
+}
@@ -0,0 +1,269 @@
+use chrono::NaiveDate;
+use notice_core::types::{ParsedQuery, with_www};
+
+/// Document statuses accepted by `status:`.
+const STATUSES: &[&str] = &["pending_summary", "summarized", "failed"];
+
+/// Parse a raw query into free text and operators.
+///
+/// Supported: `site:docs.rs`, `-site:example.com`, `-term`, `"exact phrase"`,
+/// `-"excluded phrase"`, `status:summarized`, `after:2025-01-01`,
+/// `before:2025-06-30`. Operators with invalid values ("after:yesterday")
+/// are kept as plain terms rather than silently dropped.
+pub fn parse(raw: &str) -> ParsedQuery {
+    let mut parsed = ParsedQuery::default();
+    let mut chars = raw.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let negated = c == '-';
+        let body_start = if negated { start + 1 } else { start };
+
+        // Quoted phrase: runs to the closing quote (or the end of input)
+        if raw[body_start..].starts_with('"') {
+            let phrase_start = body_start + 1;
+            let phrase_end = raw[phrase_start..]
+                .find('"')
+                .map_or(raw.len(), |i| phrase_start + i);
+            let phrase = raw[phrase_start..phrase_end].trim();
+            if !phrase.is_empty() {
+                if negated {
+                    parsed.excluded.push(format!("\"{}\"", phrase));
+                } else {
+                    parsed.phrases.push(phrase.to_string());
+                }
+            }
+            let resume = (phrase_end + 1).min(raw.len());
+            while chars.peek().is_some_and(|&(i, _)| i < resume) {
+                chars.next();
+            }
+            continue;
+        }
+
+        let mut end = raw.len();
+        while let Some(&(i, c)) = chars.peek() {
+            if c.is_whitespace() {
+                end = i;
+                break;
+            }
+            chars.next();
+        }
+        let token = &raw[start..end];
+        let body = &raw[body_start..end];
+
+        if apply_operator(&mut parsed, body, negated) {
+            continue;
+        }
+
+        if negated && body.chars().any(char::is_alphanumeric) {
+            parsed.excluded.push(body.to_string());
+        } else {
+            parsed.terms.push(token.to_string());
+        }
+    }
+
+    parsed
+}
+
+/// Apply a filter given outside the query string, e.g. the `domain`
+/// search parameter as ("site", "docs.rs"). Returns false if the value
+/// isn't valid for that operator.
+pub fn add_filter(parsed: &mut ParsedQuery, operator: &str, value: &str) -> bool {
+    apply_operator(parsed, &format!("{}:{}", operator, value.trim()), false)
+}
+
+/// Handle `key:value` operators. Returns false if `token` isn't one.
+fn apply_operator(parsed: &mut ParsedQuery, token: &str, negated: bool) -> bool {
+    let Some((key, value)) = token.split_once(':') else {
+        return false;
+    };
+    if value.is_empty() {
+        return false;
+    }
+
+    match (key.to_lowercase().as_str(), negated) {
+        ("site", _) => {
+            let Some(site) = normalize_site(value) else {
+                return false;
+            };
+            if negated {
+                parsed.excluded_sites.push(site);
+            } else {
+                parsed.sites.push(site);
+            }
+        }
+        ("status", false) => {
+            let status = value.to_lowercase();
+            if !STATUSES.contains(&status.as_str()) {
+                return false;
+            }
+            parsed.status = Some(status);
+        }
+        ("after", false) => match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            Ok(date) => parsed.after = Some(date),
+            Err(_) => return false,
+        },
+        ("before", false) => match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            Ok(date) => parsed.before = Some(date),
+            Err(_) => return false,
+        },
+        _ => return false,
+    }
+
+    true
+}
+
+/// "https://www.Docs.rs/" → "docs.rs"
+fn normalize_site(value: &str) -> Option<String> {
+    let host = value
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .split('/')
+        .next()?
+        .to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+
+    let valid = host.contains('.')
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+    valid.then(|| host.to_string())
+}
+
+/// The query string for Meilisearch: terms, quoted phrases and negated
+/// words, which Meilisearch handles natively.
+pub fn meili_query(parsed: &ParsedQuery) -> String {
+    let mut parts: Vec<String> = parsed.terms.clone();
+    parts.extend(parsed.phrases.iter().map(|p| format!("\"{}\"", p)));
+    parts.extend(parsed.excluded.iter().map(|e| format!("-{}", e)));
+    parts.join(" ")
+}
+
+/// Plain text of the query without operators, e.g. for discovery.
+pub fn text(parsed: &ParsedQuery) -> String {
+    let mut parts: Vec<&str> = parsed.terms.iter().map(String::as_str).collect();
+    parts.extend(parsed.phrases.iter().map(String::as_str));
+    parts.join(" ")
+}
+
+/// Back to query syntax, e.g. to show a corrected query the user can
+/// submit as-is. Operators come after the free text.
+pub fn render(parsed: &ParsedQuery) -> String {
+    let mut parts: Vec<String> = parsed.terms.clone();
+    parts.extend(parsed.phrases.iter().map(|p| format!("\"{}\"", p)));
+    parts.extend(parsed.excluded.iter().map(|e| format!("-{}", e)));
+    parts.extend(parsed.sites.iter().map(|s| format!("site:{}", s)));
+    parts.extend(parsed.excluded_sites.iter().map(|s| format!("-site:{}", s)));
+    if let Some(status) = &parsed.status {
+        parts.push(format!("status:{}", status));
+    }
+    if let Some(after) = parsed.after {
+        parts.push(format!("after:{}", after));
+    }
+    if let Some(before) = parsed.before {
+        parts.push(format!("before:{}", before));
+    }
+    parts.join(" ")
+}
+
+/// Meilisearch filter expression for the operators, if any.
+pub fn meili_filter(parsed: &ParsedQuery) -> Option<String> {
+    let mut clauses = Vec::new();
+
+    if !parsed.sites.is_empty() {
+        clauses.push(format!("domain IN [{}]", domain_list(&parsed.sites)));
+    }
+    if !parsed.excluded_sites.is_empty() {
+        clauses.push(format!(
+            "domain NOT IN [{}]",
+            domain_list(&parsed.excluded_sites)
+        ));
+    }
+    if let Some(status) = &parsed.status {
+        clauses.push(format!("status = {}", quote(status)));
+    }
+    if let Some(after) = parsed.after {
+        clauses.push(format!("freshness_ts >= {}", day_start(after)));
+    }
+    if let Some(before) = parsed.before {
+        clauses.push(format!("freshness_ts < {}", day_start(before)));
+    }
+
+    (!clauses.is_empty()).then(|| clauses.join(" AND "))
+}
+
+/// Each site matches with and without "www.".
+fn domain_list(sites: &[String]) -> String {
+    with_www(sites)
+        .iter()
+        .map(|s| quote(s))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub(crate) fn day_start(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc().timestamp())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_operators() {
+        let parsed = parse(
+            r#"tokio "select macro" -async-std site:docs.rs status:summarized after:2025-01-01"#,
+        );
+        assert_eq!(parsed.terms, vec!["tokio"]);
+        assert_eq!(parsed.phrases, vec!["select macro"]);
+        assert_eq!(parsed.excluded, vec!["async-std"]);
+        assert_eq!(parsed.sites, vec!["docs.rs"]);
+        assert_eq!(parsed.status.as_deref(), Some("summarized"));
+        assert_eq!(parsed.after, NaiveDate::from_ymd_opt(2025, 1, 1));
+        assert_eq!(meili_query(&parsed), r#"tokio "select macro" -async-std"#);
+        assert_eq!(text(&parsed), "tokio select macro");
+        assert_eq!(
+            render(&parsed),
+            r#"tokio "select macro" -async-std site:docs.rs status:summarized after:2025-01-01"#
+        );
+    }
+
+    #[test]
+    fn builds_filters() {
+        let parsed = parse("rust -site:www.reddit.com before:2024-02-01");
+        assert_eq!(
+            meili_filter(&parsed).as_deref(),
+            Some(r#"domain NOT IN ["reddit.com", "www.reddit.com"] AND freshness_ts < 1706745600"#)
+        );
+        assert_eq!(meili_filter(&parse("plain words")), None);
+
+        let mut parsed = parse("rust");
+        assert!(add_filter(&mut parsed, "site", "https://docs.rs/tokio"));
+        assert!(add_filter(&mut parsed, "status", "summarized"));
+        assert!(!add_filter(&mut parsed, "after", "last week"));
+        assert_eq!(
+            meili_filter(&parsed).as_deref(),
+            Some(r#"domain IN ["docs.rs", "www.docs.rs"] AND status = "summarized""#)
+        );
+    }
+
+    #[test]
+    fn keeps_invalid_operators_as_text() {
+        let parsed = parse("after:yesterday status:bogus std::fmt - x");
+        assert_eq!(
+            parsed.terms,
+            vec!["after:yesterday", "status:bogus", "std::fmt", "-", "x"]
+        );
+        assert_eq!(meili_filter(&parsed), None);
+    }
+}
@@ -0,0 +1,127 @@
+use notice_core::types::SearchResult;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// How many top relevance hits are re-ranked by age. Pages that start
+/// deeper than this keep plain relevance order.
+pub const CANDIDATE_WINDOW: usize = 100;
+
+/// Exponential recency decay on top of Meilisearch relevance, which has no
+/// notion of document age. A document one half-life old loses half of
+/// `weight` from its score; `weight` = 0.3 means age can cost at most 30%.
+#[derive(Debug, Clone, Copy)]
+pub struct RecencyDecay {
+    pub half_life_days: f64,
+    pub weight: f64,
+}
+
+impl RecencyDecay {
+    /// Score multiplier in `[1 - weight, 1]`. Documents of unknown age are
+    /// treated as one half-life old.
+    pub fn factor(&self, age_secs: Option<i64>) -> f64 {
+        let weight = self.weight.clamp(0.0, 1.0);
+        let decay = match age_secs {
+            Some(age) => {
+                let age_days = age.max(0) as f64 / SECONDS_PER_DAY;
+                0.5_f64.powf(age_days / self.half_life_days)
+            }
+            None => 0.5,
+        };
+        1.0 - weight + weight * decay
+    }
+
+    /// Re-order a page of results by decayed score. `freshness` holds each
+    /// result's freshness timestamp, in order. Reported scores stay
+    /// Meilisearch's own, so thresholds on them keep their meaning.
+    pub fn apply(
+        &self,
+        results: Vec<SearchResult>,
+        freshness: &[Option<i64>],
+        now_ts: i64,
+    ) -> Vec<SearchResult> {
+        if self.half_life_days <= 0.0 {
+            return results;
+        }
+
+        let mut scored: Vec<(f64, SearchResult)> = results
+            .into_iter()
+            .enumerate()
+            .map(|(i, result)| {
+                let age = freshness.get(i).copied().flatten().map(|ts| now_ts - ts);
+                (result.score.unwrap_or(0.0) * self.factor(age), result)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        scored.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Cut the `offset`/`limit` page out of `candidates`, the top hits in
+    /// relevance order starting at rank 0. The first [`CANDIDATE_WINDOW`]
+    /// are re-ordered by decayed score, so a fresh document below the first
+    /// page can still move onto it; the rest keep relevance order.
+    pub fn page(
+        &self,
+        mut candidates: Vec<SearchResult>,
+        freshness: &[Option<i64>],
+        now_ts: i64,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<SearchResult> {
+        let rest = candidates.split_off(CANDIDATE_WINDOW.min(candidates.len()));
+        let mut ranked = self.apply(candidates, freshness, now_ts);
+        ranked.extend(rest);
+        ranked.into_iter().skip(offset).take(limit).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn result(title: &str, score: f64) -> SearchResult {
+        SearchResult {
+            id: Uuid::nil(),
+            url: format!("https://example.com/{}", title),
+            title: Some(title.to_string()),
+            snippet: String::new(),
+            score: Some(score),
+        }
+    }
+
+    #[test]
+    fn newer_documents_win_close_calls() {
+        let decay = RecencyDecay {
+            half_life_days: 30.0,
+            weight: 0.3,
+        };
+        assert_eq!(decay.factor(Some(0)), 1.0);
+        assert!((decay.factor(Some(30 * 86_400)) - 0.85).abs() < 1e-9);
+
+        let now = 1_000 * 86_400;
+        let results = vec![result("old", 0.90), result("new", 0.85)];
+        let results = decay.apply(results, &[Some(now - 365 * 86_400), Some(now)], now);
+        assert_eq!(results[0].title.as_deref(), Some("new"));
+        assert_eq!(results[0].score, Some(0.85));
+    }
+
+    #[test]
+    fn fresh_documents_below_the_first_page_move_up() {
+        let decay = RecencyDecay {
+            half_life_days: 30.0,
+            weight: 0.3,
+        };
+        let now = 1_000 * 86_400;
+        let candidates: Vec<SearchResult> = (0..20)
+            .map(|i| result(&format!("doc{}", i), 0.90 - i as f64 * 0.005))
+            .collect();
+        let mut freshness = vec![Some(now - 365 * 86_400); 20];
+        freshness[15] = Some(now);
+
+        let first = decay.page(candidates.clone(), &freshness, now, 0, 10);
+        let second = decay.page(candidates, &freshness, now, 10, 10);
+        assert_eq!(first[0].title.as_deref(), Some("doc15"));
+        assert_eq!(first.len() + second.len(), 20);
+        assert!(second.iter().all(|r| r.title.as_deref() != Some("doc15")));
+    }
+}
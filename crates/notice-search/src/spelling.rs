@@ -0,0 +1,284 @@
+use std::collections::{HashMap, HashSet};
+
+/// Largest edit distance we correct across.
+const MAX_EDIT_DISTANCE: usize = 2;
+
+/// Deletes are only generated for this many leading characters, which keeps
+/// the index small while still catching typos in long words.
+const PREFIX_LENGTH: usize = 7;
+
+/// Words seen fewer times than this are treated as noise when building the
+/// dictionary (typos in the corpus itself, tracking IDs, ...).
+pub const MIN_WORD_COUNT: u64 = 2;
+
+/// Longest word indexed; longer tokens are hashes, URLs or garbage.
+const MAX_WORD_LENGTH: usize = 30;
+
+/// A spelling suggestion for one word.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub term: String,
+    pub distance: usize,
+    pub count: u64,
+}
+
+/// Symmetric delete spelling correction (SymSpell): every dictionary word is
+/// indexed under all strings reachable by deleting up to
+/// `MAX_EDIT_DISTANCE` characters from its prefix, so a lookup only has to
+/// generate deletes of the input instead of every possible edit.
+#[derive(Debug, Default)]
+pub struct SymSpell {
+    words: Vec<(String, u64)>,
+    index: HashMap<String, u32>,
+    deletes: HashMap<String, Vec<u32>>,
+}
+
+impl SymSpell {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build from word counts, dropping words below [`MIN_WORD_COUNT`].
+    pub fn from_counts(counts: HashMap<String, u64>) -> Self {
+        let mut speller = Self::new();
+        for (word, count) in counts {
+            if count >= MIN_WORD_COUNT {
+                speller.add_word(&word, count);
+            }
+        }
+        speller
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.index.contains_key(word)
+    }
+
+    /// Add a word (or more occurrences of one already present).
+    pub fn add_word(&mut self, word: &str, count: u64) {
+        let word = word.to_lowercase();
+        if let Some(&id) = self.index.get(&word) {
+            self.words[id as usize].1 += count;
+            return;
+        }
+
+        let id = self.words.len() as u32;
+        for key in edits(&prefix(&word), MAX_EDIT_DISTANCE) {
+            self.deletes.entry(key).or_default().push(id);
+        }
+        self.index.insert(word.clone(), id);
+        self.words.push((word, count));
+    }
+
+    /// The closest dictionary word: smallest edit distance, then most frequent.
+    pub fn lookup(&self, word: &str, max_distance: usize) -> Option<Suggestion> {
+        let word = word.to_lowercase();
+        let max_distance = max_distance.min(MAX_EDIT_DISTANCE);
+
+        if let Some(&id) = self.index.get(&word) {
+            return Some(Suggestion {
+                term: word,
+                distance: 0,
+                count: self.words[id as usize].1,
+            });
+        }
+
+        let input: Vec<char> = word.chars().collect();
+        let mut best: Option<Suggestion> = None;
+        let mut seen_words: HashSet<u32> = HashSet::new();
+
+        for candidate in edits(&prefix(&word), max_distance) {
+            let Some(ids) = self.deletes.get(&candidate) else {
+                continue;
+            };
+            for &id in ids {
+                if !seen_words.insert(id) {
+                    continue;
+                }
+                let (term, count) = &self.words[id as usize];
+                let term_chars: Vec<char> = term.chars().collect();
+                if term_chars.len().abs_diff(input.len()) > max_distance {
+                    continue;
+                }
+
+                let distance = edit_distance(&input, &term_chars);
+                if distance > max_distance {
+                    continue;
+                }
+                let better = best.as_ref().is_none_or(|b| {
+                    distance < b.distance || (distance == b.distance && *count > b.count)
+                });
+                if better {
+                    best = Some(Suggestion {
+                        term: term.clone(),
+                        distance,
+                        count: *count,
+                    });
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Correct a single query word, or None if it is known, too short to
+    /// correct safely, or has no close match. Short words get one edit,
+    /// longer ones two.
+    pub fn correct(&self, word: &str) -> Option<String> {
+        let length = word.chars().count();
+        if length < 3
+            || !word.chars().all(char::is_alphabetic)
+            || self.contains(&word.to_lowercase())
+        {
+            return None;
+        }
+
+        let max_distance = if length <= 4 { 1 } else { MAX_EDIT_DISTANCE };
+        self.lookup(word, max_distance)
+            .filter(|s| s.distance > 0)
+            .map(|s| s.term)
+    }
+
+    /// Correct each query term, or None if none needed correcting.
+    pub fn correct_terms(&self, terms: &[String]) -> Option<Vec<String>> {
+        let mut changed = false;
+        let corrected = terms
+            .iter()
+            .map(|term| match self.correct(term) {
+                Some(correction) => {
+                    changed = true;
+                    correction
+                }
+                None => term.clone(),
+            })
+            .collect();
+        changed.then_some(corrected)
+    }
+}
+
+/// Lowercased words of a text as they go into the dictionary.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphabetic())
+        .filter(|w| (2..=MAX_WORD_LENGTH).contains(&w.chars().count()))
+        .map(str::to_lowercase)
+}
+
+/// Add every word of `text` to a running count.
+pub fn count_words(text: &str, counts: &mut HashMap<String, u64>) {
+    for word in tokenize(text) {
+        *counts.entry(word).or_default() += 1;
+    }
+}
+
+fn prefix(word: &str) -> String {
+    word.chars().take(PREFIX_LENGTH).collect()
+}
+
+/// The word itself plus every string reachable by deleting up to
+/// `max_distance` characters.
+fn edits(word: &str, max_distance: usize) -> HashSet<String> {
+    let mut result = HashSet::from([word.to_string()]);
+    let mut frontier = vec![word.to_string()];
+
+    for _ in 0..max_distance {
+        let mut next = Vec::new();
+        for candidate in &frontier {
+            let chars: Vec<char> = candidate.chars().collect();
+            if chars.len() <= 1 {
+                continue;
+            }
+            for i in 0..chars.len() {
+                let delete: String = chars[..i].iter().chain(&chars[i + 1..]).collect();
+                if result.insert(delete.clone()) {
+                    next.push(delete);
+                }
+            }
+        }
+        frontier = next;
+    }
+
+    result
+}
+
+/// Optimal string alignment distance: Levenshtein plus adjacent
+/// transpositions ("teh" → "the" is one edit).
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speller() -> SymSpell {
+        let mut counts = HashMap::new();
+        count_words(
+            "Tokio is an asynchronous runtime. The tokio runtime schedules tasks. \
+             Asynchronous tasks and the scheduler. Kubernetes kubernetes rust rust",
+            &mut counts,
+        );
+        SymSpell::from_counts(counts)
+    }
+
+    #[test]
+    fn corrects_typos() {
+        let speller = speller();
+        assert_eq!(speller.correct("tokoi").as_deref(), Some("tokio"));
+        assert_eq!(
+            speller.correct("asynchronus").as_deref(),
+            Some("asynchronous")
+        );
+        assert_eq!(speller.correct("kubernetse").as_deref(), Some("kubernetes"));
+        assert_eq!(speller.correct("rsut").as_deref(), Some("rust"));
+        assert_eq!(speller.correct("runtime"), None);
+        assert_eq!(speller.correct("zzzzzz"), None);
+        assert_eq!(
+            speller.correct_terms(&["tokoi".into(), "std::fmt".into()]),
+            Some(vec!["tokio".to_string(), "std::fmt".to_string()])
+        );
+        assert_eq!(speller.correct_terms(&["rust".into()]), None);
+        // Seen once: not in the dictionary
+        assert!(!speller.contains("scheduler"));
+    }
+
+    #[test]
+    fn distance() {
+        let d = |a: &str, b: &str| {
+            edit_distance(
+                &a.chars().collect::<Vec<_>>(),
+                &b.chars().collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!(d("teh", "the"), 1);
+        assert_eq!(d("kitten", "sitting"), 3);
+        assert_eq!(d("", "abc"), 3);
+    }
+}
@@ -0,0 +1,153 @@
+use notice_core::types::{QuerySuggestion, SuggestionSource};
+use std::collections::HashMap;
+
+/// Titles longer than this make poor completions.
+const MAX_TITLE_LENGTH: usize = 80;
+
+/// Titles rank below any popular query (those need at least two users).
+const TITLE_WEIGHT: f64 = 1.0;
+
+/// Sorted prefix index over popular queries and document titles. Lookups
+/// are a binary search plus a scan of the matching range, cheap enough to
+/// run on every keystroke.
+#[derive(Debug, Default)]
+pub struct SuggestIndex {
+    /// Sorted by `key`
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    key: String,
+    text: String,
+    weight: f64,
+    source: SuggestionSource,
+}
+
+impl SuggestIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build from (query, use count) pairs and document titles. A title that
+    /// is also a popular query is kept once, as the query.
+    pub fn build(queries: Vec<(String, i64)>, titles: Vec<String>) -> Self {
+        let mut by_key: HashMap<String, Entry> = HashMap::new();
+
+        for (query, uses) in queries {
+            let key = normalize(&query);
+            if key.is_empty() {
+                continue;
+            }
+            let entry = by_key.entry(key.clone()).or_insert_with(|| Entry {
+                key,
+                text: query.trim().to_string(),
+                weight: 0.0,
+                source: SuggestionSource::Popular,
+            });
+            entry.weight += uses as f64;
+        }
+
+        for title in titles {
+            let title = title.trim();
+            let key = normalize(title);
+            if key.is_empty() || title.chars().count() > MAX_TITLE_LENGTH {
+                continue;
+            }
+            by_key.entry(key.clone()).or_insert_with(|| Entry {
+                key,
+                text: title.to_string(),
+                weight: TITLE_WEIGHT,
+                source: SuggestionSource::Title,
+            });
+        }
+
+        let mut entries: Vec<Entry> = by_key.into_values().collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        Self { entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Best completions of `prefix`: heaviest first, shorter first on ties.
+    pub fn complete(&self, prefix: &str, limit: usize) -> Vec<QuerySuggestion> {
+        let mut key = normalize(prefix);
+        if key.is_empty() {
+            return vec![];
+        }
+        // "rust " completes to "rust async", not "rustacean"
+        if prefix.ends_with(char::is_whitespace) {
+            key.push(' ');
+        }
+
+        let start = self.entries.partition_point(|e| e.key < key);
+        let mut matches: Vec<&Entry> = self.entries[start..]
+            .iter()
+            .take_while(|e| e.key.starts_with(&key))
+            .collect();
+        matches.sort_by(|a, b| {
+            b.weight
+                .total_cmp(&a.weight)
+                .then(a.key.len().cmp(&b.key.len()))
+        });
+
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|e| QuerySuggestion {
+                text: e.text.clone(),
+                source: e.source,
+            })
+            .collect()
+    }
+}
+
+/// Lowercase with runs of whitespace collapsed, so "Rust  Async" and
+/// "rust async" complete alike.
+pub fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_by_weight() {
+        let index = SuggestIndex::build(
+            vec![
+                ("rust async".into(), 12),
+                ("Rust  Async".into(), 3),
+                ("rust analyzer".into(), 40),
+                ("python".into(), 100),
+            ],
+            vec!["Rust async book".into(), "rust analyzer".into()],
+        );
+        assert_eq!(index.len(), 4);
+
+        let texts: Vec<String> = index
+            .complete("RUST a", 10)
+            .into_iter()
+            .map(|s| s.text)
+            .collect();
+        assert_eq!(
+            texts,
+            vec!["rust analyzer", "rust async", "Rust async book"]
+        );
+
+        let top = index.complete("rust async b", 1);
+        assert_eq!(top[0].source, SuggestionSource::Title);
+        assert!(index.complete("  ", 5).is_empty());
+        assert!(index.complete("zig", 5).is_empty());
+        assert!(index.complete("python ", 5).is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+use notice_db::vocabulary::NewSynonymGroup;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+use crate::SearchClient;
+
+/// Synonyms and stop words as the search backend takes them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Vocabulary {
+    /// Term → the terms it also matches
+    pub synonyms: HashMap<String, Vec<String>>,
+    pub stop_words: Vec<String>,
+}
+
+/// Import and export format.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VocabularyFile {
+    #[serde(default)]
+    pub synonyms: Vec<NewSynonymGroup>,
+    #[serde(default)]
+    pub stop_words: Vec<String>,
+}
+
+impl Vocabulary {
+    /// Expand groups into a term → synonyms map. A term in several groups
+    /// matches the terms of all of them.
+    pub fn new(groups: &[NewSynonymGroup], stop_words: Vec<String>) -> Self {
+        let mut synonyms: HashMap<String, Vec<String>> = HashMap::new();
+        for group in groups {
+            let sources = if group.one_way {
+                &group.terms[..group.terms.len().min(1)]
+            } else {
+                &group.terms[..]
+            };
+            for source in sources {
+                let targets = synonyms.entry(source.clone()).or_default();
+                for term in &group.terms {
+                    if term != source && !targets.contains(term) {
+                        targets.push(term.clone());
+                    }
+                }
+            }
+        }
+        synonyms.retain(|_, targets| !targets.is_empty());
+
+        Self {
+            synonyms,
+            stop_words,
+        }
+    }
+}
+
+/// Read the vocabulary from PostgreSQL.
+pub async fn load(db: &PgPool) -> Result<Vocabulary, notice_core::Error> {
+    let groups: Vec<NewSynonymGroup> = notice_db::vocabulary::list_synonyms(db)
+        .await?
+        .into_iter()
+        .map(NewSynonymGroup::from)
+        .collect();
+    let stop_words = notice_db::vocabulary::list_stop_words(db).await?;
+    Ok(Vocabulary::new(&groups, stop_words))
+}
+
+/// Push the stored vocabulary to the search backend.
+pub async fn push(db: &PgPool, search: &SearchClient) -> Result<(), notice_core::Error> {
+    let vocabulary = load(db).await?;
+    search.set_vocabulary(&vocabulary).await?;
+    tracing::info!(
+        synonyms = vocabulary.synonyms.len(),
+        stop_words = vocabulary.stop_words.len(),
+        "Search vocabulary pushed"
+    );
+    Ok(())
+}
+
+/// Lowercase, trim and dedupe a group's terms, keeping the first term
+/// first. At least two distinct terms must remain.
+pub fn normalize_group(group: NewSynonymGroup) -> Result<NewSynonymGroup, notice_core::Error> {
+    let mut terms: Vec<String> = Vec::with_capacity(group.terms.len());
+    for term in &group.terms {
+        let term = term
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        if !term.is_empty() && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    if terms.len() < 2 {
+        return Err(notice_core::Error::Validation(
+            "A synonym group needs at least two different terms".into(),
+        ));
+    }
+    Ok(NewSynonymGroup {
+        terms,
+        one_way: group.one_way,
+    })
+}
+
+/// Lowercase and trim a stop word, which must be a single word.
+pub fn normalize_stop_word(word: &str) -> Result<String, notice_core::Error> {
+    let word = word.trim().to_lowercase();
+    if word.is_empty() || word.contains(char::is_whitespace) {
+        return Err(notice_core::Error::Validation(format!(
+            "Invalid stop word '{}': use a single word",
+            word
+        )));
+    }
+    Ok(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(terms: &[&str], one_way: bool) -> NewSynonymGroup {
+        NewSynonymGroup {
+            terms: terms.iter().map(|t| t.to_string()).collect(),
+            one_way,
+        }
+    }
+
+    #[test]
+    fn expands_multi_way_and_one_way_groups() {
+        let vocabulary = Vocabulary::new(
+            &[
+                group(&["pg", "postgres", "postgresql"], false),
+                group(&["ml", "machine learning"], true),
+                group(&["db", "database"], true),
+                group(&["db", "pg"], true),
+            ],
+            vec![],
+        );
+
+        assert_eq!(vocabulary.synonyms["postgres"], ["pg", "postgresql"]);
+        assert_eq!(vocabulary.synonyms["ml"], ["machine learning"]);
+        assert_eq!(vocabulary.synonyms["db"], ["database", "pg"]);
+        assert!(!vocabulary.synonyms.contains_key("machine learning"));
+        assert!(!vocabulary.synonyms.contains_key("database"));
+    }
+
+    #[test]
+    fn normalizes_groups_and_stop_words() {
+        let normalized = normalize_group(group(&[" K8s ", "kubernetes", "k8s", ""], false));
+        assert_eq!(normalized.unwrap().terms, ["k8s", "kubernetes"]);
+        assert!(normalize_group(group(&["Rust", "rust"], false)).is_err());
+
+        assert_eq!(normalize_stop_word(" The ").unwrap(), "the");
+        assert!(normalize_stop_word("of the").is_err());
+        assert!(normalize_stop_word(" ").is_err());
+    }
+}
@@ -1,7 +1,9 @@
 mod error;
 mod middleware;
 mod routes;
+mod spelling;
 mod state;
+mod suggest;
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -32,20 +34,71 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // ── 5. Run migrations ──
     notice_db::run_migrations(&db_pool).await?;
 
-    // ── 6. Connect to Meilisearch ──
-    let search_client = notice_search::SearchClient::new(&config.meili_url, &config.meili_api_key)?;
+    if let Some(path) = &config.currency_rates_file {
+        match routes::admin::load_currency_rates(&db_pool, path).await {
+            Ok(count) => tracing::info!("Loaded {} currency rates from {}", count, path),
+            Err(e) => tracing::warn!("Could not load currency rates from {}: {}", path, e),
+        }
+    }
+
+    // ── 6. Connect to the search backend ──
+    let mut search_client = match config.search_backend.as_str() {
+        "meilisearch" => {
+            notice_search::SearchClient::meilisearch(&config.meili_url, &config.meili_api_key)?
+        }
+        "tantivy" => notice_search::SearchClient::tantivy(&config.tantivy_index_dir)?,
+        other => {
+            return Err(format!(
+                "Unknown SEARCH_BACKEND '{}': use 'meilisearch' or 'tantivy'",
+                other
+            )
+            .into());
+        }
+    };
+    if config.search.recency_half_life_days > 0.0 {
+        search_client = search_client.with_recency_decay(notice_search::recency::RecencyDecay {
+            half_life_days: config.search.recency_half_life_days,
+            weight: config.search.recency_weight,
+        });
+    }
     search_client.health().await?;
-    tracing::info!("Meilisearch is healthy");
+    tracing::info!("Search backend '{}' is healthy", search_client.backend_name());
 
-    // ── 7. Configure Meilisearch index ──
-    search_client.configure_index().await?;
-    search_client.configure_synonyms().await?;
+    // ── 7. Configure search indexes ──
+    search_client.configure().await?;
+    if let Err(e) = notice_search::vocabulary::push(&db_pool, &search_client).await {
+        tracing::warn!("Could not push synonyms and stop words: {}", e);
+    }
 
     match search_client.document_count().await {
-        Ok(count) => tracing::info!("Meilisearch documents index: {} documents", count),
-        Err(e) => tracing::warn!("Could not get Meilisearch document count: {}", e),
+        Ok(count) => tracing::info!("Search documents index: {} documents", count),
+        Err(e) => tracing::warn!("Could not get search document count: {}", e),
     }
 
+    // Indexes built with older settings are rebuilt and swapped in while
+    // the old ones keep serving searches
+    let index_job = if config.search.auto_reindex && search_client.needs_reindex().await? {
+        tracing::info!("Search index settings changed, starting a reindex");
+        Some(notice_search::index_job::IndexJob::start(
+            db_pool.clone(),
+            search_client.clone(),
+            notice_search::index_job::IndexJobKind::Reindex,
+        ))
+    } else {
+        None
+    };
+
+    // Document changes reach the index through the outbox
+    notice_search::outbox::spawn_dispatcher(
+        db_pool.clone(),
+        search_client.clone(),
+        notice_search::outbox::DispatcherOptions {
+            batch_size: config.search.outbox_batch_size,
+            poll_interval: std::time::Duration::from_millis(config.search.outbox_poll_ms),
+            max_attempts: config.search.outbox_max_attempts,
+        },
+    );
+
     // ── 8. Create Gemini client ──
     let gemini_client = notice_ai::GeminiClient::new(&config.gemini_api_key);
 
@@ -57,13 +110,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         ),
     }
 
+    let embedder = match config.embedding.provider.as_str() {
+        "gemini" => Some(notice_ai::embedding::Embedder::Gemini(
+            gemini_client.clone(),
+        )),
+        "hash" => Some(notice_ai::embedding::Embedder::Hash(
+            notice_ai::embedding::HashEmbedder::new(notice_ai::embedding::EMBEDDING_DIMENSIONS),
+        )),
+        "none" => None,
+        other => {
+            tracing::warn!(
+                "Unknown EMBEDDING_PROVIDER '{}', semantic search disabled",
+                other
+            );
+            None
+        }
+    };
+    match &embedder {
+        Some(embedder) => tracing::info!("Semantic search enabled ({})", embedder.model()),
+        None => tracing::info!("Semantic search is disabled"),
+    }
+
     // ── 9. Start background crawler ──
     let crawler_handle = if config.crawler.enabled {
         tracing::info!("Starting background crawler");
         let handle = notice_crawler::start_crawler(
             db_pool.clone(),
-            search_client.clone(),
             gemini_client.clone(),
+            embedder.clone(),
             config.crawler.clone(),
         );
         Some(handle)
@@ -73,11 +147,41 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     // ── 10. Build app state ──
+    let speller = Arc::new(std::sync::RwLock::new(
+        notice_search::spelling::SymSpell::new(),
+    ));
+    if config.search.spelling_rebuild_secs > 0 {
+        spelling::spawn_rebuild(
+            db_pool.clone(),
+            speller.clone(),
+            std::time::Duration::from_secs(config.search.spelling_rebuild_secs),
+        );
+    }
+    let suggestions = Arc::new(std::sync::RwLock::new(
+        notice_search::suggest::SuggestIndex::new(),
+    ));
+    if config.search.suggest_rebuild_secs > 0 {
+        suggest::spawn_rebuild(
+            db_pool.clone(),
+            suggestions.clone(),
+            std::time::Duration::from_secs(config.search.suggest_rebuild_secs),
+        );
+    }
+
     let app_state = state::AppState {
         db: db_pool,
         search: search_client,
         gemini: gemini_client,
         jwt_secret: config.jwt_secret.clone(),
+        currency_rates_file: config.currency_rates_file.clone(),
+        definitions_dir: config.definitions_dir.clone(),
+        classifier: config.classifier.clone(),
+        embedder,
+        embedding: config.embedding.clone(),
+        speller,
+        suggestions,
+        index_job: Arc::new(RwLock::new(index_job)),
+        crawler_config: config.crawler.clone(),
         crawler: Arc::new(RwLock::new(crawler_handle)),
     };
 
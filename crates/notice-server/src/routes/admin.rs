@@ -0,0 +1,226 @@
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use notice_db::definitions::NewDefinition;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Read a rates file from disk and upsert it into `currency_rates`.
+pub async fn load_currency_rates(db: &PgPool, path: &str) -> Result<usize, notice_core::Error> {
+    let raw = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| notice_core::Error::Config(format!("Cannot read {}: {}", path, e)))?;
+
+    let file: notice_db::currency_rates::RatesFile = serde_json::from_str(&raw).map_err(|e| {
+        notice_core::Error::Validation(format!("Invalid rates file {}: {}", path, e))
+    })?;
+
+    let source = match &file.date {
+        Some(date) => format!("{} ({})", path, date),
+        None => path.to_string(),
+    };
+
+    notice_db::currency_rates::import(db, &file, &source).await
+}
+
+/// GET /api/admin/currency-rates
+pub async fn list_currency_rates(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<notice_db::currency_rates::CurrencyRateRow>>, ApiError> {
+    let rates = notice_db::currency_rates::list(&state.db).await?;
+    Ok(Json(rates))
+}
+
+/// POST /api/admin/currency-rates/refresh
+///
+/// Re-reads the file configured via CURRENCY_RATES_FILE.
+pub async fn refresh_currency_rates(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let path = state.currency_rates_file.as_deref().ok_or_else(|| {
+        notice_core::Error::Validation("CURRENCY_RATES_FILE is not configured".into())
+    })?;
+
+    let imported = load_currency_rates(&state.db, path).await?;
+    tracing::info!("Refreshed {} currency rates from {}", imported, path);
+
+    Ok(Json(serde_json::json!({
+        "status": "refreshed",
+        "source": path,
+        "imported": imported,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportDefinitionsRequest {
+    /// File name of a wiktextract (kaikki.org) JSONL dump in DEFINITIONS_DIR.
+    pub file: String,
+    /// Language to keep, by wiktextract `lang_code`.
+    #[serde(default = "default_lang")]
+    pub lang: String,
+    /// Source tag stored with each row; re-imports replace the same source.
+    #[serde(default = "default_definitions_source")]
+    pub source: String,
+}
+
+fn default_lang() -> String {
+    "en".to_string()
+}
+
+fn default_definitions_source() -> String {
+    "wiktionary".to_string()
+}
+
+/// Rows per insert transaction during dictionary import.
+const DEFINITIONS_BATCH_SIZE: usize = 500;
+
+/// POST /api/admin/definitions/import
+///
+/// Imports a dump from the directory configured via DEFINITIONS_DIR.
+/// Dumps run to millions of lines, so the import runs in the background
+/// and reports progress in the logs.
+pub async fn import_definitions(
+    State(state): State<AppState>,
+    Json(req): Json<ImportDefinitionsRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    if req.source == notice_db::definitions::GENERATED_SOURCE {
+        return Err(notice_core::Error::Validation(format!(
+            "'{}' is reserved for AI-generated definitions",
+            req.source
+        ))
+        .into());
+    }
+
+    let dir = state.definitions_dir.as_deref().ok_or_else(|| {
+        notice_core::Error::Validation("DEFINITIONS_DIR is not configured".into())
+    })?;
+    // Fail fast on a bad name instead of inside the background task
+    let path = resolve_dump(dir, &req.file).await?;
+
+    let db = state.db.clone();
+    tokio::spawn(async move {
+        let path = path.to_string_lossy();
+        match load_definitions(&db, &path, &req.lang, &req.source).await {
+            Ok(count) => tracing::info!("Imported {} definitions from {}", count, path),
+            Err(e) => tracing::error!(error = %e, "Definitions import from {} failed", path),
+        }
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({
+            "status": "started",
+            "file": req.file,
+        })),
+    ))
+}
+
+/// Path of the dump `file` in `dir`. Only plain file names are accepted,
+/// and the result must still lie inside `dir` once symlinks are resolved.
+async fn resolve_dump(dir: &str, file: &str) -> Result<PathBuf, notice_core::Error> {
+    let not_found = || notice_core::Error::NotFound(format!("No dump named '{}'", file));
+
+    if Path::new(file).file_name() != Some(OsStr::new(file)) {
+        return Err(not_found());
+    }
+    let dir = tokio::fs::canonicalize(dir).await.map_err(|e| {
+        notice_core::Error::Config(format!("Cannot read DEFINITIONS_DIR {}: {}", dir, e))
+    })?;
+    let path = tokio::fs::canonicalize(dir.join(file))
+        .await
+        .map_err(|_| not_found())?;
+    let is_file = tokio::fs::metadata(&path).await.is_ok_and(|m| m.is_file());
+    if !path.starts_with(&dir) || !is_file {
+        return Err(not_found());
+    }
+    Ok(path)
+}
+
+/// Stream a wiktextract dump into the `definitions` table.
+/// Consecutive lines for the same word and part of speech (separate
+/// etymologies) are merged into one entry.
+async fn load_definitions(
+    db: &PgPool,
+    path: &str,
+    lang: &str,
+    source: &str,
+) -> Result<usize, notice_core::Error> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| notice_core::Error::Config(format!("Cannot open {}: {}", path, e)))?;
+    let mut lines = BufReader::new(file).lines();
+
+    let mut batch: Vec<NewDefinition> = Vec::with_capacity(DEFINITIONS_BATCH_SIZE);
+    let mut imported = 0;
+
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|e| notice_core::Error::Config(format!("Read error in {}: {}", path, e)))?
+    {
+        let Some(entry) = notice_db::definitions::parse_wiktextract_line(&line, lang) else {
+            continue;
+        };
+
+        if let Some(last) = batch.last_mut()
+            && last.same_entry(&entry)
+        {
+            last.merge(entry);
+            continue;
+        }
+
+        if batch.len() >= DEFINITIONS_BATCH_SIZE {
+            // Keep the last entry back so a following etymology can merge into it
+            let pending = batch.pop();
+            imported += notice_db::definitions::insert_batch(db, &batch, source).await?;
+            batch.clear();
+            batch.extend(pending);
+
+            if imported % 10_000 < DEFINITIONS_BATCH_SIZE {
+                tracing::info!("Definitions import: {} entries so far", imported);
+            }
+        }
+
+        batch.push(entry);
+    }
+
+    if !batch.is_empty() {
+        imported += notice_db::definitions::insert_batch(db, &batch, source).await?;
+    }
+
+    Ok(imported)
+}
+
+// ─── Index outbox ───
+
+/// GET /api/admin/index-outbox
+///
+/// Changes waiting for the search index, and the most recent ones that
+/// ran out of attempts.
+pub async fn index_outbox_status(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let stats = notice_db::index_outbox::stats(&state.db).await?;
+    let failed = notice_db::index_outbox::list_failed(&state.db, 50).await?;
+
+    Ok(Json(serde_json::json!({
+        "stats": stats,
+        "recent_failures": failed,
+    })))
+}
+
+/// POST /api/admin/index-outbox/retry
+pub async fn retry_index_outbox(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let requeued = notice_db::index_outbox::retry_failed(&state.db).await?;
+    tracing::info!("Requeued {} failed index outbox entries", requeued);
+
+    Ok(Json(serde_json::json!({ "requeued": requeued })))
+}
@@ -0,0 +1,135 @@
+use axum::Json;
+use axum::extract::{Path, State};
+use notice_classifier::navigate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::middleware::{AuthUser, OptionalAuthUser};
+use crate::state::AppState;
+
+/// A bang as the user sees it, after overrides.
+#[derive(Debug, Serialize)]
+pub struct BangEntry {
+    pub trigger: String,
+    pub name: String,
+    pub url_template: String,
+    /// "default", "global" or "user"
+    pub source: &'static str,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BangRequest {
+    pub name: Option<String>,
+    /// Target URL; `{query}` is replaced by the search terms.
+    pub url_template: String,
+}
+
+/// GET /api/bangs
+///
+/// Built-in bangs overlaid with global and (when logged in) personal ones.
+/// Global bangs are managed in the `bangs` table directly.
+pub async fn list_bangs(
+    State(state): State<AppState>,
+    auth: OptionalAuthUser,
+) -> Result<Json<Vec<BangEntry>>, ApiError> {
+    let mut entries: Vec<BangEntry> = navigate::DEFAULT_BANGS
+        .iter()
+        .map(|b| BangEntry {
+            trigger: b.trigger.to_string(),
+            name: b.name.to_string(),
+            url_template: b.url.to_string(),
+            source: "default",
+        })
+        .collect();
+
+    // Globals come before the user's own rows, so later rows win
+    for row in notice_db::bangs::list(&state.db, auth.user_id()).await? {
+        let entry = BangEntry {
+            source: if row.user_id.is_some() {
+                "user"
+            } else {
+                "global"
+            },
+            trigger: row.trigger,
+            name: row.name,
+            url_template: row.url_template,
+        };
+        match entries.iter_mut().find(|e| e.trigger == entry.trigger) {
+            Some(existing) => *existing = entry,
+            None => entries.push(entry),
+        }
+    }
+
+    entries.sort_by(|a, b| a.trigger.cmp(&b.trigger));
+    Ok(Json(entries))
+}
+
+/// PUT /api/bangs/{trigger}
+pub async fn put_user_bang(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(trigger): Path<String>,
+    Json(req): Json<BangRequest>,
+) -> Result<Json<notice_db::bangs::BangRow>, ApiError> {
+    save_bang(&state, auth.user_id, &trigger, req).await
+}
+
+/// DELETE /api/bangs/{trigger}
+pub async fn delete_user_bang(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(trigger): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    remove_bang(&state, auth.user_id, &trigger).await
+}
+
+async fn save_bang(
+    state: &AppState,
+    user_id: Uuid,
+    trigger: &str,
+    req: BangRequest,
+) -> Result<Json<notice_db::bangs::BangRow>, ApiError> {
+    let trigger = trigger.trim_start_matches('!').to_lowercase();
+    if !navigate::is_valid_trigger(&trigger) {
+        return Err(notice_core::Error::Validation(format!(
+            "Invalid bang trigger '{}': use up to 20 lowercase letters, digits, '-' or '_'",
+            trigger
+        ))
+        .into());
+    }
+
+    let url_template = req.url_template.trim();
+    if !url_template.starts_with("https://") && !url_template.starts_with("http://") {
+        return Err(
+            notice_core::Error::Validation("url_template must be an http(s) URL".into()).into(),
+        );
+    }
+
+    let name = req
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .unwrap_or(&trigger)
+        .to_string();
+
+    let row = notice_db::bangs::upsert(&state.db, user_id, &trigger, &name, url_template).await?;
+    Ok(Json(row))
+}
+
+async fn remove_bang(
+    state: &AppState,
+    user_id: Uuid,
+    trigger: &str,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let trigger = trigger.trim_start_matches('!').to_lowercase();
+    if !notice_db::bangs::delete(&state.db, user_id, &trigger).await? {
+        return Err(notice_core::Error::NotFound(format!("No bang '!{}'", trigger)).into());
+    }
+
+    Ok(Json(serde_json::json!({
+        "status": "deleted",
+        "trigger": trigger,
+    })))
+}
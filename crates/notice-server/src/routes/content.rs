@@ -1,42 +1,17 @@
 use axum::Json;
 use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use uuid::Uuid;
 
 use notice_core::types::{SubmitUrlRequest, SubmitUrlResponse};
-use notice_search::MeiliDocumentInput;
+use notice_search::index_job::{IndexJob, IndexJobKind, IndexJobSnapshot};
 
 use crate::error::ApiError;
 use crate::middleware::OptionalAuthUser;
 use crate::state::AppState;
 
-// ─── Helper: sync a document to Meilisearch ───
-
-fn doc_row_to_meili(doc: &notice_db::documents::DocumentRow) -> MeiliDocumentInput {
-    MeiliDocumentInput {
-        id: doc.id,
-        url: doc.url.clone(),
-        domain: doc.domain.clone(),
-        title: doc.title.clone(),
-        raw_content: doc.raw_content.clone(),
-        summary: doc.summary.clone(),
-        status: doc.status.clone(),
-        quality_score: doc.quality_score,
-    }
-}
-
-async fn sync_to_meilisearch(state: &AppState, doc: &notice_db::documents::DocumentRow) {
-    let meili_doc = doc_row_to_meili(doc);
-    match state.search.add_document(meili_doc).await {
-        Ok(()) => tracing::info!(doc_id = %doc.id, "Document synced to Meilisearch"),
-        Err(e) => tracing::error!(
-            doc_id = %doc.id,
-            error = %e,
-            "Failed to sync document to Meilisearch"
-        ),
-    }
-}
-
 // ─── Submit URL to crawl queue ───
 
 /// POST /api/submit
@@ -132,11 +107,20 @@ pub async fn crawl_url(
         page.title.as_deref(),
         &page.text_content,
         quality_score,
+        page.published_at,
     )
     .await?;
 
     tracing::info!(doc_id = %doc.id, "Document stored in PostgreSQL");
 
+    notice_db::recrawl::set_validators(
+        &state.db,
+        doc.id,
+        page.etag.as_deref(),
+        page.last_modified.as_deref(),
+    )
+    .await?;
+
     // Summarize
     let content_for_summary = notice_core::truncate_utf8(&page.text_content, 8000).to_string();
 
@@ -161,12 +145,79 @@ pub async fn crawl_url(
         }
     };
 
-    // Sync to Meilisearch
-    sync_to_meilisearch(&state, &doc).await;
+    // Split into passages (embedded for semantic search). The document and
+    // its passages reach the search index through the outbox.
+    if let Err(e) =
+        notice_crawler::passages::index_document(&state.db, state.embedder.as_ref(), &doc).await
+    {
+        tracing::warn!(doc_id = %doc.id, error = %e, "Failed to index passages");
+    }
 
     Ok(Json(doc))
 }
 
+// ─── Sitemaps ───
+
+/// Most pages one sitemap request may enqueue: one full sitemap.
+const MAX_SITEMAP_URLS: usize = 50_000;
+
+#[derive(Debug, Deserialize)]
+pub struct SitemapRequest {
+    /// A site, or the sitemap (`.xml` / `.xml.gz`) to start from
+    pub url: String,
+    /// Most pages to enqueue (default 10,000)
+    pub max_urls: Option<usize>,
+    /// Only pages whose `lastmod` is this recent or unknown
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// POST /api/sitemaps
+///
+/// Enqueue a site's pages from its sitemaps (listed in robots.txt, or
+/// `/sitemap.xml`), following sitemap indexes. Sitemap priority becomes
+/// crawl queue priority. Reading up to 50 sitemaps takes a while, so this
+/// runs in the background and reports the outcome in the logs.
+pub async fn submit_sitemap(
+    State(state): State<AppState>,
+    Json(body): Json<SitemapRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let url = url::Url::parse(body.url.trim())
+        .map_err(|e| notice_core::Error::Validation(format!("Invalid URL: {}", e)))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(notice_core::Error::Validation("URL must be http(s)".into()).into());
+    }
+
+    let config = &state.crawler_config;
+    let client = notice_crawler::scraper_engine::build_http_client(config)?;
+    let robots = notice_crawler::robots::RobotsChecker::new(
+        client.clone(),
+        &config.user_agent,
+        std::time::Duration::from_secs(config.robots_cache_secs),
+    );
+    let options = notice_crawler::sitemap::CollectOptions {
+        max_urls: body.max_urls.unwrap_or(10_000).min(MAX_SITEMAP_URLS),
+        since: body.since,
+    };
+
+    let db = state.db.clone();
+    let site = url.to_string();
+    tokio::spawn(async move {
+        if let Err(e) =
+            notice_crawler::sitemap::enqueue_from(&db, &client, &robots, &url, &options).await
+        {
+            tracing::error!(url = %url, error = %e, "Sitemap import failed");
+        }
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({
+            "status": "started",
+            "url": site,
+        })),
+    ))
+}
+
 // ─── Crawler status ───
 
 /// GET /api/crawler/status
@@ -174,6 +225,7 @@ pub async fn crawler_status(
     State(state): State<AppState>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     let queue_stats = notice_db::crawl_queue::stats(&state.db).await?;
+    let recrawl_stats = notice_db::recrawl::stats(&state.db).await?;
     let meili_count = state.search.document_count().await.unwrap_or(0);
 
     let crawler_guard = state.crawler.read().await;
@@ -182,6 +234,7 @@ pub async fn crawler_status(
     Ok(Json(serde_json::json!({
         "crawler": crawler_stats,
         "queue": queue_stats,
+        "recrawl": recrawl_stats,
         "meilisearch_documents": meili_count
     })))
 }
@@ -205,59 +258,148 @@ pub async fn crawler_stop(
 
 // ─── Resync ───
 
+#[derive(Debug, Deserialize)]
+pub struct ResyncParams {
+    /// Push every document, not just those changed since the last resync
+    pub full: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CheckParams {
+    /// Queue fixes for what the check finds in the index outbox
+    pub repair: Option<bool>,
+}
+
 /// POST /api/admin/resync
-pub async fn resync_to_meilisearch(
+///
+/// Push documents changed since the last resync (`?full=true`: all of
+/// them) to the search index, as a background job.
+pub async fn resync_index(
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, ApiError> {
-    tracing::info!("Starting full resync to Meilisearch");
+    Query(params): Query<ResyncParams>,
+) -> Result<Json<IndexJobSnapshot>, ApiError> {
+    let full = params.full.unwrap_or(false);
+    start_index_job(&state, IndexJobKind::Resync { full }).await
+}
 
-    let total = notice_db::documents::count(&state.db).await?;
+/// POST /api/admin/index/check
+///
+/// Compare PostgreSQL with the search index as a background job,
+/// reporting missing, stale and orphaned documents.
+pub async fn check_index(
+    State(state): State<AppState>,
+    Query(params): Query<CheckParams>,
+) -> Result<Json<IndexJobSnapshot>, ApiError> {
+    let repair = params.repair.unwrap_or(false);
+    start_index_job(&state, IndexJobKind::Check { repair }).await
+}
 
-    let batch_size: i64 = 100;
-    let mut offset: i64 = 0;
-    let mut synced: i64 = 0;
-    let mut failed: i64 = 0;
+/// POST /api/admin/reindex
+///
+/// Rebuild the search indexes from PostgreSQL with the current settings
+/// and swap them in, as a background job. Searches use the old indexes
+/// until the swap.
+pub async fn reindex(State(state): State<AppState>) -> Result<Json<IndexJobSnapshot>, ApiError> {
+    start_index_job(&state, IndexJobKind::Reindex).await
+}
 
-    loop {
-        let docs = notice_db::documents::list_full(&state.db, batch_size, offset).await?;
+/// GET /api/admin/index/job — progress of the last index job.
+pub async fn index_job_status(
+    State(state): State<AppState>,
+) -> Result<Json<Option<IndexJobSnapshot>>, ApiError> {
+    let job = state.index_job.read().await;
+    Ok(Json(job.as_ref().map(IndexJob::snapshot)))
+}
 
-        if docs.is_empty() {
-            break;
-        }
+/// One index job at a time: they would race on the watermark.
+async fn start_index_job(
+    state: &AppState,
+    kind: IndexJobKind,
+) -> Result<Json<IndexJobSnapshot>, ApiError> {
+    let mut slot = state.index_job.write().await;
+    if let Some(job) = slot.as_ref()
+        && job.is_running()
+    {
+        return Err(notice_core::Error::Conflict("An index job is already running".into()).into());
+    }
 
-        let meili_docs: Vec<MeiliDocumentInput> = docs.iter().map(doc_row_to_meili).collect();
+    let job = IndexJob::start(state.db.clone(), state.search.clone(), kind);
+    let snapshot = job.snapshot();
+    *slot = Some(job);
+    Ok(Json(snapshot))
+}
 
-        let count = meili_docs.len() as i64;
+// ─── Passages backfill ───
 
-        match state.search.add_documents(&meili_docs).await {
-            Ok(()) => {
-                synced += count;
-                tracing::info!(
-                    "Synced batch: {} documents (total: {}/{})",
-                    count,
-                    synced,
-                    total
-                );
-            }
-            Err(e) => {
-                failed += count;
-                tracing::error!(error = %e, "Failed to sync batch at offset {}", offset);
-            }
-        }
+/// POST /api/admin/passages/backfill
+///
+/// Split every document that has no passages yet (crawled before passages
+/// existed), then embed every passage with no vector from the current
+/// embedder. Embedding stops at the first failed batch so a rate limit
+/// doesn't spin.
+pub async fn backfill_passages(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    tracing::info!("Starting passages backfill");
 
-        offset += batch_size;
-        if count < batch_size {
+    let batch_size: i64 = 50;
+    let mut chunked: usize = 0;
+    let mut passages: usize = 0;
+    let mut after = None;
+
+    loop {
+        let docs = notice_db::chunks::list_unchunked(&state.db, after, batch_size).await?;
+        let Some(last) = docs.last() else {
             break;
+        };
+        after = Some(last.id);
+
+        // Embedded below, in larger batches
+        for doc in &docs {
+            match notice_crawler::passages::index_document(&state.db, None, doc).await {
+                Ok(count) => {
+                    chunked += 1;
+                    passages += count;
+                }
+                Err(e) => {
+                    tracing::error!(doc_id = %doc.id, error = %e, "Failed to index passages");
+                }
+            }
         }
+        tracing::info!("Chunked documents: {} ({} passages)", chunked, passages);
     }
 
-    let meili_count = state.search.document_count().await.unwrap_or(0);
+    let mut embedded: usize = 0;
+    let mut error = None;
+
+    if let Some(embedder) = &state.embedder {
+        loop {
+            let pending =
+                notice_db::chunks::list_unembedded(&state.db, embedder.model(), batch_size).await?;
+            if pending.is_empty() {
+                break;
+            }
+
+            match notice_crawler::passages::embed_chunks(&state.db, embedder, &pending).await {
+                Ok(count) => {
+                    embedded += count;
+                    tracing::info!("Embedded batch: {} passages (total: {})", count, embedded);
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Embeddings backfill stopped");
+                    error = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+    }
 
     Ok(Json(serde_json::json!({
-        "synced": synced,
-        "failed": failed,
-        "total_in_postgres": total,
-        "total_in_meilisearch": meili_count
+        "chunked_documents": chunked,
+        "passages": passages,
+        "model": state.embedder.as_ref().map(|e| e.model()),
+        "embedded": embedded,
+        "error": error,
     })))
 }
 
@@ -300,6 +442,22 @@ pub async fn get_document(
     Ok(Json(doc))
 }
 
+/// DELETE /api/admin/documents/{id}
+///
+/// Delete a document and its passages; the outbox removes it from the
+/// search index.
+pub async fn delete_document(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if !notice_db::documents::delete(&state.db, id).await? {
+        return Err(notice_core::Error::NotFound(format!("Document {} not found", id)).into());
+    }
+
+    tracing::info!(doc_id = %id, "Document deleted");
+    Ok(Json(serde_json::json!({ "deleted": id })))
+}
+
 /// GET /api/queue/stats
 pub async fn queue_stats(
     State(state): State<AppState>,
@@ -307,3 +465,15 @@ pub async fn queue_stats(
     let stats = notice_db::crawl_queue::stats(&state.db).await?;
     Ok(Json(stats))
 }
+
+/// GET /api/queue/{id}/tree — the crawl a queue entry belongs to, from its
+/// seed down through the links discovered on each page.
+pub async fn crawl_tree(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<notice_db::crawl_queue::CrawlTree>, ApiError> {
+    let tree = notice_db::crawl_queue::crawl_tree(&state.db, id)
+        .await?
+        .ok_or_else(|| notice_core::Error::NotFound(format!("Queue entry {} not found", id)))?;
+    Ok(Json(tree))
+}
@@ -8,13 +8,13 @@ use crate::state::AppState;
 pub async fn health_check(State(state): State<AppState>) -> Json<Value> {
     let db_ok = sqlx::query("SELECT 1").execute(&state.db).await.is_ok();
 
-    let meili_ok = state.search.health().await.is_ok();
+    let search_ok = state.search.health().await.is_ok();
 
-    let meili_docs = state.search.document_count().await.unwrap_or(0);
+    let search_docs = state.search.document_count().await.unwrap_or(0);
 
     let gemini_ok = state.gemini.test_connection().await.is_ok();
 
-    let status = if db_ok && meili_ok { "ok" } else { "degraded" };
+    let status = if db_ok && search_ok { "ok" } else { "degraded" };
 
     Json(json!({
         "status": status,
@@ -22,9 +22,10 @@ pub async fn health_check(State(state): State<AppState>) -> Json<Value> {
         "version": env!("CARGO_PKG_VERSION"),
         "dependencies": {
             "postgres": if db_ok { "up" } else { "down" },
-            "meilisearch": {
-                "status": if meili_ok { "up" } else { "down" },
-                "documents": meili_docs
+            "search": {
+                "backend": state.search.backend_name(),
+                "status": if search_ok { "up" } else { "down" },
+                "documents": search_docs
             },
             "gemini": if gemini_ok { "up" } else { "down" }
         }
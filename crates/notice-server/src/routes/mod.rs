@@ -1,11 +1,16 @@
+pub mod admin;
 pub mod auth;
+pub mod bangs;
 pub mod content;
 pub mod health;
+pub mod revisions;
 pub mod search;
+pub mod suggest;
+pub mod vocabulary;
 
 use axum::{
     Router,
-    routing::{get, post},
+    routing::{delete, get, post, put},
 };
 
 use crate::state::AppState;
@@ -19,17 +24,82 @@ pub fn create_router(state: AppState) -> Router {
         // ── Optional auth ──
         .route("/api/search", get(search::search))
         .route("/api/search/summary", get(search::search_summary))
+        .route("/api/suggest", get(suggest::suggest))
         .route("/api/submit", post(content::submit_url))
         .route("/api/crawl", post(content::crawl_url))
+        .route("/api/sitemaps", post(content::submit_sitemap))
         .route("/api/documents", get(content::list_documents))
         .route("/api/documents/{id}", get(content::get_document))
+        .route(
+            "/api/documents/{id}/revisions",
+            get(revisions::list_revisions),
+        )
+        .route(
+            "/api/documents/{id}/revisions/{revision_id}/diff",
+            get(revisions::diff_revision),
+        )
         .route("/api/queue/stats", get(content::queue_stats))
+        .route("/api/queue/{id}/tree", get(content::crawl_tree))
         .route("/api/crawler/status", get(content::crawler_status))
         .route("/api/crawler/stop", post(content::crawler_stop))
+        .route("/api/bangs", get(bangs::list_bangs))
         // ── Required auth ──
         .route("/api/auth/me", get(auth::me))
+        .route(
+            "/api/bangs/{trigger}",
+            put(bangs::put_user_bang).delete(bangs::delete_user_bang),
+        )
         // ── Admin ──
-        .route("/api/admin/resync", post(content::resync_to_meilisearch))
+        .route("/api/admin/resync", post(content::resync_index))
+        .route("/api/admin/index/check", post(content::check_index))
+        .route("/api/admin/reindex", post(content::reindex))
+        .route("/api/admin/index/job", get(content::index_job_status))
+        .route(
+            "/api/admin/passages/backfill",
+            post(content::backfill_passages),
+        )
+        .route(
+            "/api/admin/documents/{id}",
+            delete(content::delete_document),
+        )
+        .route("/api/admin/index-outbox", get(admin::index_outbox_status))
+        .route(
+            "/api/admin/index-outbox/retry",
+            post(admin::retry_index_outbox),
+        )
+        .route("/api/admin/currency-rates", get(admin::list_currency_rates))
+        .route(
+            "/api/admin/currency-rates/refresh",
+            post(admin::refresh_currency_rates),
+        )
+        .route(
+            "/api/admin/definitions/import",
+            post(admin::import_definitions),
+        )
+        .route(
+            "/api/admin/synonyms",
+            get(vocabulary::list_synonyms).post(vocabulary::create_synonym),
+        )
+        .route(
+            "/api/admin/synonyms/{id}",
+            put(vocabulary::update_synonym).delete(vocabulary::delete_synonym),
+        )
+        .route(
+            "/api/admin/stop-words",
+            get(vocabulary::list_stop_words).post(vocabulary::add_stop_words),
+        )
+        .route(
+            "/api/admin/stop-words/{word}",
+            delete(vocabulary::delete_stop_word),
+        )
+        .route(
+            "/api/admin/vocabulary/export",
+            get(vocabulary::export_vocabulary),
+        )
+        .route(
+            "/api/admin/vocabulary/import",
+            post(vocabulary::import_vocabulary),
+        )
         // State
         .with_state(state)
 }
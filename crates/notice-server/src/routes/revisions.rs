@@ -0,0 +1,194 @@
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use chrono::{DateTime, Utc};
+use notice_db::revisions::{RevisionContent, RevisionRow};
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Lines of unchanged text shown around each change.
+const DIFF_CONTEXT_LINES: usize = 2;
+/// How long a diff may take before a coarser one is returned.
+const DIFF_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+/// Longest diff text returned; longer diffs are cut at a line break.
+const MAX_DIFF_BYTES: usize = 256 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct DiffParams {
+    /// Revision to compare against (default: the version that replaced
+    /// this one)
+    pub to: Option<i64>,
+}
+
+/// One side of a diff.
+#[derive(Debug, Serialize)]
+pub struct VersionInfo {
+    /// None for the document's current version
+    pub revision_id: Option<i64>,
+    pub content_hash: String,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevisionDiff {
+    pub from: VersionInfo,
+    pub to: VersionInfo,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Line diff of the content in unified format
+    pub diff: String,
+    /// Whether `diff` was cut short at MAX_DIFF_BYTES
+    pub truncated: bool,
+}
+
+/// GET /api/documents/{id}/revisions — earlier versions, newest first.
+pub async fn list_revisions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<RevisionRow>>, ApiError> {
+    if notice_db::documents::get_by_id(&state.db, id)
+        .await?
+        .is_none()
+    {
+        return Err(notice_core::Error::NotFound(format!("Document {} not found", id)).into());
+    }
+    Ok(Json(notice_db::revisions::list(&state.db, id).await?))
+}
+
+/// GET /api/documents/{id}/revisions/{revision_id}/diff
+///
+/// What changed between a revision and the version that replaced it
+/// (`?to=` another revision to compare with that instead).
+pub async fn diff_revision(
+    State(state): State<AppState>,
+    Path((id, revision_id)): Path<(Uuid, i64)>,
+    Query(params): Query<DiffParams>,
+) -> Result<Json<RevisionDiff>, ApiError> {
+    let not_found =
+        |rev: i64| notice_core::Error::NotFound(format!("No revision {} of document {}", rev, id));
+
+    let from = notice_db::revisions::get(&state.db, id, revision_id)
+        .await?
+        .ok_or_else(|| not_found(revision_id))?;
+    let to = match params.to {
+        Some(to) => notice_db::revisions::get(&state.db, id, to).await?,
+        None => notice_db::revisions::get_next(&state.db, id, revision_id).await?,
+    }
+    .ok_or_else(|| not_found(params.to.unwrap_or(revision_id)))?;
+
+    // Diffing two large pages is CPU-bound
+    let diff = tokio::task::spawn_blocking(move || diff(from, to))
+        .await
+        .map_err(|e| notice_core::Error::Crawler(format!("Diff failed: {}", e)))?;
+    Ok(Json(diff))
+}
+
+fn diff(from: RevisionContent, to: RevisionContent) -> RevisionDiff {
+    let text_diff = TextDiff::configure()
+        .timeout(DIFF_TIMEOUT)
+        .diff_lines(&from.raw_content, &to.raw_content);
+
+    let (mut lines_added, mut lines_removed) = (0, 0);
+    for change in text_diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => lines_added += 1,
+            ChangeTag::Delete => lines_removed += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+
+    let label = |v: &RevisionContent| match v.id {
+        Some(id) => format!("revision {}", id),
+        None => "current".to_string(),
+    };
+    let mut diff = text_diff
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT_LINES)
+        .missing_newline_hint(false)
+        .header(&label(&from), &label(&to))
+        .to_string();
+    let truncated = diff.len() > MAX_DIFF_BYTES;
+    if truncated {
+        let end = diff.as_bytes()[..MAX_DIFF_BYTES]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1);
+        diff.truncate(end);
+    }
+
+    RevisionDiff {
+        from: from.into(),
+        to: to.into(),
+        lines_added,
+        lines_removed,
+        diff,
+        truncated,
+    }
+}
+
+impl From<RevisionContent> for VersionInfo {
+    fn from(v: RevisionContent) -> Self {
+        Self {
+            revision_id: v.id,
+            content_hash: v.content_hash,
+            title: v.title,
+            summary: v.summary,
+            fetched_at: v.fetched_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: Option<i64>, raw_content: &str) -> RevisionContent {
+        RevisionContent {
+            id,
+            content_hash: notice_db::recrawl::content_hash(raw_content),
+            title: None,
+            summary: None,
+            raw_content: raw_content.to_string(),
+            fetched_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn counts_changed_lines_and_labels_versions() {
+        let result = diff(
+            version(Some(7), "one\ntwo\nthree\n"),
+            version(None, "one\n2\nthree\nfour\n"),
+        );
+        assert_eq!(result.lines_added, 2);
+        assert_eq!(result.lines_removed, 1);
+        assert!(result.diff.starts_with("--- revision 7\n+++ current\n"));
+        assert!(result.diff.contains("-two\n+2\n"));
+        assert!(!result.truncated);
+        assert_eq!(result.from.revision_id, Some(7));
+        assert_eq!(result.to.revision_id, None);
+    }
+
+    #[test]
+    fn identical_versions_have_an_empty_diff() {
+        let result = diff(version(Some(1), "same\n"), version(Some(2), "same\n"));
+        assert_eq!((result.lines_added, result.lines_removed), (0, 0));
+        assert_eq!(result.diff, "");
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn long_diffs_are_cut_at_a_line_break() {
+        let line = "x".repeat(99);
+        let new = format!("{}\n", line).repeat(MAX_DIFF_BYTES / 50);
+        let result = diff(version(Some(1), ""), version(None, &new));
+        assert!(result.truncated);
+        assert!(result.diff.len() <= MAX_DIFF_BYTES);
+        assert!(result.diff.ends_with(&format!("+{}\n", line)));
+        assert_eq!(result.lines_added, MAX_DIFF_BYTES / 50);
+    }
+}
@@ -1,10 +1,22 @@
 use axum::Json;
 use axum::extract::{Query, State};
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
 use uuid::Uuid;
 
+use notice_ai::embedding::{EmbedKind, Embedder};
+use notice_calc::units::{ConversionQuery, Quantity};
 use notice_classifier::QueryIntent;
-use notice_core::types::{InstantAnswer, SearchResponse, SummaryResponse};
+use notice_classifier::navigate::Navigation;
+use notice_classifier::timer::{TimerSpec, TimerTarget};
+use notice_core::types::{
+    DefinitionEntry, DefinitionSense, ExchangeRate, InstantAnswer, ParsedQuery, SearchFacets,
+    SearchResponse, SearchResult, SummaryResponse, TimerKind, UnitRef,
+};
+use notice_search::SearchSort;
+use notice_search::chunk::PassageHit;
+use notice_search::hybrid::SearchMode;
 
 use crate::error::ApiError;
 use crate::middleware::OptionalAuthUser;
@@ -16,6 +28,19 @@ pub struct SearchParams {
     pub limit: Option<usize>,
     pub offset: Option<usize>,
     pub session_id: Option<String>,
+    /// Client's UTC offset in minutes east (e.g. 120 for UTC+2), used to
+    /// resolve wall-clock timers like "countdown to 17:00".
+    pub tz_offset: Option<i32>,
+    /// Filters, same as the `site:`, `status:`, `after:` and `before:`
+    /// operators (dates are YYYY-MM-DD)
+    pub domain: Option<String>,
+    pub status: Option<String>,
+    pub after: Option<String>,
+    pub before: Option<String>,
+    /// "relevance" (default) or "recent"
+    pub sort: Option<String>,
+    /// "hybrid" (default when embeddings are enabled), "semantic" or "keyword"
+    pub mode: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -23,11 +48,25 @@ pub struct SummaryParams {
     pub q: String,
 }
 
+/// Deepest result a search can page to. Hybrid and semantic search fetch
+/// everything up to the page, so this bounds the work per request.
+const MAX_SEARCH_OFFSET: usize = 1000;
+
+/// Paging, filters and ordering for a search that goes to the index.
+struct SearchOptions {
+    limit: usize,
+    offset: usize,
+    /// (operator, value) pairs, see `filter_params`
+    filters: Vec<(&'static str, String)>,
+    sort: SearchSort,
+    mode: SearchMode,
+}
+
 /// GET /api/search?q=your+query
 ///
 /// Pipeline (fast path — no AI blocking):
-/// 1. Classify intent (calculate / define / timer / search)
-/// 2. If instant answer → return immediately
+/// 1. Classify intent (calculate / convert / define / timer / navigate / search)
+/// 2. If instant answer or redirect → return immediately
 /// 3. If search → query Meilisearch directly
 /// 4. If results insufficient → trigger on-demand discovery (background), set flag
 /// 5. Record in search history
@@ -42,9 +81,26 @@ pub async fn search(
         return Err(notice_core::Error::Validation("Query cannot be empty".into()).into());
     }
 
-    let limit = params.limit.unwrap_or(20).min(100);
     let offset = params.offset.unwrap_or(0);
+    if offset > MAX_SEARCH_OFFSET {
+        return Err(notice_core::Error::Validation(format!(
+            "offset cannot exceed {}",
+            MAX_SEARCH_OFFSET
+        ))
+        .into());
+    }
+
     let user_id = auth.user_id();
+    let options = SearchOptions {
+        limit: params.limit.unwrap_or(20).min(100),
+        offset,
+        filters: filter_params(&params)?,
+        sort: match params.sort.as_deref() {
+            Some(sort) => sort.parse()?,
+            None => SearchSort::Relevance,
+        },
+        mode: search_mode(&state, params.mode.as_deref())?,
+    };
 
     tracing::info!(
         query = %query,
@@ -52,8 +108,19 @@ pub async fn search(
         "Search request"
     );
 
-    // Step 1: Classify intent
-    let intent = notice_classifier::classify(&query);
+    // Step 1: Classify intent (rules first, Gemini when they're unsure)
+    let classification = notice_classifier::classify(&query);
+    tracing::debug!(
+        query = %query,
+        intent = classification.intent.name(),
+        confidence = classification.confidence,
+        "Rule classification"
+    );
+    let intent = if classification.confidence < state.classifier.llm_threshold {
+        refine_intent(&state, &query, classification.intent).await
+    } else {
+        classification.intent
+    };
 
     let response = match intent {
         QueryIntent::Calculate(expr) => {
@@ -70,21 +137,26 @@ pub async fn search(
             SearchResponse {
                 query,
                 results: vec![],
-                total: 0,
-                instant_answer: Some(InstantAnswer {
-                    answer_type: "calculation".to_string(),
-                    value: evaluate_math(&expr),
-                }),
+                total: Some(0),
+                instant_answer: Some(calculation_answer(&expr)),
                 ai_answer: None,
                 discovery_triggered: false,
+                redirect: None,
+                parsed_query: None,
+                facets: None,
+                corrected_query: None,
+                did_you_mean: None,
+                degraded: false,
             }
         }
 
-        QueryIntent::Define(term) => {
+        QueryIntent::Convert(conversion) => {
+            let answer = conversion_answer(&state, &conversion).await;
+
             record_search(
                 &state,
                 &query,
-                "define",
+                "convert",
                 0,
                 params.session_id.as_deref(),
                 user_id,
@@ -94,17 +166,67 @@ pub async fn search(
             SearchResponse {
                 query,
                 results: vec![],
-                total: 0,
-                instant_answer: Some(InstantAnswer {
-                    answer_type: "definition".to_string(),
-                    value: format!("Definition lookup for '{}' — coming soon", term),
-                }),
+                total: Some(0),
+                instant_answer: Some(answer),
                 ai_answer: None,
                 discovery_triggered: false,
+                redirect: None,
+                parsed_query: None,
+                facets: None,
+                corrected_query: None,
+                did_you_mean: None,
+                degraded: false,
             }
         }
 
-        QueryIntent::Timer(command) => {
+        QueryIntent::Define(term) => match dictionary_answer(&state, &term).await {
+            Some(answer) => {
+                record_search(
+                    &state,
+                    &query,
+                    "define",
+                    0,
+                    params.session_id.as_deref(),
+                    user_id,
+                )
+                .await;
+
+                SearchResponse {
+                    query,
+                    results: vec![],
+                    total: Some(0),
+                    instant_answer: Some(answer),
+                    ai_answer: None,
+                    discovery_triggered: false,
+                    redirect: None,
+                    parsed_query: None,
+                    facets: None,
+                    corrected_query: None,
+                    did_you_mean: None,
+                    degraded: false,
+                }
+            }
+            // Not in the dictionary: search as usual, with a generated
+            // definition on top if one arrives in time
+            None => {
+                let (mut response, generated) = tokio::join!(
+                    run_search(
+                        &state,
+                        query,
+                        &options,
+                        params.session_id.as_deref(),
+                        user_id,
+                    ),
+                    generate_definition(&state, &term),
+                );
+                response.instant_answer = generated.and_then(|entry| {
+                    definition(notice_db::definitions::GENERATED_SOURCE, vec![entry])
+                });
+                response
+            }
+        },
+
+        QueryIntent::Timer(spec) => {
             record_search(
                 &state,
                 &query,
@@ -118,84 +240,521 @@ pub async fn search(
             SearchResponse {
                 query,
                 results: vec![],
-                total: 0,
-                instant_answer: Some(InstantAnswer {
-                    answer_type: "timer".to_string(),
-                    value: format!("Timer from '{}' — coming soon", command),
-                }),
+                total: Some(0),
+                instant_answer: Some(timer_answer(&spec, params.tz_offset.unwrap_or(0))),
                 ai_answer: None,
                 discovery_triggered: false,
+                redirect: None,
+                parsed_query: None,
+                facets: None,
+                corrected_query: None,
+                did_you_mean: None,
+                degraded: false,
             }
         }
 
-        QueryIntent::Search(search_query) => {
-            // Step 2: Search Meilisearch directly (fast)
-            let (results, total) = state
-                .search
-                .search(&search_query, limit, offset)
-                .await
-                .unwrap_or_else(|e| {
-                    tracing::error!(error = %e, "Meilisearch query failed");
-                    (vec![], 0)
-                });
+        QueryIntent::Navigate(navigation) => {
+            match navigation_target(&state, user_id, &navigation).await {
+                Some(url) => {
+                    record_search(
+                        &state,
+                        &query,
+                        "navigate",
+                        0,
+                        params.session_id.as_deref(),
+                        user_id,
+                    )
+                    .await;
 
-            let results_count = results.len() as i32;
-            
-            tracing::debug!(
-                query = %search_query,
-                results = results_count,
-                top_results = ?results.iter().take(5).map(|r| r.title.as_deref().unwrap_or("Untitled")).collect::<Vec<_>>(),
-                "Search results"
-            );
-
-            // Step 3: On-demand discovery (fire-and-forget, but signal the client)
-            let top_score = results.first().and_then(|r| r.score).unwrap_or(0.0);
-            let needs_discovery = results_count < 5 || top_score < 0.6;
-
-            if needs_discovery {
-                tracing::info!(
-                    query = %search_query, 
-                    count = results_count, 
-                    top_score = top_score, 
-                    "Insufficient or irrelevant results, triggering discovery"
-                );
-                let db = state.db.clone();
-                let discovery_query = search_query.clone();
-                
-                tokio::spawn(async move {
-                    let discovered_urls = notice_crawler::discovery::find_urls(&discovery_query).await;
-                    for url in discovered_urls {
-                        if let Err(e) = notice_db::crawl_queue::enqueue(&db, &url, 10, None).await {
-                            tracing::warn!(url = %url, error = %e, "Failed to enqueue discovered URL");
-                        }
+                    SearchResponse {
+                        query,
+                        results: vec![],
+                        total: Some(0),
+                        instant_answer: None,
+                        ai_answer: None,
+                        discovery_triggered: false,
+                        redirect: Some(url),
+                        parsed_query: None,
+                        facets: None,
+                        corrected_query: None,
+                        did_you_mean: None,
+                        degraded: false,
                     }
-                });
+                }
+                // Unknown bang: search for the query as typed
+                None => {
+                    run_search(
+                        &state,
+                        query,
+                        &options,
+                        params.session_id.as_deref(),
+                        user_id,
+                    )
+                    .await
+                }
             }
+        }
 
-            // Step 4: Record in search history
-            record_search(
+        QueryIntent::Search(search_query) => {
+            run_search(
                 &state,
-                &search_query,
-                "search",
-                results_count,
+                search_query,
+                &options,
                 params.session_id.as_deref(),
                 user_id,
             )
-            .await;
+            .await
+        }
+    };
 
-            // Return websites immediately — NO ai_answer here (decoupled)
-            SearchResponse {
-                query: search_query,
-                results,
-                total,
-                instant_answer: None,
-                ai_answer: None,
-                discovery_triggered: needs_discovery,
+    Ok(Json(response))
+}
+
+/// The search pipeline proper: Meilisearch, discovery, history.
+async fn run_search(
+    state: &AppState,
+    search_query: String,
+    options: &SearchOptions,
+    session_id: Option<&str>,
+    user_id: Option<Uuid>,
+) -> SearchResponse {
+    // Step 2: Search Meilisearch directly (fast)
+    let written = notice_search::query::parse(&search_query);
+    let correction = correct_spelling(state, &written);
+
+    let mut parsed = written;
+    for (operator, value) in &options.filters {
+        notice_search::query::add_filter(&mut parsed, operator, value);
+    }
+    let mut hits = search_index(state, &parsed, options).await;
+
+    // Spelling: re-search with the corrected query if the original found
+    // nothing, otherwise just offer it
+    let mut corrected_query = None;
+    let mut did_you_mean = None;
+    if let Some(mut corrected) = correction {
+        let suggestion = notice_search::query::render(&corrected);
+        if hits.results.is_empty() {
+            for (operator, value) in &options.filters {
+                notice_search::query::add_filter(&mut corrected, operator, value);
+            }
+            let retry = search_index(state, &corrected, options).await;
+            if retry.results.is_empty() {
+                did_you_mean = Some(suggestion);
+            } else {
+                tracing::debug!(
+                    query = %search_query,
+                    corrected = %suggestion,
+                    "No results, searched spelling correction"
+                );
+                hits = retry;
+                parsed = corrected;
+                corrected_query = Some(suggestion);
+            }
+        } else {
+            did_you_mean = Some(suggestion);
+        }
+    }
+
+    let IndexHits {
+        mut results,
+        total,
+        facets,
+        degraded,
+        top_score,
+    } = hits;
+    passage_snippets(state, &parsed, &mut results).await;
+
+    let results_count = results.len() as i32;
+    
+    tracing::debug!(
+        query = %search_query,
+        results = results_count,
+        top_results = ?results.iter().take(5).map(|r| r.title.as_deref().unwrap_or("Untitled")).collect::<Vec<_>>(),
+        "Search results"
+    );
+
+    // Step 3: On-demand discovery (fire-and-forget, but signal the client)
+    // Operators mean nothing to the discovery backends; a bare
+    // "site:docs.rs" has nothing to discover
+    let discovery_query = notice_search::query::text(&parsed);
+    let discovery_triggered =
+        needs_discovery(results.len(), top_score) && !discovery_query.is_empty();
+
+    if discovery_triggered {
+        tracing::info!(
+            query = %search_query, 
+            count = results_count, 
+            top_score = top_score, 
+            "Insufficient or irrelevant results, triggering discovery"
+        );
+        let db = state.db.clone();
+
+        tokio::spawn(async move {
+            let discovered_urls = notice_crawler::discovery::find_urls(&discovery_query).await;
+            for url in discovered_urls {
+                if let Err(e) = notice_db::crawl_queue::enqueue(&db, &url, 10, None).await {
+                    tracing::warn!(url = %url, error = %e, "Failed to enqueue discovered URL");
+                }
+            }
+        });
+    }
+
+    // Step 4: Record in search history
+    record_search(
+        state,
+        &search_query,
+        "search",
+        results_count,
+        session_id,
+        user_id,
+    )
+    .await;
+
+    // Return websites immediately — NO ai_answer here (decoupled)
+    SearchResponse {
+        query: search_query,
+        results,
+        total,
+        instant_answer: None,
+        ai_answer: None,
+        discovery_triggered,
+        redirect: None,
+        parsed_query: Some(parsed),
+        facets,
+        corrected_query,
+        did_you_mean,
+        degraded,
+    }
+}
+
+/// Replace each result's snippet with its best-matching passage, so a long
+/// page shows the section that matched instead of its opening lines.
+async fn passage_snippets(state: &AppState, parsed: &ParsedQuery, results: &mut [SearchResult]) {
+    let text = notice_search::query::text(parsed);
+    if text.is_empty() || results.is_empty() {
+        return;
+    }
+
+    let ids: Vec<Uuid> = results.iter().map(|r| r.id).collect();
+    let hits = match state
+        .search
+        .search_passages(&text, &ids, ids.len() * 3)
+        .await
+    {
+        Ok(hits) => hits,
+        Err(e) => {
+            tracing::warn!(error = %e, "Passage search failed, keeping document snippets");
+            return;
+        }
+    };
+
+    for (document_id, passages) in notice_search::chunk::group_by_document(hits, 1) {
+        if let Some(result) = results.iter_mut().find(|r| r.id == document_id)
+            && let Some(best) = passages.into_iter().next()
+        {
+            result.snippet = best.snippet;
+        }
+    }
+}
+
+/// One page of index results.
+struct IndexHits {
+    results: Vec<SearchResult>,
+    /// None when there is no meaningful count (semantic search)
+    total: Option<usize>,
+    facets: Option<SearchFacets>,
+    /// The search backend failed and these came from PostgreSQL full-text
+    /// search
+    degraded: bool,
+    /// Best keyword score (similarity for semantic search), for deciding
+    /// on discovery. Blended scores don't fit a fixed threshold: a
+    /// document only one side found scores at most the larger weight.
+    top_score: f64,
+}
+
+/// Fewer results than this trigger on-demand discovery.
+const DISCOVERY_MIN_RESULTS: usize = 5;
+/// A best score below this triggers on-demand discovery.
+const DISCOVERY_MIN_SCORE: f64 = 0.6;
+
+/// Whether the index looks short of good matches for a query.
+fn needs_discovery(results: usize, top_score: f64) -> bool {
+    results < DISCOVERY_MIN_RESULTS || top_score < DISCOVERY_MIN_SCORE
+}
+
+/// Score of the first result, 0 if none.
+fn first_score(results: &[SearchResult]) -> f64 {
+    results.first().and_then(|r| r.score).unwrap_or(0.0)
+}
+
+/// Keyword results from the search backend, vector results from pgvector,
+/// or a blend of both, depending on `options.mode`. Falls back to keyword
+/// search for `sort=recent` and when the query has no free text or cannot
+/// be embedded.
+async fn search_index(
+    state: &AppState,
+    parsed: &ParsedQuery,
+    options: &SearchOptions,
+) -> IndexHits {
+    let semantic = match (options.mode, &state.embedder) {
+        (SearchMode::Keyword, _) | (_, None) => None,
+        _ if options.sort == SearchSort::Recent => None,
+        (mode, Some(embedder)) => {
+            // Enough of both lists to blend before cutting out the page
+            let depth = options.offset.saturating_add(options.limit);
+            semantic_search(embedder, state, parsed, depth)
+                .await
+                .map(|hits| (mode, hits))
+        }
+    };
+
+    let Some((mode, hits)) = semantic else {
+        return keyword_search(state, parsed, options.limit, options.offset, options.sort).await;
+    };
+
+    if mode == SearchMode::Semantic {
+        // Nearest neighbours have no total to count
+        let top_score = first_score(&hits);
+        let page = hits.into_iter().skip(options.offset).take(options.limit);
+        return IndexHits {
+            results: page.collect(),
+            total: None,
+            facets: None,
+            degraded: false,
+            top_score,
+        };
+    }
+
+    let depth = options.offset.saturating_add(options.limit);
+    let keyword = keyword_search(state, parsed, depth, 0, options.sort).await;
+    blend_hits(keyword, hits, state.embedding.semantic_ratio, options)
+}
+
+/// Blend keyword hits (from offset 0) with semantic ones and cut out the
+/// requested page. `top_score` stays the keyword one.
+fn blend_hits(
+    keyword: IndexHits,
+    semantic: Vec<SearchResult>,
+    semantic_ratio: f64,
+    options: &SearchOptions,
+) -> IndexHits {
+    let blended = notice_search::hybrid::blend(keyword.results, semantic, semantic_ratio);
+    let total = keyword.total.map(|total| total.max(blended.len()));
+    let page = blended.into_iter().skip(options.offset).take(options.limit);
+    IndexHits {
+        results: page.collect(),
+        total,
+        facets: keyword.facets,
+        degraded: keyword.degraded,
+        top_score: keyword.top_score,
+    }
+}
+
+/// Search the backend; if it is down, fall back to PostgreSQL full-text
+/// search so users still get results.
+async fn keyword_search(
+    state: &AppState,
+    parsed: &ParsedQuery,
+    limit: usize,
+    offset: usize,
+    sort: SearchSort,
+) -> IndexHits {
+    let error = match state
+        .search
+        .search_parsed(parsed, limit, offset, sort)
+        .await
+    {
+        Ok(page) => {
+            return IndexHits {
+                top_score: first_score(&page.results),
+                results: page.results,
+                total: Some(page.total),
+                facets: Some(page.facets),
+                degraded: false,
+            };
+        }
+        Err(e) => e,
+    };
+    tracing::error!(error = %error, "Search backend query failed, using PostgreSQL full-text search");
+
+    let newest_first = sort == SearchSort::Recent;
+    match notice_db::full_text::search(&state.db, parsed, limit as i64, offset as i64, newest_first)
+        .await
+    {
+        Ok(hits) => IndexHits {
+            total: Some(hits.first().map(|h| h.total as usize).unwrap_or(0)),
+            top_score: hits.first().map(|h| h.score).unwrap_or(0.0),
+            results: hits
+                .into_iter()
+                .map(|hit| SearchResult {
+                    id: hit.id,
+                    url: hit.url,
+                    title: hit.title,
+                    snippet: hit.snippet,
+                    score: Some(hit.score),
+                })
+                .collect(),
+            facets: None,
+            degraded: true,
+        },
+        Err(e) => {
+            tracing::error!(error = %e, "Full-text fallback search failed");
+            IndexHits {
+                results: vec![],
+                total: Some(0),
+                facets: None,
+                degraded: true,
+                top_score: 0.0,
             }
         }
+    }
+}
+
+/// Nearest documents to the query text, or None if there is nothing to
+/// embed or embedding/lookup failed.
+async fn semantic_search(
+    embedder: &Embedder,
+    state: &AppState,
+    parsed: &ParsedQuery,
+    limit: usize,
+) -> Option<Vec<SearchResult>> {
+    let text = notice_search::query::text(parsed);
+    if text.is_empty() {
+        return None;
+    }
+
+    let vector = match embedder.embed_one(&text, EmbedKind::Query).await {
+        Ok(vector) => vector,
+        Err(e) => {
+            tracing::warn!(error = %e, "Query embedding failed, using keyword search");
+            return None;
+        }
     };
 
-    Ok(Json(response))
+    match notice_db::chunks::nearest(&state.db, embedder.model(), &vector, parsed, limit as i64)
+        .await
+    {
+        Ok(hits) => Some(
+            hits.into_iter()
+                .map(|hit| SearchResult {
+                    id: hit.id,
+                    url: hit.url,
+                    title: hit.title,
+                    snippet: hit.snippet,
+                    score: Some(hit.similarity),
+                })
+                .collect(),
+        ),
+        Err(e) => {
+            tracing::warn!(error = %e, "Vector search failed, using keyword search");
+            None
+        }
+    }
+}
+
+/// The requested search mode. Defaults to hybrid when an embedder is
+/// configured; asking for semantic search without one is an error.
+fn search_mode(
+    state: &AppState,
+    requested: Option<&str>,
+) -> Result<SearchMode, notice_core::Error> {
+    let mode = match requested {
+        Some(mode) => mode.parse()?,
+        None if state.embedder.is_some() => SearchMode::Hybrid,
+        None => SearchMode::Keyword,
+    };
+    if mode != SearchMode::Keyword && state.embedder.is_none() {
+        return Err(notice_core::Error::Validation(
+            "Semantic search is not enabled on this server".into(),
+        ));
+    }
+    Ok(mode)
+}
+
+/// The query with misspelled free-text terms corrected against the corpus
+/// dictionary, or None if every term looks fine.
+fn correct_spelling(state: &AppState, parsed: &ParsedQuery) -> Option<ParsedQuery> {
+    let speller = state.speller.read().ok()?;
+    let terms = speller.correct_terms(&parsed.terms)?;
+    Some(ParsedQuery {
+        terms,
+        ..parsed.clone()
+    })
+}
+
+/// Explicit filter parameters as (operator, value) pairs for
+/// `notice_search::query::add_filter`, rejecting invalid values up front.
+fn filter_params(params: &SearchParams) -> Result<Vec<(&'static str, String)>, notice_core::Error> {
+    let candidates = [
+        ("site", "domain", &params.domain),
+        ("status", "status", &params.status),
+        ("after", "after", &params.after),
+        ("before", "before", &params.before),
+    ];
+
+    let mut filters = Vec::new();
+    for (operator, param, value) in candidates {
+        let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) else {
+            continue;
+        };
+        let mut probe = notice_core::types::ParsedQuery::default();
+        if !notice_search::query::add_filter(&mut probe, operator, value) {
+            return Err(notice_core::Error::Validation(format!(
+                "Invalid {} filter: '{}'",
+                param, value
+            )));
+        }
+        filters.push((operator, value.to_string()));
+    }
+
+    Ok(filters)
+}
+
+/// Passages given to the model per document.
+const PASSAGES_PER_DOCUMENT: usize = 2;
+
+/// Answer context: the best passages of each result, labelled with their
+/// section. Results without a matching passage contribute their snippet.
+async fn summary_contexts(state: &AppState, query: &str, results: &[SearchResult]) -> Vec<String> {
+    let text = notice_search::query::text(&notice_search::query::parse(query));
+    let ids: Vec<Uuid> = results.iter().map(|r| r.id).collect();
+    let mut passages: HashMap<Uuid, Vec<PassageHit>> = match state
+        .search
+        .search_passages(&text, &ids, ids.len() * PASSAGES_PER_DOCUMENT * 2)
+        .await
+    {
+        Ok(hits) => notice_search::chunk::group_by_document(hits, PASSAGES_PER_DOCUMENT)
+            .into_iter()
+            .collect(),
+        Err(e) => {
+            tracing::warn!(error = %e, "Passage search failed, using snippets as context");
+            HashMap::new()
+        }
+    };
+
+    let mut contexts = Vec::new();
+    for r in results {
+        let title = r.title.as_deref().unwrap_or("Untitled");
+        match passages.remove(&r.id) {
+            Some(hits) => {
+                for hit in hits {
+                    contexts.push(format!(
+                        "Title: {}\nURL: {}\nSection: {}\nPassage: {}",
+                        title,
+                        r.url,
+                        hit.heading.as_deref().unwrap_or("(introduction)"),
+                        hit.content
+                    ));
+                }
+            }
+            None => contexts.push(format!(
+                "Title: {}\nURL: {}\nSnippet: {}",
+                title, r.url, r.snippet
+            )),
+        }
+    }
+    contexts
 }
 
 /// GET /api/search/summary?q=your+query
@@ -253,19 +812,8 @@ pub async fn search_summary(
         }));
     }
 
-    // Step 3: Build context and call Gemini
-    let contexts: Vec<String> = results
-        .iter()
-        .take(5)
-        .map(|r| {
-            format!(
-                "Title: {}\nURL: {}\nSnippet: {}",
-                r.title.as_deref().unwrap_or("Untitled"),
-                r.url,
-                r.snippet
-            )
-        })
-        .collect();
+    // Step 3: Build context from the best passages and call Gemini
+    let contexts = summary_contexts(&state, &query, &results).await;
 
     match state.gemini.answer_query(&query, &contexts).await {
         Ok(answer) => {
@@ -390,57 +938,398 @@ async fn record_search(
     }
 }
 
-// ─── Math evaluator ───
+// ─── Intent fallback ───
+
+/// Ask Gemini to classify a query the rules weren't sure about.
+/// Answers are cached per normalized query; any failure, timeout or
+/// unusable answer keeps the rule-based intent.
+async fn refine_intent(state: &AppState, query: &str, fallback: QueryIntent) -> QueryIntent {
+    let key = notice_classifier::normalize_query(query);
+
+    let cached = match notice_db::intent_cache::get(&state.db, &key).await {
+        Ok(cached) => cached,
+        Err(e) => {
+            tracing::warn!("Intent cache lookup failed: {}", e);
+            None
+        }
+    };
 
-fn evaluate_math(expr: &str) -> String {
-    let expr = expr.replace(' ', "");
-    if let Ok(n) = expr.parse::<f64>() {
-        return format_number(n);
+    let (intent, argument) = match cached {
+        Some(row) => (row.intent, row.argument),
+        None => {
+            let timeout = Duration::from_millis(state.classifier.llm_timeout_ms);
+            let guess =
+                match tokio::time::timeout(timeout, state.gemini.classify_intent(query)).await {
+                    Ok(Ok(guess)) => guess,
+                    Ok(Err(e)) => {
+                        tracing::warn!(query = %query, "LLM intent classification failed: {}", e);
+                        return fallback;
+                    }
+                    Err(_) => {
+                        tracing::warn!(query = %query, "LLM intent classification timed out");
+                        return fallback;
+                    }
+                };
+
+            if let Err(e) = notice_db::intent_cache::upsert(
+                &state.db,
+                &key,
+                &guess.intent,
+                guess.argument.as_deref(),
+            )
+            .await
+            {
+                tracing::warn!("Failed to cache intent classification: {}", e);
+            }
+
+            (guess.intent, guess.argument)
+        }
+    };
+
+    match notice_classifier::intent_from_llm(query, &intent, argument.as_deref()) {
+        Some(refined) => {
+            tracing::info!(query = %query, intent = refined.name(), "Intent refined by LLM");
+            refined
+        }
+        None => fallback,
     }
-    match eval_simple(&expr) {
-        Some(result) => format_number(result),
-        None => format!("Cannot evaluate: {}", expr),
+}
+
+// ─── Math evaluator ───
+
+/// Evaluate an expression and package the result (or the error, with its
+/// position) so the calculator widget can render it. notice-calc reports
+/// byte offsets; the widget gets character offsets.
+fn calculation_answer(expr: &str) -> InstantAnswer {
+    match notice_calc::evaluate(expr) {
+        Ok(calc) => InstantAnswer::Calculation {
+            expression: calc.expression,
+            result: calc.result,
+            formatted: calc.formatted,
+            exact: calc.exact,
+        },
+        Err(e) => InstantAnswer::Error {
+            widget: "calculation".to_string(),
+            message: e.to_string(),
+            input: Some(expr.to_string()),
+            position: e
+                .position()
+                .and_then(|p| expr.get(..p))
+                .map(|before| before.chars().count()),
+        },
     }
 }
 
-fn eval_simple(expr: &str) -> Option<f64> {
-    let mut total = 0.0;
-    for term in expr.split('+') {
-        let term = term.trim();
-        if term.contains('*') {
-            let parts: Vec<&str> = term.split('*').collect();
-            let mut product = 1.0;
-            for p in parts {
-                product *= p.trim().parse::<f64>().ok()?;
-            }
-            total += product;
-        } else if term.contains('/') {
-            let parts: Vec<&str> = term.splitn(2, '/').collect();
-            let a = parts[0].trim().parse::<f64>().ok()?;
-            let b = parts[1].trim().parse::<f64>().ok()?;
-            if b == 0.0 {
-                return None;
+// ─── Conversions ───
+
+/// Convert units locally, or currencies via the `currency_rates` table.
+async fn conversion_answer(state: &AppState, conversion: &ConversionQuery) -> InstantAnswer {
+    let conversion_error = |message: String| InstantAnswer::Error {
+        widget: "conversion".to_string(),
+        message,
+        input: None,
+        position: None,
+    };
+
+    let (result, rate) = match conversion.convert_units() {
+        Some(result) => (result, None),
+        None => {
+            match currency_rate(state, conversion.from.symbol(), conversion.to.symbol()).await {
+                Ok(Some(rate)) => (conversion.amount * rate.rate, Some(rate)),
+                Ok(None) => {
+                    return conversion_error(format!(
+                        "No exchange rate available for {} to {}",
+                        conversion.from.symbol(),
+                        conversion.to.symbol()
+                    ));
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Currency rate lookup failed");
+                    return conversion_error("Exchange rates are unavailable right now".into());
+                }
             }
-            total += a / b;
-        } else if term.contains('-') && !term.starts_with('-') {
-            let parts: Vec<&str> = term.splitn(2, '-').collect();
-            let a = parts[0].trim().parse::<f64>().ok()?;
-            let b = parts[1].trim().parse::<f64>().ok()?;
-            total += a - b;
-        } else {
-            total += term.parse::<f64>().ok()?;
         }
+    };
+
+    let result = match notice_calc::units::check_result(result) {
+        Ok(result) => result,
+        Err(e) => return conversion_error(e.to_string()),
+    };
+    let formatted = match conversion.to {
+        Quantity::Currency(_) => format!("{:.2}", result),
+        Quantity::Unit(_) => notice_calc::units::format_amount(result),
+    };
+
+    let unit_ref = |q: &Quantity| UnitRef {
+        symbol: q.symbol().to_string(),
+        name: q.name().to_string(),
+    };
+
+    InstantAnswer::Conversion {
+        dimension: conversion.from.dimension().as_str().to_string(),
+        amount: conversion.amount,
+        from: unit_ref(&conversion.from),
+        to: unit_ref(&conversion.to),
+        result,
+        formatted,
+        rate,
     }
-    Some(total)
 }
 
-fn format_number(n: f64) -> String {
-    if n == n.floor() {
-        format!("{}", n as i64)
-    } else {
-        format!("{:.6}", n)
-            .trim_end_matches('0')
-            .trim_end_matches('.')
-            .to_string()
+/// Rate to multiply an amount in `from` by to get `to`, dated by the older
+/// of the two stored rates. None if either rate is missing.
+async fn currency_rate(
+    state: &AppState,
+    from: &str,
+    to: &str,
+) -> Result<Option<ExchangeRate>, notice_core::Error> {
+    let from_row = notice_db::currency_rates::get(&state.db, from).await?;
+    let to_row = notice_db::currency_rates::get(&state.db, to).await?;
+
+    Ok(match (from_row, to_row) {
+        (Some(f), Some(t)) => Some(ExchangeRate {
+            rate: t.per_usd / f.per_usd,
+            updated_at: f.updated_at.min(t.updated_at),
+        }),
+        _ => None,
+    })
+}
+
+// ─── Definitions ───
+
+/// How long the AI fallback may hold up a definition query. It runs
+/// alongside the search, so this is extra latency only when it's slower.
+const GENERATED_DEFINITION_TIMEOUT: Duration = Duration::from_millis(1500);
+/// How long a generated definition is served from the dictionary table
+/// before it is generated again.
+const GENERATED_DEFINITION_TTL_SECS: i64 = 7 * 24 * 3600;
+
+/// Look a term up in the offline dictionary (including cached generated
+/// definitions). None if it has no entry.
+async fn dictionary_answer(state: &AppState, term: &str) -> Option<InstantAnswer> {
+    let rows = notice_db::definitions::lookup(&state.db, term)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Definition lookup failed");
+            vec![]
+        });
+
+    let source = rows.first()?.source.clone();
+    definition(
+        &source,
+        rows.into_iter()
+            .map(notice_db::definitions::NewDefinition::from)
+            .collect(),
+    )
+}
+
+/// Package dictionary entries as an instant answer.
+fn definition(
+    source: &str,
+    entries: Vec<notice_db::definitions::NewDefinition>,
+) -> Option<InstantAnswer> {
+    let term = entries.first()?.term.clone();
+
+    Some(InstantAnswer::Definition {
+        term,
+        source: source.to_string(),
+        entries: entries
+            .into_iter()
+            .map(|e| DefinitionEntry {
+                part_of_speech: e.part_of_speech,
+                pronunciation: e.pronunciation,
+                senses: e
+                    .senses
+                    .into_iter()
+                    .map(|s| DefinitionSense {
+                        gloss: s.gloss,
+                        examples: s.examples,
+                    })
+                    .collect(),
+                synonyms: e.synonyms,
+            })
+            .collect(),
+    })
+}
+
+/// Ask Gemini to define a term from the top search results, and cache the
+/// result for GENERATED_DEFINITION_TTL_SECS so the next lookups hit the
+/// dictionary table.
+async fn generate_definition(
+    state: &AppState,
+    term: &str,
+) -> Option<notice_db::definitions::NewDefinition> {
+    let (results, _) = state.search.search(term, 5, 0).await.ok()?;
+    if results.is_empty() {
+        return None;
+    }
+
+    let contexts: Vec<String> = results
+        .iter()
+        .map(|r| {
+            format!(
+                "Title: {}\nURL: {}\nSnippet: {}",
+                r.title.as_deref().unwrap_or("Untitled"),
+                r.url,
+                r.snippet
+            )
+        })
+        .collect();
+
+    let raw = match tokio::time::timeout(
+        GENERATED_DEFINITION_TIMEOUT,
+        state.gemini.define(term, &contexts),
+    )
+    .await
+    {
+        Ok(Ok(raw)) => raw,
+        Ok(Err(e)) => {
+            tracing::warn!(term = %term, error = %e, "Generated definition failed");
+            return None;
+        }
+        Err(_) => {
+            tracing::warn!(term = %term, "Generated definition timed out");
+            return None;
+        }
+    };
+
+    let json: serde_json::Value = serde_json::from_str(strip_code_fences(&raw)).ok()?;
+    let gloss = json.get("definition")?.as_str()?.trim();
+    if gloss.is_empty() {
+        return None;
+    }
+
+    let entry = notice_db::definitions::NewDefinition {
+        term: term.to_string(),
+        part_of_speech: json
+            .get("part_of_speech")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string(),
+        pronunciation: None,
+        senses: vec![notice_db::definitions::Sense {
+            gloss: gloss.to_string(),
+            examples: vec![],
+        }],
+        synonyms: json
+            .get("synonyms")
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|s| s.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+
+    if let Err(e) =
+        notice_db::definitions::insert_generated(&state.db, &entry, GENERATED_DEFINITION_TTL_SECS)
+            .await
+    {
+        tracing::warn!(error = %e, "Failed to store generated definition");
+    }
+
+    Some(entry)
+}
+
+// ─── Timers ───
+
+/// Pin a parsed timer to an absolute deadline so the widget can count down
+/// without re-parsing the query.
+fn timer_answer(spec: &TimerSpec, tz_offset: i32) -> InstantAnswer {
+    let timer = spec.resolve(chrono::Utc::now(), tz_offset);
+    let duration = notice_classifier::timer::format_duration(timer.seconds);
+
+    let (kind, display) = match spec.target {
+        TimerTarget::Duration(_) => (TimerKind::Duration, duration),
+        TimerTarget::Until(time) => (
+            TimerKind::Until,
+            format!("{} (in {})", time.format("%H:%M"), duration),
+        ),
+    };
+
+    InstantAnswer::Timer {
+        kind,
+        seconds: timer.seconds,
+        target: timer.target,
+        label: timer.label,
+        display,
+    }
+}
+
+// ─── Navigation ───
+
+/// Where to send a navigational query. Bangs resolve to the user's own
+/// definition, then the global one, then the built-in default; None for
+/// a trigger nobody defined.
+async fn navigation_target(
+    state: &AppState,
+    user_id: Option<Uuid>,
+    navigation: &Navigation,
+) -> Option<String> {
+    let (trigger, terms) = match navigation {
+        Navigation::Url(url) => return Some(url.clone()),
+        Navigation::Bang { trigger, terms } => (trigger, terms),
+    };
+
+    let template = match notice_db::bangs::resolve(&state.db, user_id, trigger).await {
+        Ok(Some(bang)) => bang.url_template,
+        Ok(None) => notice_classifier::navigate::default_bang(trigger)?
+            .url
+            .to_string(),
+        Err(e) => {
+            tracing::warn!("Bang lookup failed for !{}: {}", trigger, e);
+            notice_classifier::navigate::default_bang(trigger)?
+                .url
+                .to_string()
+        }
+    };
+
+    Some(notice_classifier::navigate::expand_template(
+        &template, terms,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: u128, score: f64) -> SearchResult {
+        SearchResult {
+            id: Uuid::from_u128(id),
+            url: format!("https://example.com/{}", id),
+            title: None,
+            snippet: String::new(),
+            score: Some(score),
+        }
+    }
+
+    #[test]
+    fn discovery_uses_the_keyword_score_in_hybrid_mode() {
+        // Strong keyword matches the semantic side did not return: each
+        // blends to at most 0.45, but the index clearly has good results
+        let keyword = IndexHits {
+            top_score: 0.9,
+            results: (1..=6).map(|id| result(id, 0.9)).collect(),
+            total: Some(6),
+            facets: None,
+            degraded: false,
+        };
+        let semantic = vec![result(7, 0.8)];
+        let options = SearchOptions {
+            limit: 10,
+            offset: 0,
+            filters: vec![],
+            sort: SearchSort::Relevance,
+            mode: SearchMode::Hybrid,
+        };
+
+        let hits = blend_hits(keyword, semantic, 0.5, &options);
+        assert!(first_score(&hits.results) < DISCOVERY_MIN_SCORE);
+        assert_eq!(hits.top_score, 0.9);
+        assert!(!needs_discovery(hits.results.len(), hits.top_score));
+
+        assert!(needs_discovery(3, 0.9));
+        assert!(needs_discovery(10, 0.5));
     }
 }
@@ -0,0 +1,76 @@
+use axum::Json;
+use axum::extract::{Query, State};
+use notice_core::types::{QuerySuggestion, SuggestResponse, SuggestionSource};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::OptionalAuthUser;
+use crate::state::AppState;
+
+const DEFAULT_LIMIT: usize = 8;
+const MAX_LIMIT: usize = 20;
+
+/// Of the limit, how many slots the user's own history may take.
+const MAX_HISTORY: usize = 3;
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestParams {
+    pub q: String,
+    pub limit: Option<usize>,
+}
+
+/// GET /api/suggest?q=prefix
+///
+/// Completions for the search box: the user's own recent searches first
+/// (when logged in), then popular searches and document titles from the
+/// in-memory prefix index.
+pub async fn suggest(
+    State(state): State<AppState>,
+    auth: OptionalAuthUser,
+    Query(params): Query<SuggestParams>,
+) -> Result<Json<SuggestResponse>, ApiError> {
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let prefix = params.q.trim_start();
+    if prefix.trim().is_empty() {
+        return Ok(Json(SuggestResponse {
+            query: params.q,
+            suggestions: vec![],
+        }));
+    }
+
+    let mut suggestions: Vec<QuerySuggestion> = Vec::new();
+    if let Some(user_id) = auth.user_id() {
+        let history = notice_db::search_history::user_prefix(
+            &state.db,
+            user_id,
+            prefix,
+            MAX_HISTORY.min(limit) as i64,
+        )
+        .await?;
+        suggestions.extend(history.into_iter().map(|text| QuerySuggestion {
+            text,
+            source: SuggestionSource::History,
+        }));
+    }
+
+    let completions = match state.suggestions.read() {
+        Ok(index) => index.complete(prefix, limit),
+        Err(_) => vec![],
+    };
+    for completion in completions {
+        if suggestions.len() >= limit {
+            break;
+        }
+        let duplicate = suggestions
+            .iter()
+            .any(|s| s.text.eq_ignore_ascii_case(&completion.text));
+        if !duplicate {
+            suggestions.push(completion);
+        }
+    }
+
+    Ok(Json(SuggestResponse {
+        query: params.q,
+        suggestions,
+    }))
+}
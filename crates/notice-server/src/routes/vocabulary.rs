@@ -0,0 +1,175 @@
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::IntoResponse;
+use notice_db::vocabulary::{NewSynonymGroup, SynonymGroupRow};
+use notice_search::vocabulary::{self, VocabularyFile};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct StopWordsRequest {
+    pub words: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportParams {
+    /// Drop the stored vocabulary instead of adding to it
+    pub replace: Option<bool>,
+}
+
+// ─── Synonyms ───
+
+/// GET /api/admin/synonyms
+pub async fn list_synonyms(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<SynonymGroupRow>>, ApiError> {
+    Ok(Json(notice_db::vocabulary::list_synonyms(&state.db).await?))
+}
+
+/// POST /api/admin/synonyms
+///
+/// Add a group of interchangeable terms; with `one_way`, only the first
+/// term expands to the others.
+pub async fn create_synonym(
+    State(state): State<AppState>,
+    Json(req): Json<NewSynonymGroup>,
+) -> Result<Json<SynonymGroupRow>, ApiError> {
+    let group = vocabulary::normalize_group(req)?;
+    let row = notice_db::vocabulary::create_synonym(&state.db, &group).await?;
+    vocabulary::push(&state.db, &state.search).await?;
+    Ok(Json(row))
+}
+
+/// PUT /api/admin/synonyms/{id}
+pub async fn update_synonym(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(req): Json<NewSynonymGroup>,
+) -> Result<Json<SynonymGroupRow>, ApiError> {
+    let group = vocabulary::normalize_group(req)?;
+    let row = notice_db::vocabulary::update_synonym(&state.db, id, &group)
+        .await?
+        .ok_or_else(|| notice_core::Error::NotFound(format!("No synonym group {}", id)))?;
+    vocabulary::push(&state.db, &state.search).await?;
+    Ok(Json(row))
+}
+
+/// DELETE /api/admin/synonyms/{id}
+pub async fn delete_synonym(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if !notice_db::vocabulary::delete_synonym(&state.db, id).await? {
+        return Err(notice_core::Error::NotFound(format!("No synonym group {}", id)).into());
+    }
+    vocabulary::push(&state.db, &state.search).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "deleted",
+        "id": id,
+    })))
+}
+
+// ─── Stop words ───
+
+/// GET /api/admin/stop-words
+pub async fn list_stop_words(State(state): State<AppState>) -> Result<Json<Vec<String>>, ApiError> {
+    Ok(Json(
+        notice_db::vocabulary::list_stop_words(&state.db).await?,
+    ))
+}
+
+/// POST /api/admin/stop-words
+pub async fn add_stop_words(
+    State(state): State<AppState>,
+    Json(req): Json<StopWordsRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let words = req
+        .words
+        .iter()
+        .map(|w| vocabulary::normalize_stop_word(w))
+        .collect::<Result<Vec<_>, _>>()?;
+    let added = notice_db::vocabulary::add_stop_words(&state.db, &words).await?;
+    vocabulary::push(&state.db, &state.search).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "added",
+        "added": added,
+    })))
+}
+
+/// DELETE /api/admin/stop-words/{word}
+pub async fn delete_stop_word(
+    State(state): State<AppState>,
+    Path(word): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let word = word.trim().to_lowercase();
+    if !notice_db::vocabulary::delete_stop_word(&state.db, &word).await? {
+        return Err(notice_core::Error::NotFound(format!("No stop word '{}'", word)).into());
+    }
+    vocabulary::push(&state.db, &state.search).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "deleted",
+        "word": word,
+    })))
+}
+
+// ─── Import / export ───
+
+/// GET /api/admin/vocabulary/export — synonyms and stop words as a JSON
+/// file that `import` takes back.
+pub async fn export_vocabulary(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let file = VocabularyFile {
+        synonyms: notice_db::vocabulary::list_synonyms(&state.db)
+            .await?
+            .into_iter()
+            .map(NewSynonymGroup::from)
+            .collect(),
+        stop_words: notice_db::vocabulary::list_stop_words(&state.db).await?,
+    };
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"vocabulary.json\"",
+        )],
+        Json(file),
+    ))
+}
+
+/// POST /api/admin/vocabulary/import
+///
+/// Add the file's synonyms and stop words (`?replace=true`: replace the
+/// stored ones). Nothing is stored if any entry is invalid.
+pub async fn import_vocabulary(
+    State(state): State<AppState>,
+    Query(params): Query<ImportParams>,
+    Json(file): Json<VocabularyFile>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let synonyms = file
+        .synonyms
+        .into_iter()
+        .map(vocabulary::normalize_group)
+        .collect::<Result<Vec<_>, _>>()?;
+    let stop_words = file
+        .stop_words
+        .iter()
+        .map(|w| vocabulary::normalize_stop_word(w))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let replace = params.replace.unwrap_or(false);
+    notice_db::vocabulary::import(&state.db, &synonyms, &stop_words, replace).await?;
+    vocabulary::push(&state.db, &state.search).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "imported",
+        "synonyms": synonyms.len(),
+        "stop_words": stop_words.len(),
+        "replaced": replace,
+    })))
+}
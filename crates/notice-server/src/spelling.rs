@@ -0,0 +1,50 @@
+use notice_search::spelling::{self, SymSpell};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+const BATCH_SIZE: i64 = 200;
+
+/// Count the words of every document's title and text and build a fresh
+/// dictionary from them.
+pub async fn build_dictionary(pool: &PgPool) -> Result<SymSpell, notice_core::Error> {
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    let mut offset = 0;
+
+    loop {
+        let rows = notice_db::documents::list_text(pool, BATCH_SIZE, offset).await?;
+        if rows.is_empty() {
+            break;
+        }
+        for (title, raw_content) in &rows {
+            if let Some(title) = title {
+                spelling::count_words(title, &mut counts);
+            }
+            spelling::count_words(raw_content, &mut counts);
+        }
+        offset += rows.len() as i64;
+    }
+
+    Ok(SymSpell::from_counts(counts))
+}
+
+/// Rebuild the dictionary now and then every `interval`, swapping it in
+/// once built so searches never wait on a rebuild.
+pub fn spawn_rebuild(pool: PgPool, speller: Arc<RwLock<SymSpell>>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match build_dictionary(&pool).await {
+                Ok(dictionary) => {
+                    tracing::info!("Spelling dictionary rebuilt: {} words", dictionary.len());
+                    if let Ok(mut guard) = speller.write() {
+                        *guard = dictionary;
+                    }
+                }
+                Err(e) => tracing::warn!("Spelling dictionary rebuild failed: {}", e),
+            }
+        }
+    });
+}
@@ -1,6 +1,11 @@
 use notice_ai::GeminiClient;
+use notice_ai::embedding::Embedder;
+use notice_core::config::{ClassifierConfig, CrawlerConfig, EmbeddingConfig};
 use notice_crawler::CrawlerHandle;
 use notice_search::SearchClient;
+use notice_search::index_job::IndexJob;
+use notice_search::spelling::SymSpell;
+use notice_search::suggest::SuggestIndex;
 use sqlx::PgPool;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -12,6 +17,22 @@ pub struct AppState {
     pub search: SearchClient,
     pub gemini: GeminiClient,
     pub jwt_secret: String,
+    /// Exchange-rate file re-read by the admin refresh endpoint.
+    pub currency_rates_file: Option<String>,
+    /// Directory the definitions import reads dumps from.
+    pub definitions_dir: Option<String>,
+    pub classifier: ClassifierConfig,
+    /// None when semantic search is disabled (`EMBEDDING_PROVIDER=none`).
+    pub embedder: Option<Embedder>,
+    pub embedding: EmbeddingConfig,
+    /// Corpus spelling dictionary, rebuilt in the background.
+    pub speller: Arc<std::sync::RwLock<SymSpell>>,
+    /// Autocomplete index, rebuilt in the background.
+    pub suggestions: Arc<std::sync::RwLock<SuggestIndex>>,
+    /// Last index resync or consistency check, if any ran.
+    pub index_job: Arc<RwLock<Option<IndexJob>>>,
+    /// Crawler settings, for requests that fetch pages or sitemaps.
+    pub crawler_config: CrawlerConfig,
     /// Optional crawler handle. None if crawler is disabled.
     pub crawler: Arc<RwLock<Option<CrawlerHandle>>>,
 }
@@ -0,0 +1,35 @@
+use notice_search::suggest::SuggestIndex;
+use sqlx::PgPool;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// A query must come from this many logged-in users to be suggested to others.
+const MIN_SEARCHERS: i64 = 2;
+const MAX_QUERIES: i64 = 20_000;
+const MAX_TITLES: i64 = 20_000;
+
+/// Build the suggestion index from popular searches and document titles.
+pub async fn build_index(pool: &PgPool) -> Result<SuggestIndex, notice_core::Error> {
+    let queries = notice_db::search_history::popular(pool, MIN_SEARCHERS, MAX_QUERIES).await?;
+    let titles = notice_db::documents::list_titles(pool, MAX_TITLES).await?;
+    Ok(SuggestIndex::build(queries, titles))
+}
+
+/// Rebuild the index now and then every `interval`.
+pub fn spawn_rebuild(pool: PgPool, index: Arc<RwLock<SuggestIndex>>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match build_index(&pool).await {
+                Ok(fresh) => {
+                    tracing::info!("Suggestion index rebuilt: {} entries", fresh.len());
+                    if let Ok(mut guard) = index.write() {
+                        *guard = fresh;
+                    }
+                }
+                Err(e) => tracing::warn!("Suggestion index rebuild failed: {}", e),
+            }
+        }
+    });
+}
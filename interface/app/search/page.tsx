@@ -58,6 +58,12 @@ function SearchContent() {
                 // Step A: Fast Search (Web Results Only)
                 const res = await api.search(query, { limit: 20 });
 
+                // Navigational query: leave for the target site
+                if (res.redirect) {
+                    window.location.assign(res.redirect);
+                    return;
+                }
+
                 setResult({
                     type: "universal",
                     title: query,
@@ -1,4 +1,5 @@
 import type { InstantAnswer as InstantAnswerType } from "@/lib/api";
+import { TimerBlock } from "@/components/blocks/timer_block";
 
 interface Props {
     answer: InstantAnswerType;
@@ -6,27 +7,127 @@ interface Props {
 
 const typeLabels: Record<string, { icon: string; label: string }> = {
     calculation: { icon: "🔢", label: "Calculator" },
+    conversion: { icon: "🔄", label: "Converter" },
     definition: { icon: "📖", label: "Definition" },
     timer: { icon: "⏱️", label: "Timer" },
 };
 
-export default function InstantAnswer({ answer }: Props) {
-    const meta = typeLabels[answer.answer_type] || {
+function Header({ widget }: { widget: string }) {
+    const meta = typeLabels[widget] || {
         icon: "⚡",
         label: "Instant Answer",
     };
 
     return (
-        <div className="instant-answer mb-6">
-            <div className="mb-2 flex items-center gap-2">
-                <span className="text-lg">{meta.icon}</span>
-                <span className="text-xs font-medium tracking-wider uppercase">
-                    {meta.label}
-                </span>
-            </div>
-            <div className="text-2xl font-semibold text-white">
-                {answer.value}
-            </div>
+        <div className="mb-2 flex items-center gap-2">
+            <span className="text-lg">{meta.icon}</span>
+            <span className="text-xs font-medium tracking-wider uppercase">
+                {meta.label}
+            </span>
         </div>
     );
 }
+
+export default function InstantAnswer({ answer }: Props) {
+    switch (answer.type) {
+        case "calculation":
+            return (
+                <div className="instant-answer mb-6">
+                    <Header widget={answer.type} />
+                    <div className="text-sm text-neutral-400">
+                        {answer.expression} =
+                    </div>
+                    <div className="text-2xl font-semibold text-white">
+                        {answer.formatted}
+                    </div>
+                </div>
+            );
+
+        case "conversion":
+            return (
+                <div className="instant-answer mb-6">
+                    <Header widget={answer.type} />
+                    <div className="text-sm text-neutral-400">
+                        {answer.amount} {answer.from.symbol} =
+                    </div>
+                    <div className="text-2xl font-semibold text-white">
+                        {answer.formatted} {answer.to.symbol}
+                    </div>
+                    {answer.rate && (
+                        <div className="mt-1 text-xs text-neutral-500">
+                            1 {answer.from.symbol} = {answer.rate.rate.toFixed(4)}{" "}
+                            {answer.to.symbol} · updated{" "}
+                            {new Date(answer.rate.updated_at).toLocaleDateString()}
+                        </div>
+                    )}
+                </div>
+            );
+
+        case "definition":
+            return (
+                <div className="instant-answer mb-6">
+                    <Header widget={answer.type} />
+                    <div className="text-2xl font-semibold text-white">
+                        {answer.term}
+                    </div>
+                    {answer.entries.map((entry, i) => (
+                        <div key={i} className="mt-3">
+                            <div className="text-sm text-neutral-400 italic">
+                                {entry.part_of_speech}
+                                {entry.pronunciation && ` · ${entry.pronunciation}`}
+                            </div>
+                            <ol className="mt-1 list-decimal pl-5 text-neutral-200">
+                                {entry.senses.map((sense, j) => (
+                                    <li key={j}>
+                                        {sense.gloss}
+                                        {sense.examples?.map((example, k) => (
+                                            <div key={k} className="text-sm text-neutral-500">
+                                                “{example}”
+                                            </div>
+                                        ))}
+                                    </li>
+                                ))}
+                            </ol>
+                            {entry.synonyms.length > 0 && (
+                                <div className="mt-1 text-sm text-neutral-400">
+                                    Synonyms: {entry.synonyms.join(", ")}
+                                </div>
+                            )}
+                        </div>
+                    ))}
+                    {answer.source === "generated" && (
+                        <div className="mt-2 text-xs text-neutral-500">
+                            Generated from indexed pages
+                        </div>
+                    )}
+                </div>
+            );
+
+        case "timer": {
+            // Count down to the server-resolved deadline, not the original duration,
+            // so a slow response doesn't add drift
+            const target = Date.parse(answer.target);
+            const seconds = Number.isNaN(target)
+                ? answer.seconds
+                : Math.max(1, Math.round((target - Date.now()) / 1000));
+
+            return (
+                <TimerBlock
+                    data={{ seconds, query: answer.label ?? answer.display }}
+                />
+            );
+        }
+
+        case "error":
+            return (
+                <div className="instant-answer mb-6">
+                    <Header widget={answer.widget} />
+                    <div className="text-lg text-neutral-300">{answer.message}</div>
+                </div>
+            );
+
+        default:
+            // Unknown widget type from a newer server
+            return null;
+    }
+}
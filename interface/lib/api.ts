@@ -8,18 +8,111 @@ export interface SearchResult {
     score: number | null;
 }
 
-export interface InstantAnswer {
-    answer_type: string;
-    value: string;
+export interface UnitRef {
+    symbol: string;
+    name: string;
+}
+
+export interface DefinitionEntry {
+    part_of_speech: string;
+    pronunciation?: string;
+    senses: { gloss: string; examples?: string[] }[];
+    synonyms: string[];
 }
 
+/// Discriminated on `type`. Unknown types should be ignored so the server
+/// can add widgets without breaking older clients.
+export type InstantAnswer =
+    | {
+          type: "calculation";
+          expression: string;
+          result: number;
+          formatted: string;
+          exact: boolean;
+      }
+    | {
+          type: "conversion";
+          dimension: string;
+          amount: number;
+          from: UnitRef;
+          to: UnitRef;
+          result: number;
+          formatted: string;
+          rate?: { rate: number; updated_at: string };
+      }
+    | {
+          type: "definition";
+          term: string;
+          source: string;
+          entries: DefinitionEntry[];
+      }
+    | {
+          type: "timer";
+          kind: "duration" | "until";
+          seconds: number;
+          target: string;
+          label?: string;
+          display: string;
+      }
+    | {
+          type: "error";
+          widget: string;
+          message: string;
+          input?: string;
+          position?: number;
+      };
+
 export interface SearchResponse {
     query: string;
     results: SearchResult[];
-    total: number;
+    // null for semantic search, which has no count
+    total: number | null;
     instant_answer: InstantAnswer | null;
     ai_answer: string | null;
     discovery_triggered: boolean;
+    // Set for bangs ("!gh tokio") and bare domains: go straight there
+    redirect: string | null;
+    // How the query was interpreted (site:, -term, "phrase", status:, after:)
+    parsed_query: ParsedQuery | null;
+    // Result counts per domain/status, for "narrow to this site" chips
+    facets: SearchFacets | null;
+    // Original query found nothing; results are for this correction instead
+    corrected_query: string | null;
+    // Spelling correction to offer ("Did you mean ...?")
+    did_you_mean: string | null;
+    // Search backend is down: results come from the PostgreSQL fallback
+    degraded: boolean;
+}
+
+export interface FacetCount {
+    value: string;
+    count: number;
+}
+
+export interface SearchFacets {
+    domains: FacetCount[];
+    statuses: FacetCount[];
+}
+
+export interface ParsedQuery {
+    terms: string[];
+    phrases?: string[];
+    excluded?: string[];
+    sites?: string[];
+    excluded_sites?: string[];
+    status?: string;
+    after?: string;
+    before?: string;
+}
+
+export interface QuerySuggestion {
+    text: string;
+    source: "history" | "popular" | "title";
+}
+
+export interface SuggestResponse {
+    query: string;
+    suggestions: QuerySuggestion[];
 }
 
 export interface SummaryResponse {
@@ -64,7 +157,7 @@ export interface HealthResponse {
     version: string;
     dependencies: {
         postgres: string;
-        meilisearch: { status: string; documents: number };
+        search: { backend: string; status: string; documents: number };
         gemini: string;
     };
 }
@@ -151,12 +244,31 @@ class ApiClient {
 
     async search(
         query: string,
-        options?: { limit?: number; offset?: number; sessionId?: string },
+        options?: {
+            limit?: number;
+            offset?: number;
+            sessionId?: string;
+            domain?: string;
+            status?: string;
+            after?: string;
+            before?: string;
+            sort?: "relevance" | "recent";
+            // Defaults to hybrid when the server has embeddings enabled
+            mode?: "hybrid" | "semantic" | "keyword";
+        },
     ): Promise<SearchResponse> {
         const params = new URLSearchParams({ q: query });
         if (options?.limit) params.set("limit", options.limit.toString());
         if (options?.offset) params.set("offset", options.offset.toString());
         if (options?.sessionId) params.set("session_id", options.sessionId);
+        if (options?.domain) params.set("domain", options.domain);
+        if (options?.status) params.set("status", options.status);
+        if (options?.after) params.set("after", options.after);
+        if (options?.before) params.set("before", options.before);
+        if (options?.sort) params.set("sort", options.sort);
+        if (options?.mode) params.set("mode", options.mode);
+        // Minutes east of UTC, for "countdown to 17:00"-style timers
+        params.set("tz_offset", (-new Date().getTimezoneOffset()).toString());
 
         const res = await fetch(
             `${this.baseUrl}/api/search?${params.toString()}`,
@@ -225,6 +337,23 @@ class ApiClient {
         }
     }
 
+    // Cheap enough to call on every keystroke; failures just mean no suggestions
+    async suggest(prefix: string, limit?: number): Promise<QuerySuggestion[]> {
+        const params = new URLSearchParams({ q: prefix });
+        if (limit) params.set("limit", limit.toString());
+
+        try {
+            const res = await fetch(`${this.baseUrl}/api/suggest?${params.toString()}`, {
+                headers: this.headers(),
+            });
+            if (!res.ok) return [];
+            const data: SuggestResponse = await res.json();
+            return data.suggestions;
+        } catch {
+            return [];
+        }
+    }
+
     // ── Content ──
 
     async submitUrl(url: string): Promise<SubmitUrlResponse> {
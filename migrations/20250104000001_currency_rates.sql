@@ -0,0 +1,9 @@
+-- Exchange rates for the Convert intent, stored as units of currency per 1 USD
+CREATE TABLE currency_rates (
+    code        TEXT PRIMARY KEY,
+    per_usd     DOUBLE PRECISION NOT NULL CHECK (per_usd > 0),
+    source      TEXT,
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO currency_rates (code, per_usd, source) VALUES ('USD', 1.0, 'builtin');
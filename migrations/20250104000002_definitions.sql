@@ -0,0 +1,17 @@
+-- Offline dictionary for the Define intent.
+-- One row per (term, part of speech, source); senses are stored as JSONB:
+-- [{"gloss": "...", "examples": ["..."]}]
+CREATE TABLE definitions (
+    id              BIGSERIAL PRIMARY KEY,
+    term            TEXT NOT NULL,
+    term_normalized TEXT NOT NULL,
+    part_of_speech  TEXT NOT NULL,
+    pronunciation   TEXT,
+    senses          JSONB NOT NULL DEFAULT '[]',
+    synonyms        TEXT[] NOT NULL DEFAULT '{}',
+    source          TEXT NOT NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (term_normalized, part_of_speech, source)
+);
+
+CREATE INDEX idx_definitions_term ON definitions (term_normalized);
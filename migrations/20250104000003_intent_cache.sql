@@ -0,0 +1,8 @@
+-- Cached LLM intent classifications for low-confidence queries,
+-- keyed by the normalized query text
+CREATE TABLE intent_cache (
+    query      TEXT PRIMARY KEY,
+    intent     TEXT NOT NULL,
+    argument   TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
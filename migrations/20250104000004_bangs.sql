@@ -0,0 +1,14 @@
+-- Bang shortcuts ("!gh tokio") on top of the built-in defaults.
+-- Rows without a user_id apply to everyone; a user's own row for the
+-- same trigger takes precedence.
+CREATE TABLE bangs (
+    id           BIGSERIAL PRIMARY KEY,
+    user_id      UUID REFERENCES users (id) ON DELETE CASCADE,
+    trigger      TEXT NOT NULL,
+    name         TEXT NOT NULL,
+    url_template TEXT NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_bangs_global_trigger ON bangs (trigger) WHERE user_id IS NULL;
+CREATE UNIQUE INDEX idx_bangs_user_trigger ON bangs (user_id, trigger) WHERE user_id IS NOT NULL;
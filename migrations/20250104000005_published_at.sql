@@ -0,0 +1,4 @@
+-- Publication date extracted from page metadata, when the page has one
+ALTER TABLE documents ADD COLUMN published_at TIMESTAMPTZ;
+
+CREATE INDEX idx_documents_published_at ON documents (published_at DESC);
@@ -0,0 +1,3 @@
+-- Prefix lookups for per-user query suggestions (GET /api/suggest)
+CREATE INDEX idx_search_history_user_query
+    ON search_history (user_id, lower(query) text_pattern_ops);
@@ -0,0 +1,18 @@
+-- ─────────────────────────────────────────────
+-- Document embeddings (pgvector)
+-- ─────────────────────────────────────────────
+-- One vector per document for semantic search.
+-- `model` records which embedder produced it;
+-- searches only compare vectors of the same model.
+
+CREATE EXTENSION IF NOT EXISTS vector;
+
+CREATE TABLE document_embeddings (
+    document_id UUID PRIMARY KEY REFERENCES documents (id) ON DELETE CASCADE,
+    model       TEXT NOT NULL,
+    embedding   vector(768) NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_document_embeddings_hnsw
+    ON document_embeddings USING hnsw (embedding vector_cosine_ops);
@@ -0,0 +1,26 @@
+-- ─────────────────────────────────────────────
+-- Document chunks (passages)
+-- ─────────────────────────────────────────────
+-- Heading-aware passages of documents.raw_content,
+-- with byte offsets into it. Passages are what
+-- snippets, RAG context and vectors come from.
+
+CREATE TABLE document_chunks (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    document_id     UUID NOT NULL REFERENCES documents (id) ON DELETE CASCADE,
+    chunk_index     INTEGER NOT NULL,
+    heading         TEXT,
+    content         TEXT NOT NULL,
+    start_offset    INTEGER NOT NULL,
+    end_offset      INTEGER NOT NULL,
+    embedding_model TEXT,
+    embedding       vector(768),
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (document_id, chunk_index)
+);
+
+CREATE INDEX idx_document_chunks_embedding
+    ON document_chunks USING hnsw (embedding vector_cosine_ops);
+
+-- Passage vectors replace the whole-document ones
+DROP TABLE document_embeddings;
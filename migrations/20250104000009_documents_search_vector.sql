@@ -0,0 +1,11 @@
+-- Full-text search over documents, used when the search backend is down.
+-- Title outweighs summary outweighs content; content is capped so the
+-- vector stays under tsvector's 1MB limit.
+ALTER TABLE documents ADD COLUMN search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+        setweight(to_tsvector('english', coalesce(summary, '')), 'B') ||
+        setweight(to_tsvector('english', left(raw_content, 100000)), 'C')
+    ) STORED;
+
+CREATE INDEX idx_documents_search_vector ON documents USING GIN (search_vector);
@@ -0,0 +1,25 @@
+-- ─────────────────────────────────────────────
+-- Index outbox
+-- ─────────────────────────────────────────────
+-- One row per document change, written in the
+-- same transaction as the change. The dispatcher
+-- pushes the document's current state to the
+-- search backend and deletes the row; failures
+-- are retried with backoff and kept once they
+-- run out of attempts.
+
+CREATE TABLE index_outbox (
+    id              BIGSERIAL PRIMARY KEY,
+    -- No foreign key: deletes must outlive the document
+    document_id     UUID NOT NULL,
+    op              TEXT NOT NULL CHECK (op IN ('upsert', 'delete')),
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    last_error      TEXT,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    failed_at       TIMESTAMPTZ,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_index_outbox_pending
+    ON index_outbox (next_attempt_at)
+    WHERE failed_at IS NULL;
@@ -0,0 +1,15 @@
+-- ─────────────────────────────────────────────
+-- Index sync watermarks
+-- ─────────────────────────────────────────────
+-- How far the incremental resync has pushed
+-- documents into each search backend, as the
+-- (updated_at, id) of the last one pushed.
+
+CREATE TABLE index_sync_state (
+    backend         TEXT PRIMARY KEY,
+    updated_at_mark TIMESTAMPTZ NOT NULL,
+    id_mark         UUID NOT NULL,
+    synced_at       TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_documents_updated_at_id ON documents (updated_at, id);
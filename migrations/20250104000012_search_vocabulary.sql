@@ -0,0 +1,79 @@
+-- ─────────────────────────────────────────────
+-- Search vocabulary
+-- ─────────────────────────────────────────────
+-- Synonyms and stop words pushed to the search
+-- backend, editable at runtime. A group's terms
+-- all find each other; a one-way group only
+-- expands its first term to the rest ("ml"
+-- finds "machine learning", not the reverse).
+
+CREATE TABLE synonym_groups (
+    id         BIGSERIAL PRIMARY KEY,
+    terms      TEXT[] NOT NULL CHECK (cardinality(terms) >= 2),
+    one_way    BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE stop_words (
+    word       TEXT PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Previously hard-coded in the Meilisearch backend
+INSERT INTO synonym_groups (terms, one_way) VALUES
+    -- Programming languages
+    (ARRAY['js', 'javascript'], FALSE),
+    (ARRAY['ts', 'typescript'], FALSE),
+    (ARRAY['py', 'python'], FALSE),
+    (ARRAY['rb', 'ruby'], FALSE),
+    (ARRAY['cpp', 'c++'], FALSE),
+    (ARRAY['golang', 'go'], FALSE),
+    -- Databases
+    (ARRAY['pg', 'postgresql', 'postgres'], FALSE),
+    (ARRAY['mongo', 'mongodb'], FALSE),
+    -- Technologies
+    (ARRAY['k8s', 'kubernetes'], FALSE),
+    (ARRAY['wasm', 'webassembly'], FALSE),
+    (ARRAY['ml', 'machine learning'], TRUE),
+    (ARRAY['ai', 'artificial intelligence'], TRUE),
+    (ARRAY['os', 'operating system'], TRUE),
+    (ARRAY['db', 'database'], TRUE),
+    (ARRAY['api', 'application programming interface'], TRUE),
+    (ARRAY['cli', 'command line interface'], TRUE),
+    (ARRAY['gui', 'graphical user interface'], TRUE),
+    (ARRAY['tui', 'terminal user interface'], TRUE),
+    (ARRAY['ui', 'user interface'], TRUE),
+    (ARRAY['ux', 'user experience'], TRUE),
+    (ARRAY['oop', 'object oriented programming'], TRUE),
+    (ARRAY['fp', 'functional programming'], TRUE),
+    -- Common abbreviations
+    (ARRAY['docs', 'documentation'], TRUE),
+    (ARRAY['doc', 'documentation'], TRUE),
+    (ARRAY['config', 'configuration'], TRUE),
+    (ARRAY['auth', 'authentication'], TRUE),
+    (ARRAY['env', 'environment'], TRUE),
+    (ARRAY['repo', 'repository'], TRUE),
+    (ARRAY['lib', 'library'], TRUE),
+    (ARRAY['pkg', 'package'], TRUE),
+    (ARRAY['deps', 'dependencies'], TRUE),
+    (ARRAY['dev', 'development'], TRUE),
+    (ARRAY['prod', 'production'], TRUE),
+    (ARRAY['impl', 'implementation'], TRUE),
+    (ARRAY['fn', 'function'], TRUE),
+    (ARRAY['func', 'function'], TRUE),
+    (ARRAY['var', 'variable'], TRUE),
+    (ARRAY['arg', 'argument'], TRUE),
+    (ARRAY['param', 'parameter'], TRUE),
+    (ARRAY['err', 'error'], TRUE),
+    (ARRAY['msg', 'message'], TRUE),
+    (ARRAY['async', 'asynchronous'], TRUE),
+    (ARRAY['sync', 'synchronous'], TRUE);
+
+INSERT INTO stop_words (word)
+SELECT UNNEST(ARRAY[
+    'a', 'an', 'the', 'is', 'of', 'and', 'or', 'in', 'on', 'at', 'to', 'for',
+    'with', 'by', 'about', 'what', 'how', 'why', 'where', 'when', 'which',
+    'who', 'whom', 'this', 'that', 'these', 'those', 'it', 'they', 'them',
+    'he', 'she', 'his', 'her', 'i', 'you', 'we', 'me', 'us', 'my', 'your', 'our'
+]);
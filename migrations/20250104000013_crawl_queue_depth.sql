@@ -0,0 +1,14 @@
+-- ─────────────────────────────────────────────
+-- Crawl depth
+-- ─────────────────────────────────────────────
+-- Where each queued URL sits in the crawl that
+-- found it: submitted and sitemap URLs are seeds
+-- (depth 0, no seed_id); discovered links point
+-- at the page they were found on and its seed.
+
+ALTER TABLE crawl_queue
+    ADD COLUMN depth      INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN parent_url TEXT,
+    ADD COLUMN seed_id    UUID REFERENCES crawl_queue (id) ON DELETE SET NULL;
+
+CREATE INDEX idx_crawl_queue_seed ON crawl_queue (seed_id) WHERE seed_id IS NOT NULL;
@@ -0,0 +1,35 @@
+-- ─────────────────────────────────────────────
+-- Recrawl schedule
+-- ─────────────────────────────────────────────
+-- When each document is fetched again, and what
+-- the last fetch saw. Kept out of `documents` so
+-- a recrawl that finds nothing new doesn't touch
+-- the document's updated_at (the resync
+-- watermark). The interval shrinks when a page
+-- changes and grows when it doesn't.
+
+CREATE TABLE recrawl_state (
+    document_id     UUID PRIMARY KEY REFERENCES documents (id) ON DELETE CASCADE,
+    -- SHA-256 (hex) of the extracted text
+    content_hash    TEXT NOT NULL,
+    -- Validators for conditional requests
+    etag            TEXT,
+    last_modified   TEXT,
+    interval_secs   INTEGER NOT NULL DEFAULT 86400,
+    next_crawl_at   TIMESTAMPTZ NOT NULL DEFAULT NOW() + INTERVAL '1 day',
+    last_crawled_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error      TEXT
+);
+
+CREATE INDEX idx_recrawl_state_next_crawl_at ON recrawl_state (next_crawl_at);
+
+INSERT INTO recrawl_state (document_id, content_hash, last_crawled_at, last_changed_at, next_crawl_at)
+SELECT
+    id,
+    encode(sha256(convert_to(raw_content, 'UTF8')), 'hex'),
+    created_at,
+    updated_at,
+    -- Spread the backlog over a day instead of refetching everything at once
+    NOW() + random() * INTERVAL '1 day'
+FROM documents;
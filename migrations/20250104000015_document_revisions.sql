@@ -0,0 +1,20 @@
+-- ─────────────────────────────────────────────
+-- Document revisions
+-- ─────────────────────────────────────────────
+-- Earlier versions of a document, saved when a
+-- recrawl replaces its content, so changes can
+-- be compared between visits.
+
+CREATE TABLE document_revisions (
+    id           BIGSERIAL PRIMARY KEY,
+    document_id  UUID NOT NULL REFERENCES documents (id) ON DELETE CASCADE,
+    content_hash TEXT NOT NULL,
+    title        TEXT,
+    summary      TEXT,
+    raw_content  TEXT NOT NULL,
+    -- When this version was first seen, and when a recrawl replaced it
+    fetched_at   TIMESTAMPTZ NOT NULL,
+    replaced_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_document_revisions_document ON document_revisions (document_id, id DESC);
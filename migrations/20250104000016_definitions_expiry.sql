@@ -0,0 +1,8 @@
+-- AI-generated definitions are cached, not kept: lookups skip them once
+-- expires_at has passed, and the next miss regenerates them.
+ALTER TABLE definitions ADD COLUMN expires_at TIMESTAMPTZ;
+
+-- Generated rows saved before expiry existed
+UPDATE definitions
+SET expires_at = NOW()
+WHERE source = 'generated';